@@ -0,0 +1,299 @@
+//! `continuum` - operate and inspect the Continuum CP-Swap program from the
+//! command line against any Solana RPC endpoint.
+
+mod render;
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::instruction::Instruction;
+use anyhow::{bail, Context, Result};
+use clap::{Parser, Subcommand};
+use continuum_client::rpc::RpcClient;
+use continuum_client::state::{
+    AdminConfig, CpSwapPoolRegistry, FifoState, OrderState, OrderStatus,
+};
+use continuum_client::{instructions, pda, ClientError};
+use serde_json::{json, Value};
+use solana_keypair::{read_keypair_file, Keypair};
+use solana_signer::Signer;
+use solana_transaction::Transaction;
+use std::path::PathBuf;
+
+#[derive(Parser)]
+#[command(name = "continuum", version, about)]
+struct Cli {
+    /// RPC URL or moniker: localhost, devnet or mainnet-beta
+    #[arg(short, long, env = "CONTINUUM_RPC_URL", default_value = "localhost")]
+    url: String,
+
+    /// Keypair that signs and pays for transactions
+    #[arg(short, long, env = "CONTINUUM_KEYPAIR")]
+    keypair: Option<PathBuf>,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Create the FIFO state and admin config, skipping whichever already exists
+    Init,
+    /// Register a CP-Swap pool whose custom authority is already Continuum's
+    RegisterPool { pool_state: Pubkey },
+    /// Set the emergency pause
+    Pause,
+    /// Clear the emergency pause
+    Unpause,
+    /// Show the FIFO state and admin roles
+    Status {
+        #[arg(long)]
+        json: bool,
+    },
+    /// List pending orders in queue order
+    Queue {
+        /// Only show orders whose route starts at this pool
+        #[arg(long)]
+        pool: Option<Pubkey>,
+        #[arg(long)]
+        json: bool,
+    },
+    /// Show a decoded order account
+    Order { address: Pubkey },
+    /// Show a decoded pool registry
+    Pool { pool_id: Pubkey },
+    /// List registered pools
+    Pools {
+        #[arg(long)]
+        json: bool,
+    },
+    /// Export the FIFO state, pools and pending orders as JSON
+    Export {
+        /// Write to this file instead of stdout
+        #[arg(short, long)]
+        out: Option<PathBuf>,
+    },
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let rpc = RpcClient::new(resolve_url(&cli.url));
+
+    match cli.command {
+        Command::Init => {
+            let payer = load_keypair(cli.keypair)?;
+            let mut ixs = vec![];
+            if rpc.get_account_data(&pda::fifo_state().0)?.is_none() {
+                ixs.push(instructions::initialize(&payer.pubkey()));
+            }
+            if rpc.get_account_data(&pda::admin_config().0)?.is_none() {
+                ixs.push(instructions::initialize_admin_config(&payer.pubkey()));
+            }
+            if ixs.is_empty() {
+                println!("Already initialized");
+                return Ok(());
+            }
+            send(&rpc, &payer, ixs)?;
+        }
+        Command::RegisterPool { pool_state } => {
+            let payer = load_keypair(cli.keypair)?;
+            send(
+                &rpc,
+                &payer,
+                vec![instructions::register_existing_pool(
+                    &payer.pubkey(),
+                    &payer.pubkey(),
+                    &pool_state,
+                )],
+            )?;
+            println!("Registry: {}", pda::pool_registry(&pool_state).0);
+        }
+        Command::Pause | Command::Unpause => {
+            let payer = load_keypair(cli.keypair)?;
+            let paused = matches!(cli.command, Command::Pause);
+            send(
+                &rpc,
+                &payer,
+                vec![instructions::set_emergency_pause(&payer.pubkey(), paused)],
+            )?;
+        }
+        Command::Status { json } => {
+            let status = status(&rpc)?;
+            if json {
+                print_json(&status)?;
+            } else {
+                let fifo = &status["fifo_state"];
+                println!("Current sequence: {}", fifo["current_sequence"]);
+                println!("Emergency pause:  {}", fifo["emergency_pause"]);
+                println!(
+                    "Admin:            {}",
+                    fifo["admin"].as_str().unwrap_or_default()
+                );
+                if let Some(config) = status["admin_config"].as_object() {
+                    for role in [
+                        "super_admin",
+                        "pending_super_admin",
+                        "pauser",
+                        "pool_manager",
+                        "fee_manager",
+                        "relayer_manager",
+                    ] {
+                        println!("{:<20}{}", role, config[role].as_str().unwrap_or_default());
+                    }
+                    println!("{:<20}{}s", "timelock_delay", config["timelock_delay"]);
+                }
+            }
+        }
+        Command::Queue { pool, json } => {
+            let orders = pending_orders(&rpc, pool)?;
+            if json {
+                print_json(&Value::Array(
+                    orders
+                        .iter()
+                        .map(|(address, order)| render::order(address, order))
+                        .collect(),
+                ))?;
+            } else {
+                println!(
+                    "{:>8}  {:<44}  {:<44}  {:>20}  {:>20}",
+                    "SEQUENCE", "USER", "POOL", "AMOUNT_IN", "MIN_OUT"
+                );
+                for (_, order) in &orders {
+                    println!(
+                        "{:>8}  {:<44}  {:<44}  {:>20}  {:>20}",
+                        order.sequence,
+                        order.user.to_string(),
+                        order.pool_id.to_string(),
+                        order.amount_in,
+                        order.min_amount_out
+                    );
+                }
+                println!("{} pending order(s)", orders.len());
+            }
+        }
+        Command::Order { address } => {
+            let order: OrderState = rpc.fetch(&address)?;
+            print_json(&render::order(&address, &order))?;
+        }
+        Command::Pool { pool_id } => {
+            let address = pda::pool_registry(&pool_id).0;
+            let registry: CpSwapPoolRegistry = rpc.fetch(&address)?;
+            print_json(&render::pool_registry(&address, &registry))?;
+        }
+        Command::Pools { json } => {
+            let pools = rpc.fetch_all::<CpSwapPoolRegistry>()?;
+            if json {
+                print_json(&Value::Array(
+                    pools
+                        .iter()
+                        .map(|(address, registry)| render::pool_registry(address, registry))
+                        .collect(),
+                ))?;
+            } else {
+                for (_, registry) in &pools {
+                    println!("{}  {:?}", registry.pool_id, registry.status());
+                }
+            }
+            let legacy = rpc.fetch_legacy_addresses::<CpSwapPoolRegistry>()?;
+            if !legacy.is_empty() {
+                eprintln!("{} registry account(s) still need migration", legacy.len());
+            }
+        }
+        Command::Export { out } => {
+            let mut export = status(&rpc)?;
+            export["pools"] = Value::Array(
+                rpc.fetch_all::<CpSwapPoolRegistry>()?
+                    .iter()
+                    .map(|(address, registry)| render::pool_registry(address, registry))
+                    .collect(),
+            );
+            export["pending_orders"] = Value::Array(
+                pending_orders(&rpc, None)?
+                    .iter()
+                    .map(|(address, order)| render::order(address, order))
+                    .collect(),
+            );
+
+            let rendered = serde_json::to_string_pretty(&export)?;
+            match out {
+                Some(path) => std::fs::write(&path, rendered)
+                    .with_context(|| format!("writing {}", path.display()))?,
+                None => println!("{}", rendered),
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn resolve_url(url: &str) -> String {
+    match url {
+        "localhost" | "l" => "http://127.0.0.1:8899",
+        "devnet" | "d" => "https://api.devnet.solana.com",
+        "mainnet-beta" | "m" => "https://api.mainnet-beta.solana.com",
+        url => url,
+    }
+    .to_string()
+}
+
+fn load_keypair(path: Option<PathBuf>) -> Result<Keypair> {
+    let path = match path {
+        Some(path) => path,
+        None => {
+            let home = std::env::var("HOME").context("HOME is not set, pass --keypair")?;
+            PathBuf::from(home).join(".config/solana/id.json")
+        }
+    };
+    read_keypair_file(&path).map_err(|e| anyhow::anyhow!("reading {}: {}", path.display(), e))
+}
+
+fn send(rpc: &RpcClient, payer: &Keypair, ixs: Vec<Instruction>) -> Result<()> {
+    let blockhash = rpc.get_latest_blockhash()?;
+    let tx = Transaction::new_signed_with_payer(&ixs, Some(&payer.pubkey()), &[payer], blockhash);
+    let wire_transaction = bincode::serialize(&tx)?;
+
+    match rpc.send_and_confirm_transaction(&wire_transaction) {
+        Ok(signature) => {
+            println!("Signature: {}", signature);
+            Ok(())
+        }
+        Err(ClientError::Program(error)) => bail!("program error {:?}: {}", error, error),
+        Err(error) => Err(error.into()),
+    }
+}
+
+fn status(rpc: &RpcClient) -> Result<Value> {
+    let fifo_address = pda::fifo_state().0;
+    let fifo: FifoState = rpc
+        .fetch(&fifo_address)
+        .context("fetching FIFO state, is the program initialized?")?;
+
+    let config_address = pda::admin_config().0;
+    let admin_config = match rpc.fetch::<AdminConfig>(&config_address) {
+        Ok(config) => render::admin_config(&config_address, &config),
+        Err(ClientError::AccountNotFound(_)) => Value::Null,
+        Err(error) => return Err(error.into()),
+    };
+
+    Ok(json!({
+        "rpc_url": rpc.url(),
+        "program_id": continuum_client::ID.to_string(),
+        "fifo_state": render::fifo_state(&fifo_address, &fifo),
+        "admin_config": admin_config,
+    }))
+}
+
+/// Pending orders sorted by sequence, i.e. in execution order
+fn pending_orders(rpc: &RpcClient, pool: Option<Pubkey>) -> Result<Vec<(Pubkey, OrderState)>> {
+    let mut orders: Vec<_> = rpc
+        .fetch_all::<OrderState>()?
+        .into_iter()
+        .filter(|(_, order)| order.status == OrderStatus::Pending)
+        .filter(|(_, order)| pool.is_none_or(|pool| order.pool_id == pool))
+        .collect();
+    orders.sort_by_key(|(_, order)| order.sequence);
+    Ok(orders)
+}
+
+fn print_json(value: &Value) -> Result<()> {
+    println!("{}", serde_json::to_string_pretty(value)?);
+    Ok(())
+}
@@ -0,0 +1,90 @@
+//! JSON views of decoded program accounts.
+
+use anchor_lang::prelude::Pubkey;
+use continuum_client::state::{AdminConfig, CpSwapPoolRegistry, FifoState, OrderState};
+use serde_json::{json, Value};
+
+pub fn fifo_state(address: &Pubkey, fifo: &FifoState) -> Value {
+    json!({
+        "address": address.to_string(),
+        "current_sequence": fifo.current_sequence,
+        "admin": fifo.admin.to_string(),
+        "emergency_pause": fifo.emergency_pause,
+        "relayer_count": fifo.relayer_count,
+        "version": fifo.version.get(),
+    })
+}
+
+pub fn admin_config(address: &Pubkey, config: &AdminConfig) -> Value {
+    json!({
+        "address": address.to_string(),
+        "super_admin": config.super_admin.to_string(),
+        "pending_super_admin": config.pending_super_admin.to_string(),
+        "pauser": config.pauser.to_string(),
+        "pool_manager": config.pool_manager.to_string(),
+        "fee_manager": config.fee_manager.to_string(),
+        "relayer_manager": config.relayer_manager.to_string(),
+        "timelock_delay": config.timelock_delay,
+        "change_count": config.change_count,
+    })
+}
+
+pub fn pool_registry(address: &Pubkey, registry: &CpSwapPoolRegistry) -> Value {
+    json!({
+        "address": address.to_string(),
+        "pool_id": registry.pool_id.to_string(),
+        "status": format!("{:?}", registry.status()),
+        "token_0": registry.token_0.to_string(),
+        "token_1": registry.token_1.to_string(),
+        "token_0_vault": registry.token_0_vault.to_string(),
+        "token_1_vault": registry.token_1_vault.to_string(),
+        "continuum_authority": registry.continuum_authority.to_string(),
+        "created_at": registry.created_at,
+        "max_pending_per_user": registry.max_pending_per_user,
+        "order_ttl": registry.order_ttl,
+        "order_bond": registry.order_bond,
+        "min_resting_period": registry.min_resting_period,
+        "bond_slash_bps": registry.bond_slash_bps,
+        "bond_recipient": registry.bond_recipient.to_string(),
+        "min_inclusion_delay": registry.min_inclusion_delay,
+        "reveal_window": registry.reveal_window,
+        "min_relayer_stake": registry.min_relayer_stake,
+        "relayer_slash_bps": registry.relayer_slash_bps,
+        "self_execution_timeout": registry.self_execution_timeout,
+        "has_order_ring": registry.has_order_ring,
+        "queue": {
+            "head_sequence": registry.queue.head_sequence,
+            "tail_sequence": registry.queue.tail_sequence,
+            "pending_count": registry.queue.pending_count,
+            "oldest_pending_at": registry.queue.oldest_pending_at,
+            "head_since": registry.head_since,
+        },
+        "version": registry.version.get(),
+    })
+}
+
+pub fn order(address: &Pubkey, order: &OrderState) -> Value {
+    json!({
+        "address": address.to_string(),
+        "sequence": order.sequence,
+        "user": order.user.to_string(),
+        "route": order.route().iter().map(Pubkey::to_string).collect::<Vec<_>>(),
+        "amount_in": order.amount_in,
+        "min_amount_out": order.min_amount_out,
+        "is_base_input": order.is_base_input,
+        "fill_policy": format!("{:?}", order.fill_policy),
+        "filled_amount_in": order.filled_amount_in,
+        "filled_amount_out": order.filled_amount_out,
+        "source": (order.source != Pubkey::default()).then(|| order.source.to_string()),
+        "status": format!("{:?}", order.status),
+        "submitted_at": order.submitted_at,
+        "submitted_slot": order.submitted_slot,
+        "executed_at": order.executed_at,
+        "executed_by": order.executed_by,
+        "bond_lamports": order.bond_lamports,
+        "bond_payer": order.bond_refund_recipient().to_string(),
+        "queue_prev": order.queue_prev,
+        "queue_next": order.queue_next,
+        "version": order.version.get(),
+    })
+}
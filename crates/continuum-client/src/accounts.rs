@@ -0,0 +1,74 @@
+//! Typed decoding of program accounts.
+
+use crate::error::{ClientError, Result};
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::{AccountDeserialize, Discriminator};
+use continuum_cp_swap::state::{
+    AdminConfig, CpSwapPoolRegistry, CpSwapPoolRegistryV0, FifoState, FifoStateV0, OrderCommitment,
+    OrderIndex, OrderRing, OrderState, OrderStateV0, OrderStateV1, PendingChange, RelayerStake,
+    UserPoolState, UserState,
+};
+
+/// Program accounts that can be decoded from raw account data
+pub trait ProgramAccount: AccountDeserialize + Discriminator {
+    /// Data length of the current layout
+    const LEN: usize;
+    /// Data lengths of the older layouts still awaiting migration, if any
+    const LEGACY_LENS: &'static [usize] = &[];
+}
+
+impl ProgramAccount for FifoState {
+    const LEN: usize = FifoState::LEN;
+    const LEGACY_LENS: &'static [usize] = &[FifoStateV0::LEN];
+}
+
+impl ProgramAccount for CpSwapPoolRegistry {
+    const LEN: usize = CpSwapPoolRegistry::LEN;
+    const LEGACY_LENS: &'static [usize] = &[CpSwapPoolRegistryV0::LEN];
+}
+
+impl ProgramAccount for OrderState {
+    const LEN: usize = OrderState::LEN;
+    const LEGACY_LENS: &'static [usize] = &[OrderStateV0::LEN, OrderStateV1::LEN];
+}
+
+impl ProgramAccount for OrderIndex {
+    const LEN: usize = OrderIndex::LEN;
+}
+
+impl ProgramAccount for OrderCommitment {
+    const LEN: usize = OrderCommitment::LEN;
+}
+
+impl ProgramAccount for RelayerStake {
+    const LEN: usize = RelayerStake::LEN;
+}
+
+impl ProgramAccount for OrderRing {
+    const LEN: usize = OrderRing::LEN;
+}
+
+impl ProgramAccount for AdminConfig {
+    const LEN: usize = AdminConfig::LEN;
+}
+
+impl ProgramAccount for UserState {
+    const LEN: usize = UserState::LEN;
+}
+
+impl ProgramAccount for UserPoolState {
+    const LEN: usize = UserPoolState::LEN;
+}
+
+impl ProgramAccount for PendingChange {
+    const LEN: usize = PendingChange::LEN;
+}
+
+/// Decodes `data` stored at `address`, checking the discriminator
+pub fn decode<T: ProgramAccount>(address: &Pubkey, data: &[u8]) -> Result<T> {
+    if T::LEGACY_LENS.contains(&data.len()) && data.starts_with(T::DISCRIMINATOR) {
+        return Err(ClientError::NeedsMigration(*address));
+    }
+
+    T::try_deserialize(&mut &data[..]).map_err(|e| ClientError::Decode(e.to_string()))
+}
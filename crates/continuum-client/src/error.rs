@@ -0,0 +1,98 @@
+use anchor_lang::prelude::Pubkey;
+use continuum_cp_swap::errors::ContinuumError;
+
+pub type Result<T> = std::result::Result<T, ClientError>;
+
+#[derive(Debug, thiserror::Error)]
+pub enum ClientError {
+    #[error("program error: {0}")]
+    Program(ContinuumError),
+
+    /// A custom error code that is not a `ContinuumError`, e.g. from Anchor or CP-Swap
+    #[error("custom program error: {0:#x}")]
+    Custom(u32),
+
+    #[error("account {0} not found")]
+    AccountNotFound(Pubkey),
+
+    #[error("account {0} uses a pre-versioning layout and must be migrated")]
+    NeedsMigration(Pubkey),
+
+    #[error("failed to decode account: {0}")]
+    Decode(String),
+
+    #[error("transaction failed: {0}")]
+    Transaction(String),
+
+    #[error("rpc error: {0}")]
+    Rpc(String),
+}
+
+impl ClientError {
+    /// Maps a custom instruction error code to a typed error where possible
+    pub fn from_custom_code(code: u32) -> Self {
+        match continuum_error(code) {
+            Some(error) => ClientError::Program(error),
+            None => ClientError::Custom(code),
+        }
+    }
+
+    /// The program error, if this is one
+    pub fn program_error(&self) -> Option<ContinuumError> {
+        match self {
+            ClientError::Program(error) => Some(*error),
+            _ => None,
+        }
+    }
+}
+
+/// Every `ContinuumError` variant in declaration order, new variants go at the end.
+/// The program-test `errors` test fails for any variant left out.
+const CONTINUUM_ERRORS: &[ContinuumError] = &[
+    ContinuumError::InvalidSequence,
+    ContinuumError::OrderAlreadyExecuted,
+    ContinuumError::OrderNotFound,
+    ContinuumError::Unauthorized,
+    ContinuumError::PoolNotRegistered,
+    ContinuumError::PoolAlreadyRegistered,
+    ContinuumError::EmergencyPause,
+    ContinuumError::InvalidPoolConfig,
+    ContinuumError::SlippageExceeded,
+    ContinuumError::InvalidOrderStatus,
+    ContinuumError::InvalidRoute,
+    ContinuumError::InvalidOrderType,
+    ContinuumError::InvalidIntentSignature,
+    ContinuumError::InvalidIntentNonce,
+    ContinuumError::IntentExpired,
+    ContinuumError::InvalidAmount,
+    ContinuumError::InvalidAmendment,
+    ContinuumError::TooManyPendingOrders,
+    ContinuumError::OrderNotExpired,
+    ContinuumError::PoolNotAcceptingOrders,
+    ContinuumError::InvalidConfigChange,
+    ContinuumError::TimelockNotElapsed,
+    ContinuumError::UnsupportedAccountVersion,
+    ContinuumError::MathOverflow,
+    ContinuumError::InvalidQueueNeighbor,
+    ContinuumError::OrderRingFull,
+    ContinuumError::InclusionDelayNotElapsed,
+    ContinuumError::CommitRevealDisabled,
+    ContinuumError::InvalidCommitment,
+    ContinuumError::RevealWindowClosed,
+    ContinuumError::RelayerNotEligible,
+    ContinuumError::RelayerUnstaking,
+    ContinuumError::UnstakeDelayNotElapsed,
+    ContinuumError::InvalidMisbehaviorEvidence,
+    ContinuumError::PartialFillNotAllowed,
+    ContinuumError::OrderStillExecutable,
+    ContinuumError::InvalidSourceAccount,
+    ContinuumError::InvalidBondPayer,
+];
+
+/// Looks up the `ContinuumError` with the given on-chain error code
+pub fn continuum_error(code: u32) -> Option<ContinuumError> {
+    CONTINUUM_ERRORS
+        .iter()
+        .copied()
+        .find(|error| u32::from(*error) == code)
+}
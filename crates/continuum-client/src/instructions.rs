@@ -0,0 +1,1071 @@
+//! Instruction builders for every program instruction.
+//!
+//! Builders derive every PDA themselves. Accounts that are forwarded to CP-Swap
+//! are passed through as `remaining` account metas in the order CP-Swap expects.
+
+use crate::pda;
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::sysvar;
+use anchor_lang::system_program;
+use anchor_lang::{InstructionData, ToAccountMetas};
+use anchor_spl::associated_token::get_associated_token_address;
+use continuum_cp_swap::state::{
+    ConfigChange, CpSwapPoolRegistry, FillPolicy, OrderIntent, OrderState, OrderStatus,
+};
+use continuum_cp_swap::{accounts, instruction, ID};
+
+fn build(
+    accounts: impl ToAccountMetas,
+    remaining: Vec<AccountMeta>,
+    data: impl InstructionData,
+) -> Instruction {
+    let mut metas = accounts.to_account_metas(None);
+    metas.extend(remaining);
+    Instruction {
+        program_id: ID,
+        accounts: metas,
+        data: data.data(),
+    }
+}
+
+fn event_authority() -> Pubkey {
+    pda::event_authority().0
+}
+
+/// Addresses of the orders linked before and after an order in its pool queue,
+/// needed by every instruction that takes the order out of the queue
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct QueueNeighbors {
+    pub prev: Option<Pubkey>,
+    pub next: Option<Pubkey>,
+}
+
+pub fn initialize(admin: &Pubkey) -> Instruction {
+    build(
+        accounts::Initialize {
+            fifo_state: pda::fifo_state().0,
+            admin: *admin,
+            system_program: system_program::ID,
+            event_authority: event_authority(),
+            program: ID,
+        },
+        vec![],
+        instruction::Initialize {},
+    )
+}
+
+/// `cp_swap_accounts` are the CP-Swap `initialize` accounts
+#[allow(clippy::too_many_arguments)]
+pub fn initialize_cp_swap_pool(
+    admin: &Pubkey,
+    payer: &Pubkey,
+    pool_state: &Pubkey,
+    cp_swap_program: &Pubkey,
+    init_amount_0: u64,
+    init_amount_1: u64,
+    open_time: u64,
+    cp_swap_accounts: Vec<AccountMeta>,
+) -> Instruction {
+    build(
+        accounts::InitializeCpSwapPool {
+            admin_config: pda::admin_config().0,
+            pool_registry: pda::pool_registry(pool_state).0,
+            pool_authority: pda::pool_authority(pool_state).0,
+            admin: *admin,
+            payer: *payer,
+            pool_state: *pool_state,
+            cp_swap_program: *cp_swap_program,
+            system_program: system_program::ID,
+            event_authority: event_authority(),
+            program: ID,
+        },
+        cp_swap_accounts,
+        instruction::InitializeCpSwapPool {
+            init_amount_0,
+            init_amount_1,
+            open_time,
+        },
+    )
+}
+
+pub fn register_existing_pool(admin: &Pubkey, payer: &Pubkey, pool_state: &Pubkey) -> Instruction {
+    build(
+        accounts::RegisterExistingPool {
+            admin_config: pda::admin_config().0,
+            pool_registry: pda::pool_registry(pool_state).0,
+            pool_authority: pda::pool_authority(pool_state).0,
+            admin: *admin,
+            payer: *payer,
+            pool_state: *pool_state,
+            system_program: system_program::ID,
+            event_authority: event_authority(),
+            program: ID,
+        },
+        vec![],
+        instruction::RegisterExistingPool {},
+    )
+}
+
+/// `current_sequence` is the FIFO sequence before submission, it seeds the order
+/// account. `queue_tail` is the last order in the pool queue, if any.
+/// `user_source` binds the order to the token account it is funded from.
+#[allow(clippy::too_many_arguments)]
+pub fn submit_order(
+    user: &Pubkey,
+    pool_id: &Pubkey,
+    current_sequence: u64,
+    queue_tail: Option<Pubkey>,
+    user_source: Option<Pubkey>,
+    amount_in: u64,
+    min_amount_out: u64,
+    is_base_input: bool,
+    fill_policy: FillPolicy,
+) -> Instruction {
+    build(
+        accounts::SubmitOrder {
+            fifo_state: pda::fifo_state().0,
+            pool_registry: pda::pool_registry(pool_id).0,
+            queue_tail,
+            order_state: pda::order(user, current_sequence).0,
+            order_index: pda::order_index(pool_id, current_sequence + 1).0,
+            user_state: pda::user_state(user).0,
+            user_pool_state: pda::user_pool_state(user, pool_id).0,
+            user: *user,
+            user_source,
+            pool_id: *pool_id,
+            system_program: system_program::ID,
+            clock: sysvar::clock::ID,
+            event_authority: event_authority(),
+            program: ID,
+        },
+        vec![],
+        instruction::SubmitOrder {
+            amount_in,
+            min_amount_out,
+            is_base_input,
+            fill_policy,
+        },
+    )
+}
+
+/// `swap_accounts` are the CP-Swap swap accounts after the authority and user token accounts.
+/// `staked` passes the executor's relayer stake, required by pools with a minimum stake.
+/// `fill_amount` fills only part of the order, `None` fills what is left of it.
+#[allow(clippy::too_many_arguments)]
+pub fn execute_order(
+    executor: &Pubkey,
+    staked: bool,
+    order_address: &Pubkey,
+    order: &OrderState,
+    neighbors: QueueNeighbors,
+    user_source: &Pubkey,
+    user_destination: &Pubkey,
+    cp_swap_program: &Pubkey,
+    fill_amount: Option<u64>,
+    swap_accounts: Vec<AccountMeta>,
+) -> Instruction {
+    build(
+        accounts::ExecuteOrder {
+            order_state: *order_address,
+            user_state: pda::user_state(&order.user).0,
+            user_pool_state: pda::user_pool_state(&order.user, &order.pool_id).0,
+            order_index: pda::order_index(&order.pool_id, order.sequence).0,
+            user: order.user,
+            bond_payer: order.bond_refund_recipient(),
+            pool_registry: pda::pool_registry(&order.pool_id).0,
+            queue_prev: neighbors.prev,
+            queue_next: neighbors.next,
+            pool_authority: pda::pool_authority(&order.pool_id).0,
+            executor: *executor,
+            relayer_stake: staked.then(|| pda::relayer_stake(executor).0),
+            user_source: *user_source,
+            user_destination: *user_destination,
+            cp_swap_program: *cp_swap_program,
+            token_program: anchor_spl::token::ID,
+            clock: sysvar::clock::ID,
+            event_authority: event_authority(),
+            program: ID,
+        },
+        swap_accounts,
+        instruction::ExecuteOrder {
+            expected_sequence: order.sequence,
+            fill_amount,
+        },
+    )
+}
+
+/// `bond_recipient` is only needed when cancelling forfeits part of the bond
+pub fn cancel_order(
+    user: &Pubkey,
+    order_address: &Pubkey,
+    order: &OrderState,
+    neighbors: QueueNeighbors,
+    bond_recipient: Option<Pubkey>,
+) -> Instruction {
+    build(
+        accounts::CancelOrder {
+            order_state: *order_address,
+            user_state: pda::user_state(&order.user).0,
+            user_pool_state: pda::user_pool_state(&order.user, &order.pool_id).0,
+            order_index: pda::order_index(&order.pool_id, order.sequence).0,
+            pool_registry: pda::pool_registry(&order.pool_id).0,
+            queue_prev: neighbors.prev,
+            queue_next: neighbors.next,
+            bond_recipient,
+            bond_payer: order.bond_refund_recipient(),
+            user: *user,
+            clock: sysvar::clock::ID,
+            event_authority: event_authority(),
+            program: ID,
+        },
+        vec![],
+        instruction::CancelOrder {},
+    )
+}
+
+/// `requeue_sequence` is the current FIFO sequence when the amendment loses queue
+/// priority and the order moves to a new account at the tail, `None` otherwise.
+/// Re-queueing also needs the order's queue neighbors and the queue tail, which
+/// is left out when it is the order itself or one of its neighbors. It closes the
+/// order's index, so clear `order_index` for orders submitted before indexing.
+#[allow(clippy::too_many_arguments)]
+pub fn amend_order(
+    user: &Pubkey,
+    order_address: &Pubkey,
+    order: &OrderState,
+    requeue_sequence: Option<u64>,
+    neighbors: QueueNeighbors,
+    queue_tail: Option<Pubkey>,
+    new_amount_in: u64,
+    new_min_amount_out: u64,
+) -> Instruction {
+    build(
+        accounts::AmendOrder {
+            fifo_state: pda::fifo_state().0,
+            order_state: *order_address,
+            pool_registry: pda::pool_registry(&order.pool_id).0,
+            queue_prev: neighbors.prev,
+            queue_next: neighbors.next,
+            queue_tail,
+            new_order_state: requeue_sequence.map(|sequence| pda::order(user, sequence).0),
+            order_index: requeue_sequence
+                .map(|_| pda::order_index(&order.pool_id, order.sequence).0),
+            new_order_index: requeue_sequence
+                .map(|sequence| pda::order_index(&order.pool_id, sequence + 1).0),
+            user: *user,
+            system_program: system_program::ID,
+            clock: sysvar::clock::ID,
+            event_authority: event_authority(),
+            program: ID,
+        },
+        vec![],
+        instruction::AmendOrder {
+            new_amount_in,
+            new_min_amount_out,
+        },
+    )
+}
+
+pub fn submit_order_simple(
+    user: &Pubkey,
+    pool_id: &Pubkey,
+    user_source_token: &Pubkey,
+    user_destination_token: &Pubkey,
+    amount_in: u64,
+    min_amount_out: u64,
+    is_base_input: bool,
+) -> Instruction {
+    build(
+        accounts::SubmitOrderSimple {
+            fifo_state: pda::fifo_state().0,
+            pool_registry: pda::pool_registry(pool_id).0,
+            user: *user,
+            pool_id: *pool_id,
+            user_source_token: *user_source_token,
+            user_destination_token: *user_destination_token,
+            token_program: anchor_spl::token::ID,
+            clock: sysvar::clock::ID,
+            event_authority: event_authority(),
+            program: ID,
+        },
+        vec![],
+        instruction::SubmitOrderSimple {
+            amount_in,
+            min_amount_out,
+            is_base_input,
+        },
+    )
+}
+
+/// `swap_accounts` are the CP-Swap swap accounts after the authority
+pub fn swap_immediate(
+    pool_id: &Pubkey,
+    cp_swap_program: &Pubkey,
+    amount_in: u64,
+    min_amount_out: u64,
+    is_base_input: bool,
+    swap_accounts: Vec<AccountMeta>,
+) -> Instruction {
+    build(
+        accounts::SwapImmediate {
+            fifo_state: pda::fifo_state().0,
+            pool_registry: pda::pool_registry(pool_id).0,
+            pool_authority: pda::pool_authority(pool_id).0,
+            pool_id: *pool_id,
+            cp_swap_program: *cp_swap_program,
+            event_authority: event_authority(),
+            program: ID,
+        },
+        swap_accounts,
+        instruction::SwapImmediate {
+            amount_in,
+            min_amount_out,
+            is_base_input,
+        },
+    )
+}
+
+pub fn initialize_order_ring(admin: &Pubkey, payer: &Pubkey, pool_id: &Pubkey) -> Instruction {
+    build(
+        accounts::InitializeOrderRing {
+            admin_config: pda::admin_config().0,
+            pool_registry: pda::pool_registry(pool_id).0,
+            order_ring: pda::order_ring(pool_id).0,
+            admin: *admin,
+            payer: *payer,
+            pool_id: *pool_id,
+            system_program: system_program::ID,
+            event_authority: event_authority(),
+            program: ID,
+        },
+        vec![],
+        instruction::InitializeOrderRing {},
+    )
+}
+
+pub fn submit_ring_order(
+    user: &Pubkey,
+    pool_id: &Pubkey,
+    amount_in: u64,
+    min_amount_out: u64,
+    is_base_input: bool,
+) -> Instruction {
+    build(
+        accounts::SubmitRingOrder {
+            fifo_state: pda::fifo_state().0,
+            pool_registry: pda::pool_registry(pool_id).0,
+            order_ring: pda::order_ring(pool_id).0,
+            user_state: pda::user_state(user).0,
+            user_pool_state: pda::user_pool_state(user, pool_id).0,
+            user: *user,
+            pool_id: *pool_id,
+            system_program: system_program::ID,
+            clock: sysvar::clock::ID,
+            event_authority: event_authority(),
+            program: ID,
+        },
+        vec![],
+        instruction::SubmitRingOrder {
+            amount_in,
+            min_amount_out,
+            is_base_input,
+        },
+    )
+}
+
+/// `expected_sequence` is the ring's head sequence, and `user` and the token
+/// accounts belong to the head order's user. `staked` as for `execute_order`.
+#[allow(clippy::too_many_arguments)]
+pub fn execute_ring_order(
+    executor: &Pubkey,
+    staked: bool,
+    pool_id: &Pubkey,
+    expected_sequence: u64,
+    user: &Pubkey,
+    user_source: &Pubkey,
+    user_destination: &Pubkey,
+    cp_swap_program: &Pubkey,
+    swap_accounts: Vec<AccountMeta>,
+) -> Instruction {
+    build(
+        accounts::ExecuteRingOrder {
+            order_ring: pda::order_ring(pool_id).0,
+            pool_registry: pda::pool_registry(pool_id).0,
+            pool_authority: pda::pool_authority(pool_id).0,
+            pool_id: *pool_id,
+            executor: *executor,
+            relayer_stake: staked.then(|| pda::relayer_stake(executor).0),
+            user_source: *user_source,
+            user_destination: *user_destination,
+            user_state: pda::user_state(user).0,
+            user_pool_state: pda::user_pool_state(user, pool_id).0,
+            user: *user,
+            cp_swap_program: *cp_swap_program,
+            token_program: anchor_spl::token::ID,
+            clock: sysvar::clock::ID,
+            event_authority: event_authority(),
+            program: ID,
+        },
+        swap_accounts,
+        instruction::ExecuteRingOrder { expected_sequence },
+    )
+}
+
+/// `bond_recipient` is only needed when cancelling forfeits part of the bond
+pub fn cancel_ring_order(
+    user: &Pubkey,
+    pool_id: &Pubkey,
+    sequence: u64,
+    bond_recipient: Option<Pubkey>,
+) -> Instruction {
+    build(
+        accounts::CancelRingOrder {
+            order_ring: pda::order_ring(pool_id).0,
+            pool_registry: pda::pool_registry(pool_id).0,
+            user_state: pda::user_state(user).0,
+            user_pool_state: pda::user_pool_state(user, pool_id).0,
+            bond_recipient,
+            user: *user,
+            pool_id: *pool_id,
+            clock: sysvar::clock::ID,
+            event_authority: event_authority(),
+            program: ID,
+        },
+        vec![],
+        instruction::CancelRingOrder { sequence },
+    )
+}
+
+/// `user` is the user of the ring record with `sequence`
+pub fn expire_ring_order(
+    caller: &Pubkey,
+    user: &Pubkey,
+    pool_id: &Pubkey,
+    sequence: u64,
+) -> Instruction {
+    build(
+        accounts::ExpireRingOrder {
+            order_ring: pda::order_ring(pool_id).0,
+            pool_registry: pda::pool_registry(pool_id).0,
+            user_state: pda::user_state(user).0,
+            user_pool_state: pda::user_pool_state(user, pool_id).0,
+            user: *user,
+            pool_id: *pool_id,
+            caller: *caller,
+            clock: sysvar::clock::ID,
+            event_authority: event_authority(),
+            program: ID,
+        },
+        vec![],
+        instruction::ExpireRingOrder { sequence },
+    )
+}
+
+/// `deposit_accounts` are the CP-Swap `deposit` accounts after the owner
+pub fn deposit_liquidity(
+    pool_id: &Pubkey,
+    cp_swap_program: &Pubkey,
+    lp_token_amount: u64,
+    maximum_token_0_amount: u64,
+    maximum_token_1_amount: u64,
+    deposit_accounts: Vec<AccountMeta>,
+) -> Instruction {
+    build(
+        accounts::DepositLiquidity {
+            fifo_state: pda::fifo_state().0,
+            pool_registry: pda::pool_registry(pool_id).0,
+            pool_authority: pda::pool_authority(pool_id).0,
+            pool_id: *pool_id,
+            cp_swap_program: *cp_swap_program,
+            event_authority: event_authority(),
+            program: ID,
+        },
+        deposit_accounts,
+        instruction::DepositLiquidity {
+            lp_token_amount,
+            maximum_token_0_amount,
+            maximum_token_1_amount,
+        },
+    )
+}
+
+/// `withdraw_accounts` are the CP-Swap `withdraw` accounts after the owner
+pub fn withdraw_liquidity(
+    pool_id: &Pubkey,
+    cp_swap_program: &Pubkey,
+    lp_token_amount: u64,
+    minimum_token_0_amount: u64,
+    minimum_token_1_amount: u64,
+    withdraw_accounts: Vec<AccountMeta>,
+) -> Instruction {
+    build(
+        accounts::WithdrawLiquidity {
+            fifo_state: pda::fifo_state().0,
+            pool_registry: pda::pool_registry(pool_id).0,
+            pool_authority: pda::pool_authority(pool_id).0,
+            pool_id: *pool_id,
+            cp_swap_program: *cp_swap_program,
+            event_authority: event_authority(),
+            program: ID,
+        },
+        withdraw_accounts,
+        instruction::WithdrawLiquidity {
+            lp_token_amount,
+            minimum_token_0_amount,
+            minimum_token_1_amount,
+        },
+    )
+}
+
+/// The order queues in the first pool of `route`, `queue_tail` is the last order there
+pub fn submit_multi_hop_order(
+    user: &Pubkey,
+    current_sequence: u64,
+    queue_tail: Option<Pubkey>,
+    route: Vec<Pubkey>,
+    amount_in: u64,
+    min_amount_out: u64,
+) -> Instruction {
+    let registries = route
+        .iter()
+        .enumerate()
+        .map(|(i, pool_id)| {
+            let registry = pda::pool_registry(pool_id).0;
+            if i == 0 {
+                AccountMeta::new(registry, false)
+            } else {
+                AccountMeta::new_readonly(registry, false)
+            }
+        })
+        .collect();
+
+    // An empty route gets a placeholder index, the program refuses it anyway
+    let first_pool = route.first().copied().unwrap_or_default();
+
+    build(
+        accounts::SubmitMultiHopOrder {
+            fifo_state: pda::fifo_state().0,
+            order_state: pda::order(user, current_sequence).0,
+            order_index: pda::order_index(&first_pool, current_sequence + 1).0,
+            user_state: pda::user_state(user).0,
+            user_pool_state: pda::user_pool_state(user, &first_pool).0,
+            user: *user,
+            queue_tail,
+            system_program: system_program::ID,
+            clock: sysvar::clock::ID,
+            event_authority: event_authority(),
+            program: ID,
+        },
+        registries,
+        instruction::SubmitMultiHopOrder {
+            route,
+            amount_in,
+            min_amount_out,
+        },
+    )
+}
+
+/// `intermediates` are the user's token accounts between hops and
+/// `hop_swap_accounts` the CP-Swap swap accounts of each hop, all of equal length.
+/// `staked` as for `execute_order`, the first pool decides whether it is required.
+#[allow(clippy::too_many_arguments)]
+pub fn execute_multi_hop_order(
+    executor: &Pubkey,
+    staked: bool,
+    order_address: &Pubkey,
+    order: &OrderState,
+    neighbors: QueueNeighbors,
+    user_source: &Pubkey,
+    user_destination: &Pubkey,
+    cp_swap_program: &Pubkey,
+    intermediates: &[Pubkey],
+    hop_swap_accounts: Vec<Vec<AccountMeta>>,
+) -> Instruction {
+    let accounts_per_hop = hop_swap_accounts.first().map_or(0, Vec::len) as u8;
+
+    let mut remaining: Vec<AccountMeta> = intermediates
+        .iter()
+        .map(|account| AccountMeta::new(*account, false))
+        .collect();
+    for (i, (pool_id, swap_accounts)) in order.route().iter().zip(hop_swap_accounts).enumerate() {
+        // The first pool's registry holds the order's queue
+        let registry = pda::pool_registry(pool_id).0;
+        remaining.push(if i == 0 {
+            AccountMeta::new(registry, false)
+        } else {
+            AccountMeta::new_readonly(registry, false)
+        });
+        remaining.push(AccountMeta::new_readonly(
+            pda::pool_authority(pool_id).0,
+            false,
+        ));
+        remaining.extend(swap_accounts);
+    }
+
+    build(
+        accounts::ExecuteMultiHopOrder {
+            order_state: *order_address,
+            user_state: pda::user_state(&order.user).0,
+            user_pool_state: pda::user_pool_state(&order.user, &order.pool_id).0,
+            order_index: pda::order_index(&order.pool_id, order.sequence).0,
+            user: order.user,
+            bond_payer: order.bond_refund_recipient(),
+            executor: *executor,
+            relayer_stake: staked.then(|| pda::relayer_stake(executor).0),
+            user_source: *user_source,
+            user_destination: *user_destination,
+            cp_swap_program: *cp_swap_program,
+            queue_prev: neighbors.prev,
+            queue_next: neighbors.next,
+            token_program: anchor_spl::token::ID,
+            clock: sysvar::clock::ID,
+            event_authority: event_authority(),
+            program: ID,
+        },
+        remaining,
+        instruction::ExecuteMultiHopOrder {
+            expected_sequence: order.sequence,
+            accounts_per_hop,
+        },
+    )
+}
+
+/// Must directly follow an ed25519 program instruction verifying the user's
+/// signature over the borsh-serialized `intent`
+pub fn submit_order_with_intent(
+    relayer: &Pubkey,
+    current_sequence: u64,
+    queue_tail: Option<Pubkey>,
+    relayer_fee_account: &Pubkey,
+    intent: OrderIntent,
+) -> Instruction {
+    build(
+        accounts::SubmitOrderWithIntent {
+            fifo_state: pda::fifo_state().0,
+            pool_registry: pda::pool_registry(&intent.pool_id).0,
+            queue_tail,
+            order_state: pda::order(&intent.user, current_sequence).0,
+            order_index: pda::order_index(&intent.pool_id, current_sequence + 1).0,
+            user_state: pda::user_state(&intent.user).0,
+            user_pool_state: pda::user_pool_state(&intent.user, &intent.pool_id).0,
+            pool_authority: pda::pool_authority(&intent.pool_id).0,
+            relayer: *relayer,
+            user_source: intent.source,
+            relayer_fee_account: *relayer_fee_account,
+            instructions: sysvar::instructions::ID,
+            token_program: anchor_spl::token::ID,
+            system_program: system_program::ID,
+            clock: sysvar::clock::ID,
+            event_authority: event_authority(),
+            program: ID,
+        },
+        vec![],
+        instruction::SubmitOrderWithIntent { intent },
+    )
+}
+
+pub fn expire_order(
+    caller: &Pubkey,
+    order_address: &Pubkey,
+    order: &OrderState,
+    neighbors: QueueNeighbors,
+) -> Instruction {
+    build(
+        accounts::ExpireOrder {
+            order_state: *order_address,
+            user_state: pda::user_state(&order.user).0,
+            user_pool_state: pda::user_pool_state(&order.user, &order.pool_id).0,
+            order_index: pda::order_index(&order.pool_id, order.sequence).0,
+            user: order.user,
+            bond_payer: order.bond_refund_recipient(),
+            pool_registry: pda::pool_registry(&order.pool_id).0,
+            queue_prev: neighbors.prev,
+            queue_next: neighbors.next,
+            order_commitment: (order.status == OrderStatus::Committed)
+                .then(|| pda::order_commitment(order_address).0),
+            caller: *caller,
+            clock: sysvar::clock::ID,
+            event_authority: event_authority(),
+            program: ID,
+        },
+        vec![],
+        instruction::ExpireOrder {},
+    )
+}
+
+/// `order` must be at the head of its pool's queue and `queue_next` is the
+/// order after it, if any. `staked` as for `execute_order`, `amm_config` as for
+/// `quote_swap`. The order is judged on its funding source, or without one on
+/// the user's associated token accounts.
+pub fn fail_order(
+    executor: &Pubkey,
+    staked: bool,
+    order_address: &Pubkey,
+    order: &OrderState,
+    queue_next: Option<Pubkey>,
+    registry: &CpSwapPoolRegistry,
+    amm_config: &Pubkey,
+) -> Instruction {
+    build(
+        accounts::FailOrder {
+            order_state: *order_address,
+            user_state: pda::user_state(&order.user).0,
+            user_pool_state: pda::user_pool_state(&order.user, &order.pool_id).0,
+            order_index: pda::order_index(&order.pool_id, order.sequence).0,
+            user: order.user,
+            bond_payer: order.bond_refund_recipient(),
+            pool_registry: pda::pool_registry(&order.pool_id).0,
+            queue_next,
+            pool_authority: pda::pool_authority(&order.pool_id).0,
+            executor: *executor,
+            relayer_stake: staked.then(|| pda::relayer_stake(executor).0),
+            pool_state: registry.pool_id,
+            amm_config: *amm_config,
+            token_0_vault: registry.token_0_vault,
+            token_1_vault: registry.token_1_vault,
+            user_token_0: if order.source == Pubkey::default() {
+                get_associated_token_address(&order.user, &registry.token_0)
+            } else {
+                order.source
+            },
+            user_token_1: get_associated_token_address(&order.user, &registry.token_1),
+            clock: sysvar::clock::ID,
+            event_authority: event_authority(),
+            program: ID,
+        },
+        vec![],
+        instruction::FailOrder {},
+    )
+}
+
+/// Same accounts as `submit_order`, the order parameters stay hidden behind
+/// `commitment`, see `OrderCommitment::hash`
+pub fn commit_order(
+    user: &Pubkey,
+    pool_id: &Pubkey,
+    current_sequence: u64,
+    queue_tail: Option<Pubkey>,
+    commitment: [u8; 32],
+) -> Instruction {
+    let order_state = pda::order(user, current_sequence).0;
+    build(
+        accounts::CommitOrder {
+            fifo_state: pda::fifo_state().0,
+            pool_registry: pda::pool_registry(pool_id).0,
+            queue_tail,
+            order_state,
+            order_commitment: pda::order_commitment(&order_state).0,
+            order_index: pda::order_index(pool_id, current_sequence + 1).0,
+            user_state: pda::user_state(user).0,
+            user_pool_state: pda::user_pool_state(user, pool_id).0,
+            user: *user,
+            pool_id: *pool_id,
+            system_program: system_program::ID,
+            clock: sysvar::clock::ID,
+            event_authority: event_authority(),
+            program: ID,
+        },
+        vec![],
+        instruction::CommitOrder { commitment },
+    )
+}
+
+pub fn reveal_order(
+    user: &Pubkey,
+    order_address: &Pubkey,
+    amount_in: u64,
+    min_amount_out: u64,
+    is_base_input: bool,
+    salt: [u8; 32],
+) -> Instruction {
+    build(
+        accounts::RevealOrder {
+            order_state: *order_address,
+            order_commitment: pda::order_commitment(order_address).0,
+            user: *user,
+            clock: sysvar::clock::ID,
+            event_authority: event_authority(),
+            program: ID,
+        },
+        vec![],
+        instruction::RevealOrder {
+            amount_in,
+            min_amount_out,
+            is_base_input,
+            salt,
+        },
+    )
+}
+
+pub fn stake_relayer(relayer: &Pubkey, amount: u64) -> Instruction {
+    build(
+        accounts::StakeRelayer {
+            fifo_state: pda::fifo_state().0,
+            relayer_stake: pda::relayer_stake(relayer).0,
+            relayer: *relayer,
+            system_program: system_program::ID,
+            event_authority: event_authority(),
+            program: ID,
+        },
+        vec![],
+        instruction::StakeRelayer { amount },
+    )
+}
+
+pub fn request_unstake(relayer: &Pubkey) -> Instruction {
+    build(
+        accounts::RequestUnstake {
+            relayer_stake: pda::relayer_stake(relayer).0,
+            relayer: *relayer,
+            clock: sysvar::clock::ID,
+            event_authority: event_authority(),
+            program: ID,
+        },
+        vec![],
+        instruction::RequestUnstake {},
+    )
+}
+
+pub fn withdraw_stake(relayer: &Pubkey) -> Instruction {
+    build(
+        accounts::WithdrawStake {
+            relayer_stake: pda::relayer_stake(relayer).0,
+            relayer: *relayer,
+            clock: sysvar::clock::ID,
+            event_authority: event_authority(),
+            program: ID,
+        },
+        vec![],
+        instruction::WithdrawStake {},
+    )
+}
+
+/// `relayer` is the holder of the stake recorded on `executed`
+pub fn report_misbehavior(
+    reporter: &Pubkey,
+    relayer: &Pubkey,
+    executed_address: &Pubkey,
+    executed: &OrderState,
+    skipped_address: &Pubkey,
+    skipped: &OrderState,
+) -> Instruction {
+    build(
+        accounts::ReportMisbehavior {
+            executed_order: *executed_address,
+            skipped_order: *skipped_address,
+            pool_registry: pda::pool_registry(&executed.pool_id).0,
+            relayer_stake: pda::relayer_stake(relayer).0,
+            skipped_user: skipped.user,
+            reporter: *reporter,
+            event_authority: event_authority(),
+            program: ID,
+        },
+        vec![],
+        instruction::ReportMisbehavior {},
+    )
+}
+
+/// Read-only, simulate it and decode the return data as a `QueueStatus`
+pub fn get_queue_status(pool_id: &Pubkey) -> Instruction {
+    build(
+        accounts::GetQueueStatus {
+            pool_registry: pda::pool_registry(pool_id).0,
+            pool_id: *pool_id,
+        },
+        vec![],
+        instruction::GetQueueStatus {},
+    )
+}
+
+/// Read-only, simulate it and decode the return data as a `SwapQuote`.
+/// `amm_config` is the config the pool was created against.
+pub fn quote_swap(
+    registry: &CpSwapPoolRegistry,
+    amm_config: &Pubkey,
+    amount: u64,
+    zero_for_one: bool,
+    is_base_input: bool,
+) -> Instruction {
+    build(
+        accounts::QuoteSwap {
+            pool_registry: pda::pool_registry(&registry.pool_id).0,
+            pool_state: registry.pool_id,
+            amm_config: *amm_config,
+            token_0_vault: registry.token_0_vault,
+            token_1_vault: registry.token_1_vault,
+        },
+        vec![],
+        instruction::QuoteSwap {
+            amount,
+            zero_for_one,
+            is_base_input,
+        },
+    )
+}
+
+pub fn initialize_admin_config(admin: &Pubkey) -> Instruction {
+    build(
+        accounts::InitializeAdminConfig {
+            fifo_state: pda::fifo_state().0,
+            admin_config: pda::admin_config().0,
+            admin: *admin,
+            system_program: system_program::ID,
+            event_authority: event_authority(),
+            program: ID,
+        },
+        vec![],
+        instruction::InitializeAdminConfig {},
+    )
+}
+
+pub fn accept_super_admin(new_super_admin: &Pubkey) -> Instruction {
+    build(
+        accounts::AcceptSuperAdmin {
+            admin_config: pda::admin_config().0,
+            fifo_state: pda::fifo_state().0,
+            new_super_admin: *new_super_admin,
+            event_authority: event_authority(),
+            program: ID,
+        },
+        vec![],
+        instruction::AcceptSuperAdmin {},
+    )
+}
+
+pub fn set_emergency_pause(pauser: &Pubkey, paused: bool) -> Instruction {
+    build(
+        accounts::SetEmergencyPause {
+            admin_config: pda::admin_config().0,
+            fifo_state: pda::fifo_state().0,
+            pauser: *pauser,
+            event_authority: event_authority(),
+            program: ID,
+        },
+        vec![],
+        instruction::SetEmergencyPause { paused },
+    )
+}
+
+/// `change_id` is the admin config's current `change_count`
+pub fn queue_config_change(
+    admin: &Pubkey,
+    payer: &Pubkey,
+    change_id: u64,
+    change: ConfigChange,
+) -> Instruction {
+    build(
+        accounts::QueueConfigChange {
+            admin_config: pda::admin_config().0,
+            pending_change: pda::pending_change(change_id).0,
+            admin: *admin,
+            payer: *payer,
+            system_program: system_program::ID,
+            clock: sysvar::clock::ID,
+            event_authority: event_authority(),
+            program: ID,
+        },
+        vec![],
+        instruction::QueueConfigChange { change },
+    )
+}
+
+/// `pool_id` is the pool targeted by the change, if any
+pub fn execute_config_change(
+    change_id: u64,
+    payer: &Pubkey,
+    pool_id: Option<Pubkey>,
+) -> Instruction {
+    build(
+        accounts::ExecuteConfigChange {
+            admin_config: pda::admin_config().0,
+            pending_change: pda::pending_change(change_id).0,
+            pool_registry: pool_id.map(|pool_id| pda::pool_registry(&pool_id).0),
+            payer: *payer,
+            clock: sysvar::clock::ID,
+            event_authority: event_authority(),
+            program: ID,
+        },
+        vec![],
+        instruction::ExecuteConfigChange {},
+    )
+}
+
+pub fn cancel_config_change(admin: &Pubkey, change_id: u64, payer: &Pubkey) -> Instruction {
+    build(
+        accounts::CancelConfigChange {
+            admin_config: pda::admin_config().0,
+            pending_change: pda::pending_change(change_id).0,
+            admin: *admin,
+            payer: *payer,
+            event_authority: event_authority(),
+            program: ID,
+        },
+        vec![],
+        instruction::CancelConfigChange {},
+    )
+}
+
+pub fn migrate_fifo_state(super_admin: &Pubkey, payer: &Pubkey) -> Instruction {
+    build(
+        accounts::MigrateFifoState {
+            admin_config: pda::admin_config().0,
+            fifo_state: pda::fifo_state().0,
+            super_admin: *super_admin,
+            payer: *payer,
+            system_program: system_program::ID,
+            event_authority: event_authority(),
+            program: ID,
+        },
+        vec![],
+        instruction::MigrateFifoState {},
+    )
+}
+
+pub fn migrate_pool_registry(
+    super_admin: &Pubkey,
+    payer: &Pubkey,
+    pool_state: &Pubkey,
+) -> Instruction {
+    build(
+        accounts::MigratePoolRegistry {
+            admin_config: pda::admin_config().0,
+            pool_registry: pda::pool_registry(pool_state).0,
+            pool_state: *pool_state,
+            super_admin: *super_admin,
+            payer: *payer,
+            system_program: system_program::ID,
+            event_authority: event_authority(),
+            program: ID,
+        },
+        vec![],
+        instruction::MigratePoolRegistry {},
+    )
+}
+
+/// `user` and `pool_id` are the legacy order's, read from its old layout
+pub fn migrate_order(
+    super_admin: &Pubkey,
+    payer: &Pubkey,
+    order_address: &Pubkey,
+    user: &Pubkey,
+    pool_id: &Pubkey,
+) -> Instruction {
+    build(
+        accounts::MigrateOrder {
+            admin_config: pda::admin_config().0,
+            order_state: *order_address,
+            user: *user,
+            pool_id: *pool_id,
+            user_state: pda::user_state(user).0,
+            user_pool_state: pda::user_pool_state(user, pool_id).0,
+            super_admin: *super_admin,
+            payer: *payer,
+            system_program: system_program::ID,
+            event_authority: event_authority(),
+            program: ID,
+        },
+        vec![],
+        instruction::MigrateOrder {},
+    )
+}
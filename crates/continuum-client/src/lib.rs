@@ -0,0 +1,12 @@
+//! Rust client for the Continuum CP-Swap program: PDA derivation, instruction
+//! builders, typed account decoding and program error mapping.
+
+pub mod accounts;
+pub mod error;
+pub mod instructions;
+pub mod pda;
+#[cfg(feature = "rpc")]
+pub mod rpc;
+
+pub use continuum_cp_swap::{self, state, ID};
+pub use error::{ClientError, Result};
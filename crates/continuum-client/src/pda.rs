@@ -0,0 +1,68 @@
+//! PDA derivation for every seed used by the program.
+
+use anchor_lang::prelude::Pubkey;
+use continuum_cp_swap::ID;
+
+pub fn fifo_state() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"fifo_state"], &ID)
+}
+
+pub fn admin_config() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"admin_config"], &ID)
+}
+
+pub fn pool_registry(pool_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"pool_registry", pool_id.as_ref()], &ID)
+}
+
+/// Continuum authority set as the CP-Swap pool's custom authority
+pub fn pool_authority(pool_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"cp_pool_authority", pool_id.as_ref()], &ID)
+}
+
+/// Order account seeded with `seed_sequence`. Submissions seed new orders with
+/// the FIFO sequence read before it is incremented.
+pub fn order(user: &Pubkey, seed_sequence: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"order", user.as_ref(), &seed_sequence.to_le_bytes()],
+        &ID,
+    )
+}
+
+/// Pointer to the order with `sequence` queued in `pool_id`. Unlike the order
+/// itself it takes the order's own sequence, not the seed sequence.
+pub fn order_index(pool_id: &Pubkey, sequence: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"order_index", pool_id.as_ref(), &sequence.to_le_bytes()],
+        &ID,
+    )
+}
+
+pub fn order_commitment(order: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"order_commitment", order.as_ref()], &ID)
+}
+
+pub fn order_ring(pool_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"order_ring", pool_id.as_ref()], &ID)
+}
+
+pub fn relayer_stake(relayer: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"relayer_stake", relayer.as_ref()], &ID)
+}
+
+pub fn user_state(user: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"user_state", user.as_ref()], &ID)
+}
+
+pub fn user_pool_state(user: &Pubkey, pool_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"user_pool_state", user.as_ref(), pool_id.as_ref()], &ID)
+}
+
+pub fn pending_change(id: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"pending_change", &id.to_le_bytes()], &ID)
+}
+
+/// Signer of the self-CPI that carries program events
+pub fn event_authority() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"__event_authority"], &ID)
+}
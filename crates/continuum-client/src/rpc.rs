@@ -0,0 +1,255 @@
+//! Minimal blocking JSON-RPC client, enough to fetch program state and send
+//! transactions against any Solana RPC endpoint.
+
+use crate::accounts::{decode, ProgramAccount};
+use crate::error::{ClientError, Result};
+use crate::instructions::QueueNeighbors;
+use crate::pda;
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::hash::Hash;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use continuum_cp_swap::state::{OrderIndex, OrderState};
+use continuum_cp_swap::ID;
+use serde_json::{json, Value};
+use std::str::FromStr;
+use std::thread::sleep;
+use std::time::{Duration, Instant};
+
+pub struct RpcClient {
+    url: String,
+    agent: ureq::Agent,
+}
+
+impl RpcClient {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            agent: ureq::AgentBuilder::new()
+                .timeout(Duration::from_secs(30))
+                .build(),
+        }
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    fn call(&self, method: &str, params: Value) -> Result<Value> {
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": method,
+            "params": params,
+        });
+        let mut response: Value = self
+            .agent
+            .post(&self.url)
+            .send_json(request)
+            .map_err(|e| ClientError::Rpc(e.to_string()))?
+            .into_json()
+            .map_err(|e| ClientError::Rpc(e.to_string()))?;
+
+        if let Some(error) = response.get("error") {
+            return Err(transaction_error(error).unwrap_or_else(|| {
+                ClientError::Rpc(
+                    error["message"]
+                        .as_str()
+                        .unwrap_or("unknown error")
+                        .to_string(),
+                )
+            }));
+        }
+        Ok(response["result"].take())
+    }
+
+    pub fn get_account_data(&self, address: &Pubkey) -> Result<Option<Vec<u8>>> {
+        let result = self.call(
+            "getAccountInfo",
+            json!([address.to_string(), { "encoding": "base64", "commitment": "confirmed" }]),
+        )?;
+        match result["value"].as_object() {
+            Some(account) => decode_data(&account["data"]).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Fetches and decodes a program account
+    pub fn fetch<T: ProgramAccount>(&self, address: &Pubkey) -> Result<T> {
+        let data = self
+            .get_account_data(address)?
+            .ok_or(ClientError::AccountNotFound(*address))?;
+        decode(address, &data)
+    }
+
+    /// Fetches every account of type `T` in the current layout
+    pub fn fetch_all<T: ProgramAccount>(&self) -> Result<Vec<(Pubkey, T)>> {
+        self.program_accounts::<T>(T::LEN, vec![])?
+            .into_iter()
+            .map(|(address, data)| Ok((address, decode(&address, &data)?)))
+            .collect()
+    }
+
+    /// Addresses of accounts of type `T` still in an older layout
+    pub fn fetch_legacy_addresses<T: ProgramAccount>(&self) -> Result<Vec<Pubkey>> {
+        let mut addresses = vec![];
+        for &legacy_len in T::LEGACY_LENS {
+            addresses.extend(
+                self.program_accounts::<T>(legacy_len, vec![])?
+                    .into_iter()
+                    .map(|(address, _)| address),
+            );
+        }
+        Ok(addresses)
+    }
+
+    /// Address of the order with FIFO `sequence`, if its account still exists
+    pub fn find_order(&self, sequence: u64) -> Result<Option<Pubkey>> {
+        // `sequence` is the first field after the discriminator
+        let filter = json!({ "memcmp": { "offset": 8, "bytes": STANDARD.encode(sequence.to_le_bytes()), "encoding": "base64" } });
+        Ok(self
+            .program_accounts::<OrderState>(OrderState::LEN, vec![filter])?
+            .first()
+            .map(|(address, _)| *address))
+    }
+
+    /// Address of the order with `sequence` queued in `pool_id`, read from its
+    /// index. Falls back to a program account scan for orders submitted before
+    /// indexing.
+    pub fn find_queued_order(&self, pool_id: &Pubkey, sequence: u64) -> Result<Option<Pubkey>> {
+        let index = pda::order_index(pool_id, sequence).0;
+        match self.get_account_data(&index)? {
+            Some(data) => Ok(Some(decode::<OrderIndex>(&index, &data)?.order)),
+            None => self.find_order(sequence),
+        }
+    }
+
+    /// Orders linked before and after `order` in its pool queue
+    pub fn queue_neighbors(&self, order: &OrderState) -> Result<QueueNeighbors> {
+        let find = |sequence| match sequence {
+            0 => Ok(None),
+            sequence => self.find_queued_order(&order.pool_id, sequence),
+        };
+        Ok(QueueNeighbors {
+            prev: find(order.queue_prev)?,
+            next: find(order.queue_next)?,
+        })
+    }
+
+    fn program_accounts<T: ProgramAccount>(
+        &self,
+        data_size: usize,
+        extra_filters: Vec<Value>,
+    ) -> Result<Vec<(Pubkey, Vec<u8>)>> {
+        let mut filters = vec![
+            json!({ "dataSize": data_size }),
+            json!({ "memcmp": { "offset": 0, "bytes": STANDARD.encode(T::DISCRIMINATOR), "encoding": "base64" } }),
+        ];
+        filters.extend(extra_filters);
+        let result = self.call(
+            "getProgramAccounts",
+            json!([ID.to_string(), {
+                "encoding": "base64",
+                "commitment": "confirmed",
+                "filters": filters,
+            }]),
+        )?;
+
+        result
+            .as_array()
+            .ok_or_else(|| ClientError::Rpc("malformed getProgramAccounts result".to_string()))?
+            .iter()
+            .map(|entry| {
+                let address = parse_pubkey(&entry["pubkey"])?;
+                let data = decode_data(&entry["account"]["data"])?;
+                Ok((address, data))
+            })
+            .collect()
+    }
+
+    pub fn get_latest_blockhash(&self) -> Result<Hash> {
+        let result = self.call("getLatestBlockhash", json!([{ "commitment": "confirmed" }]))?;
+        let blockhash = result["value"]["blockhash"]
+            .as_str()
+            .ok_or_else(|| ClientError::Rpc("malformed getLatestBlockhash result".to_string()))?;
+        Hash::from_str(blockhash).map_err(|e| ClientError::Rpc(e.to_string()))
+    }
+
+    /// Sends a serialized, signed transaction and returns its signature
+    pub fn send_transaction(&self, wire_transaction: &[u8]) -> Result<String> {
+        let result = self.call(
+            "sendTransaction",
+            json!([STANDARD.encode(wire_transaction), { "encoding": "base64", "preflightCommitment": "confirmed" }]),
+        )?;
+        result
+            .as_str()
+            .map(str::to_string)
+            .ok_or_else(|| ClientError::Rpc("malformed sendTransaction result".to_string()))
+    }
+
+    /// Sends a transaction and waits until it is confirmed or fails
+    pub fn send_and_confirm_transaction(&self, wire_transaction: &[u8]) -> Result<String> {
+        let signature = self.send_transaction(wire_transaction)?;
+        let started = Instant::now();
+
+        while started.elapsed() < Duration::from_secs(60) {
+            let result = self.call(
+                "getSignatureStatuses",
+                json!([[signature], { "searchTransactionHistory": false }]),
+            )?;
+            let status = &result["value"][0];
+            if !status.is_null() {
+                if let Some(err) = status.get("err").filter(|err| !err.is_null()) {
+                    return Err(instruction_error(err)
+                        .unwrap_or_else(|| ClientError::Transaction(err.to_string())));
+                }
+                if matches!(
+                    status["confirmationStatus"].as_str(),
+                    Some("confirmed" | "finalized")
+                ) {
+                    return Ok(signature);
+                }
+            }
+            sleep(Duration::from_millis(500));
+        }
+
+        Err(ClientError::Transaction(format!(
+            "transaction {} was not confirmed in time",
+            signature
+        )))
+    }
+}
+
+fn parse_pubkey(value: &Value) -> Result<Pubkey> {
+    value
+        .as_str()
+        .and_then(|s| Pubkey::from_str(s).ok())
+        .ok_or_else(|| ClientError::Rpc("malformed pubkey".to_string()))
+}
+
+/// Account data comes back as `[base64, "base64"]`
+fn decode_data(value: &Value) -> Result<Vec<u8>> {
+    let encoded = value[0]
+        .as_str()
+        .ok_or_else(|| ClientError::Rpc("malformed account data".to_string()))?;
+    STANDARD
+        .decode(encoded)
+        .map_err(|e| ClientError::Decode(e.to_string()))
+}
+
+/// Preflight failures carry the transaction error in `error.data.err`
+fn transaction_error(error: &Value) -> Option<ClientError> {
+    let err = error.get("data")?.get("err")?;
+    Some(instruction_error(err).unwrap_or_else(|| {
+        ClientError::Transaction(error["message"].as_str().unwrap_or_default().to_string())
+    }))
+}
+
+/// Extracts `{"InstructionError": [index, {"Custom": code}]}` into a typed error
+fn instruction_error(err: &Value) -> Option<ClientError> {
+    let code = err
+        .get("InstructionError")?
+        .get(1)?
+        .get("Custom")?
+        .as_u64()?;
+    Some(ClientError::from_custom_code(code as u32))
+}
@@ -0,0 +1,79 @@
+//! The ed25519 signature verification precompile
+use crate::runtime::InstructionError;
+use anchor_lang::solana_program::ed25519_program;
+use anchor_lang::solana_program::instruction::Instruction;
+use ed25519_dalek::{Keypair, PublicKey, Signature, Signer, Verifier};
+
+const HEADER_LEN: usize = 2;
+const OFFSETS_LEN: usize = 14;
+const PUBLIC_KEY_LEN: usize = 32;
+const SIGNATURE_LEN: usize = 64;
+/// Instruction index meaning "this instruction"
+const THIS_INSTRUCTION: u16 = u16::MAX;
+
+/// Verification instruction for `message` signed by `keypair`, with the public
+/// key, signature and message inline
+pub fn new_ed25519_instruction(keypair: &Keypair, message: &[u8]) -> Instruction {
+    let public_key_offset = HEADER_LEN + OFFSETS_LEN;
+    let signature_offset = public_key_offset + PUBLIC_KEY_LEN;
+    let message_offset = signature_offset + SIGNATURE_LEN;
+
+    let mut data = vec![1, 0];
+    for field in [
+        signature_offset as u16,
+        THIS_INSTRUCTION,
+        public_key_offset as u16,
+        THIS_INSTRUCTION,
+        message_offset as u16,
+        message.len() as u16,
+        THIS_INSTRUCTION,
+    ] {
+        data.extend_from_slice(&field.to_le_bytes());
+    }
+    data.extend_from_slice(keypair.public.as_bytes());
+    data.extend_from_slice(&keypair.sign(message).to_bytes());
+    data.extend_from_slice(message);
+
+    Instruction {
+        program_id: ed25519_program::ID,
+        accounts: vec![],
+        data,
+    }
+}
+
+/// Check every signature an ed25519 program instruction carries
+pub(crate) fn verify(data: &[u8], instruction_datas: &[&[u8]]) -> Result<(), InstructionError> {
+    let invalid = || InstructionError::InvalidSignature;
+    let count = *data.first().ok_or_else(invalid)? as usize;
+
+    for index in 0..count {
+        let start = HEADER_LEN + index * OFFSETS_LEN;
+        let offsets = data.get(start..start + OFFSETS_LEN).ok_or_else(invalid)?;
+        let field = |at: usize| u16::from_le_bytes([offsets[at * 2], offsets[at * 2 + 1]]);
+
+        let slice = |instruction: u16, offset: u16, len: usize| {
+            let source = if instruction == THIS_INSTRUCTION {
+                data
+            } else {
+                instruction_datas
+                    .get(instruction as usize)
+                    .ok_or_else(invalid)?
+            };
+            source
+                .get(offset as usize..offset as usize + len)
+                .ok_or_else(invalid)
+        };
+
+        let signature = slice(field(1), field(0), SIGNATURE_LEN)?;
+        let public_key = slice(field(3), field(2), PUBLIC_KEY_LEN)?;
+        let message = slice(field(6), field(4), field(5) as usize)?;
+
+        let public_key = PublicKey::from_bytes(public_key).map_err(|_| invalid())?;
+        let signature = Signature::from_bytes(signature).map_err(|_| invalid())?;
+        public_key
+            .verify(message, &signature)
+            .map_err(|_| invalid())?;
+    }
+
+    Ok(())
+}
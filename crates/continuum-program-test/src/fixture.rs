@@ -0,0 +1,543 @@
+//! A Continuum deployment over the mock CP-Swap, with pools, funded users and
+//! a relayer, plus shortcuts for the order flows most tests go through
+use crate::runtime::{Runtime, TransactionError, TransactionMeta};
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::sysvar;
+use anchor_lang::{system_program, InstructionData, ToAccountMetas};
+use continuum_client::instructions::{self, QueueNeighbors};
+use continuum_client::pda;
+use continuum_cp_swap::state::{CpSwapPoolRegistry, FifoState, FillPolicy, OrderIndex, OrderState};
+use mock_cp_swap::state::{
+    AMM_CONFIG_SEED, AUTH_SEED, POOL_LP_MINT_SEED, POOL_SEED, POOL_VAULT_SEED,
+};
+use std::collections::HashMap;
+
+/// Lamports every fixture wallet starts with
+pub const WALLET_LAMPORTS: u64 = 100_000_000_000;
+
+/// Trade fee of the configs `create_pool` opens pools against, 0.25%
+pub const DEFAULT_TRADE_FEE_RATE: u64 = 2_500;
+
+/// Vault and LP mint authority of every mock CP-Swap pool
+pub fn mock_authority() -> Pubkey {
+    Pubkey::find_program_address(&[AUTH_SEED], &mock_cp_swap::ID).0
+}
+
+/// Addresses of a mock CP-Swap pool, all derived from its config and mints
+#[derive(Clone, Debug)]
+pub struct Pool {
+    pub pool_state: Pubkey,
+    pub amm_config: Pubkey,
+    pub token_0_mint: Pubkey,
+    pub token_1_mint: Pubkey,
+    pub token_0_vault: Pubkey,
+    pub token_1_vault: Pubkey,
+    pub lp_mint: Pubkey,
+}
+
+impl Pool {
+    /// `token_0_mint` must sort before `token_1_mint`, as CP-Swap requires
+    pub fn derive(amm_config: Pubkey, token_0_mint: Pubkey, token_1_mint: Pubkey) -> Self {
+        let program_id = mock_cp_swap::ID;
+        let (pool_state, _) = Pubkey::find_program_address(
+            &[
+                POOL_SEED,
+                amm_config.as_ref(),
+                token_0_mint.as_ref(),
+                token_1_mint.as_ref(),
+            ],
+            &program_id,
+        );
+        let vault = |mint: &Pubkey| {
+            Pubkey::find_program_address(
+                &[POOL_VAULT_SEED, pool_state.as_ref(), mint.as_ref()],
+                &program_id,
+            )
+            .0
+        };
+        Self {
+            pool_state,
+            amm_config,
+            token_0_mint,
+            token_1_mint,
+            token_0_vault: vault(&token_0_mint),
+            token_1_vault: vault(&token_1_mint),
+            lp_mint: Pubkey::find_program_address(
+                &[POOL_LP_MINT_SEED, pool_state.as_ref()],
+                &program_id,
+            )
+            .0,
+        }
+    }
+
+    /// Continuum's authority over this pool
+    pub fn authority(&self) -> Pubkey {
+        pda::pool_authority(&self.pool_state).0
+    }
+
+    pub fn vault(&self, mint: &Pubkey) -> Pubkey {
+        if *mint == self.token_0_mint {
+            self.token_0_vault
+        } else {
+            self.token_1_vault
+        }
+    }
+
+    pub fn other_mint(&self, mint: &Pubkey) -> Pubkey {
+        if *mint == self.token_0_mint {
+            self.token_1_mint
+        } else {
+            self.token_0_mint
+        }
+    }
+
+    /// CP-Swap `initialize` accounts for a pool seeded by `creator`
+    pub fn initialize_accounts(
+        &self,
+        creator: &Pubkey,
+        creator_token_0: &Pubkey,
+        creator_token_1: &Pubkey,
+    ) -> Vec<AccountMeta> {
+        mock_cp_swap::accounts::Initialize {
+            creator: *creator,
+            amm_config: self.amm_config,
+            authority: mock_authority(),
+            pool_state: self.pool_state,
+            token_0_mint: self.token_0_mint,
+            token_1_mint: self.token_1_mint,
+            lp_mint: self.lp_mint,
+            creator_token_0: *creator_token_0,
+            creator_token_1: *creator_token_1,
+            creator_lp_token: anchor_spl::associated_token::get_associated_token_address(
+                creator,
+                &self.lp_mint,
+            ),
+            token_0_vault: self.token_0_vault,
+            token_1_vault: self.token_1_vault,
+            create_pool_fee: Pubkey::find_program_address(&[b"create_pool_fee"], &mock_cp_swap::ID)
+                .0,
+            observation_state: Pubkey::find_program_address(
+                &[b"observation", self.pool_state.as_ref()],
+                &mock_cp_swap::ID,
+            )
+            .0,
+            token_program: anchor_spl::token::ID,
+            token_0_program: anchor_spl::token::ID,
+            token_1_program: anchor_spl::token::ID,
+            associated_token_program: anchor_spl::associated_token::ID,
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+        }
+        .to_account_metas(None)
+    }
+
+    /// CP-Swap swap accounts for a swap out of `input_mint`, after the payer
+    /// and the two user token accounts
+    pub fn swap_accounts(&self, input_mint: &Pubkey) -> Vec<AccountMeta> {
+        let output_mint = self.other_mint(input_mint);
+        mock_cp_swap::accounts::Swap {
+            payer: self.authority(),
+            input_token_account: Pubkey::default(),
+            output_token_account: Pubkey::default(),
+            authority: mock_authority(),
+            pool_state: self.pool_state,
+            input_vault: self.vault(input_mint),
+            output_vault: self.vault(&output_mint),
+            token_program: anchor_spl::token::ID,
+            input_token_mint: *input_mint,
+            output_token_mint: output_mint,
+            amm_config: self.amm_config,
+        }
+        .to_account_metas(None)
+        .split_off(3)
+    }
+
+    /// CP-Swap `deposit` and `withdraw` accounts after the owner
+    pub fn liquidity_accounts(
+        &self,
+        owner_lp_token: &Pubkey,
+        token_0_account: &Pubkey,
+        token_1_account: &Pubkey,
+    ) -> Vec<AccountMeta> {
+        mock_cp_swap::accounts::Deposit {
+            owner: self.authority(),
+            authority: mock_authority(),
+            pool_state: self.pool_state,
+            owner_lp_token: *owner_lp_token,
+            token_0_account: *token_0_account,
+            token_1_account: *token_1_account,
+            token_0_vault: self.token_0_vault,
+            token_1_vault: self.token_1_vault,
+            token_program: anchor_spl::token::ID,
+            vault_0_mint: self.token_0_mint,
+            vault_1_mint: self.token_1_mint,
+            lp_mint: self.lp_mint,
+        }
+        .to_account_metas(None)
+        .split_off(1)
+    }
+}
+
+/// A funded wallet with one associated token account per mint
+#[derive(Clone, Debug)]
+pub struct User {
+    pub key: Pubkey,
+    token_accounts: HashMap<Pubkey, Pubkey>,
+}
+
+impl User {
+    pub fn token_account(&self, mint: &Pubkey) -> Pubkey {
+        *self
+            .token_accounts
+            .get(mint)
+            .unwrap_or_else(|| panic!("user {} has no account for mint {}", self.key, mint))
+    }
+}
+
+/// Continuum initialized, with an admin holding every role and a funded relayer
+pub struct Continuum {
+    pub runtime: Runtime,
+    pub admin: Pubkey,
+    pub relayer: Pubkey,
+    amm_config_count: u16,
+}
+
+impl Default for Continuum {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Continuum {
+    pub fn new() -> Self {
+        let mut runtime = Runtime::new();
+        let admin = Pubkey::new_unique();
+        let relayer = Pubkey::new_unique();
+        runtime.airdrop(&admin, WALLET_LAMPORTS);
+        runtime.airdrop(&relayer, WALLET_LAMPORTS);
+
+        runtime
+            .process_transaction(
+                &[
+                    instructions::initialize(&admin),
+                    instructions::initialize_admin_config(&admin),
+                ],
+                &[admin],
+            )
+            .expect("initialize Continuum");
+
+        Self {
+            runtime,
+            admin,
+            relayer,
+            amm_config_count: 0,
+        }
+    }
+
+    pub fn process(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &[Pubkey],
+    ) -> Result<TransactionMeta, TransactionError> {
+        self.runtime.process_transaction(instructions, signers)
+    }
+
+    pub fn fifo_state(&self) -> FifoState {
+        self.runtime
+            .get(&pda::fifo_state().0)
+            .expect("FIFO state is initialized")
+    }
+
+    pub fn order(&self, address: &Pubkey) -> OrderState {
+        self.runtime.get(address).expect("order account exists")
+    }
+
+    pub fn pool_registry(&self, pool_id: &Pubkey) -> CpSwapPoolRegistry {
+        self.runtime
+            .get(&pda::pool_registry(pool_id).0)
+            .expect("pool is registered")
+    }
+
+    /// Address of the order with `sequence` queued in `pool_id`, from its index
+    pub fn find_order(&self, pool_id: &Pubkey, sequence: u64) -> Option<Pubkey> {
+        self.runtime
+            .get::<OrderIndex>(&pda::order_index(pool_id, sequence).0)
+            .map(|index| index.order)
+    }
+
+    /// Last order in `pool_id`'s queue, needed to submit behind it
+    pub fn queue_tail(&self, pool_id: &Pubkey) -> Option<Pubkey> {
+        self.find_order(pool_id, self.pool_registry(pool_id).queue.tail_sequence)
+    }
+
+    /// Orders linked before and after `order` in its pool queue
+    pub fn queue_neighbors(&self, order: &OrderState) -> QueueNeighbors {
+        QueueNeighbors {
+            prev: self.find_order(&order.pool_id, order.queue_prev),
+            next: self.find_order(&order.pool_id, order.queue_next),
+        }
+    }
+
+    /// A new mock CP-Swap fee tier, created by the admin, who owns its
+    /// protocol and fund fees
+    pub fn create_amm_config(
+        &mut self,
+        trade_fee_rate: u64,
+        protocol_fee_rate: u64,
+        fund_fee_rate: u64,
+    ) -> Pubkey {
+        let index = self.amm_config_count;
+        self.amm_config_count += 1;
+        let (amm_config, _) = Pubkey::find_program_address(
+            &[AMM_CONFIG_SEED, &index.to_be_bytes()],
+            &mock_cp_swap::ID,
+        );
+
+        let admin = self.admin;
+        let ix = mock_instruction(
+            mock_cp_swap::accounts::CreateAmmConfig {
+                owner: admin,
+                amm_config,
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            mock_cp_swap::instruction::CreateAmmConfig {
+                index,
+                trade_fee_rate,
+                protocol_fee_rate,
+                fund_fee_rate,
+                create_pool_fee: 0,
+            },
+        );
+        self.process(&[ix], &[admin]).expect("create AMM config");
+        amm_config
+    }
+
+    /// A mock CP-Swap pool over two new mints, created by the admin through
+    /// `initialize_cp_swap_pool` and seeded with the given reserves
+    pub fn create_pool(&mut self, reserve_0: u64, reserve_1: u64) -> Pool {
+        let amm_config = self.create_amm_config(DEFAULT_TRADE_FEE_RATE, 0, 0);
+        self.create_pool_with_config(&amm_config, reserve_0, reserve_1)
+    }
+
+    /// Like `create_pool`, but against an existing fee tier
+    pub fn create_pool_with_config(
+        &mut self,
+        amm_config: &Pubkey,
+        reserve_0: u64,
+        reserve_1: u64,
+    ) -> Pool {
+        let (mint_a, mint_b) = (self.runtime.create_mint(6), self.runtime.create_mint(6));
+        let pool = Pool::derive(*amm_config, mint_a.min(mint_b), mint_a.max(mint_b));
+        self.initialize_pool(&pool, reserve_0, reserve_1);
+        pool
+    }
+
+    /// Like `create_pool`, but over existing mints
+    pub fn create_pool_with_mints(
+        &mut self,
+        mint_a: &Pubkey,
+        mint_b: &Pubkey,
+        reserve_a: u64,
+        reserve_b: u64,
+    ) -> Pool {
+        let amm_config = self.create_amm_config(DEFAULT_TRADE_FEE_RATE, 0, 0);
+        let pool = Pool::derive(amm_config, *mint_a.min(mint_b), *mint_a.max(mint_b));
+        let (reserve_0, reserve_1) = if pool.token_0_mint == *mint_a {
+            (reserve_a, reserve_b)
+        } else {
+            (reserve_b, reserve_a)
+        };
+        self.initialize_pool(&pool, reserve_0, reserve_1);
+        pool
+    }
+
+    fn initialize_pool(&mut self, pool: &Pool, reserve_0: u64, reserve_1: u64) {
+        let admin = self.admin;
+        let creator_token_0 = self
+            .runtime
+            .create_token_account(&pool.token_0_mint, &admin);
+        let creator_token_1 = self
+            .runtime
+            .create_token_account(&pool.token_1_mint, &admin);
+        self.runtime.mint_tokens(&creator_token_0, reserve_0);
+        self.runtime.mint_tokens(&creator_token_1, reserve_1);
+
+        let ix = instructions::initialize_cp_swap_pool(
+            &admin,
+            &admin,
+            &pool.pool_state,
+            &mock_cp_swap::ID,
+            reserve_0,
+            reserve_1,
+            0,
+            pool.initialize_accounts(&admin, &creator_token_0, &creator_token_1),
+        );
+        self.process(&[ix], &[admin]).expect("initialize pool");
+    }
+
+    /// A wallet holding `amount` of each given mint, every token account
+    /// delegated to `pool`'s authority so Continuum can swap on its behalf
+    pub fn create_user(&mut self, pool: &Pool, balances: &[(Pubkey, u64)]) -> User {
+        self.create_user_with_key(Pubkey::new_unique(), pool, balances)
+    }
+
+    /// Like `create_user`, for a wallet whose key the test controls
+    pub fn create_user_with_key(
+        &mut self,
+        key: Pubkey,
+        pool: &Pool,
+        balances: &[(Pubkey, u64)],
+    ) -> User {
+        self.runtime.airdrop(&key, WALLET_LAMPORTS);
+        let mut user = User {
+            key,
+            token_accounts: HashMap::new(),
+        };
+        for mint in [pool.token_0_mint, pool.token_1_mint] {
+            self.add_token_account(&mut user, &mint, 0);
+        }
+        for (mint, amount) in balances {
+            let account = self.add_token_account(&mut user, mint, 0);
+            self.runtime.mint_tokens(&account, *amount);
+        }
+        self.approve_pool(&user, pool);
+        user
+    }
+
+    /// The user's account for `mint`, created with `amount` if it does not exist yet
+    pub fn add_token_account(&mut self, user: &mut User, mint: &Pubkey, amount: u64) -> Pubkey {
+        if let Some(account) = user.token_accounts.get(mint) {
+            return *account;
+        }
+        let account = self
+            .runtime
+            .create_associated_token_account(mint, &user.key);
+        self.runtime.mint_tokens(&account, amount);
+        user.token_accounts.insert(*mint, account);
+        account
+    }
+
+    /// Delegate the user's accounts for `pool`'s mints to its Continuum authority
+    pub fn approve_pool(&mut self, user: &User, pool: &Pool) {
+        for mint in [pool.token_0_mint, pool.token_1_mint, pool.lp_mint] {
+            if let Some(account) = user.token_accounts.get(&mint) {
+                self.runtime.approve(account, &pool.authority(), u64::MAX);
+            }
+        }
+    }
+
+    /// Submit an order at the tail of the queue and return its account
+    pub fn submit_order(
+        &mut self,
+        user: &User,
+        pool: &Pool,
+        amount_in: u64,
+        min_amount_out: u64,
+        is_base_input: bool,
+    ) -> Result<Pubkey, TransactionError> {
+        self.submit_order_with_fill_policy(
+            user,
+            pool,
+            amount_in,
+            min_amount_out,
+            is_base_input,
+            FillPolicy::FillOrKill,
+        )
+    }
+
+    /// Submit an order at the tail of the queue under `fill_policy`
+    pub fn submit_order_with_fill_policy(
+        &mut self,
+        user: &User,
+        pool: &Pool,
+        amount_in: u64,
+        min_amount_out: u64,
+        is_base_input: bool,
+        fill_policy: FillPolicy,
+    ) -> Result<Pubkey, TransactionError> {
+        let current_sequence = self.fifo_state().current_sequence;
+        let ix = instructions::submit_order(
+            &user.key,
+            &pool.pool_state,
+            current_sequence,
+            self.queue_tail(&pool.pool_state),
+            None,
+            amount_in,
+            min_amount_out,
+            is_base_input,
+            fill_policy,
+        );
+        self.process(&[ix], &[user.key])?;
+        Ok(pda::order(&user.key, current_sequence).0)
+    }
+
+    /// Execute a single-pool order swapping out of `input_mint`, as the relayer,
+    /// passing its stake if it has one
+    pub fn execute_order(
+        &mut self,
+        order_address: &Pubkey,
+        user: &User,
+        pool: &Pool,
+        input_mint: &Pubkey,
+    ) -> Result<TransactionMeta, TransactionError> {
+        self.fill_order(order_address, user, pool, input_mint, None)
+    }
+
+    /// Fill `fill_amount` of a single-pool order, or what is left of it
+    pub fn fill_order(
+        &mut self,
+        order_address: &Pubkey,
+        user: &User,
+        pool: &Pool,
+        input_mint: &Pubkey,
+        fill_amount: Option<u64>,
+    ) -> Result<TransactionMeta, TransactionError> {
+        let order = self.order(order_address);
+        let staked = self
+            .runtime
+            .get_account(&pda::relayer_stake(&self.relayer).0)
+            .is_some();
+        let ix = instructions::execute_order(
+            &self.relayer,
+            staked,
+            order_address,
+            &order,
+            self.queue_neighbors(&order),
+            &user.token_account(input_mint),
+            &user.token_account(&pool.other_mint(input_mint)),
+            &mock_cp_swap::ID,
+            fill_amount,
+            pool.swap_accounts(input_mint),
+        );
+        let relayer = self.relayer;
+        self.process(&[ix], &[relayer])
+    }
+
+    /// Cancel a pending order as its user
+    pub fn cancel_order(
+        &mut self,
+        order_address: &Pubkey,
+        bond_recipient: Option<Pubkey>,
+    ) -> Result<TransactionMeta, TransactionError> {
+        let order = self.order(order_address);
+        let ix = instructions::cancel_order(
+            &order.user,
+            order_address,
+            &order,
+            self.queue_neighbors(&order),
+            bond_recipient,
+        );
+        self.process(&[ix], &[order.user])
+    }
+}
+
+/// Instruction calling a mock CP-Swap instruction directly, outside Continuum
+pub fn mock_instruction(accounts: Vec<AccountMeta>, data: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: mock_cp_swap::ID,
+        accounts,
+        data: data.data(),
+    }
+}
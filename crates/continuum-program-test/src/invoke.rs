@@ -0,0 +1,450 @@
+//! Native program invocation. Every invocation gets its own input buffer in the
+//! SBF loader layout, so `AccountInfo::realloc` and `AccountInfo::assign` work
+//! exactly as on chain, and results are read back and checked from the buffer.
+use crate::runtime::{Account, InstructionError};
+use crate::system_program;
+use anchor_lang::prelude::{AccountInfo, Clock, Pubkey, Rent};
+use anchor_lang::solana_program::entrypoint::{
+    self, ProgramResult, MAX_PERMITTED_DATA_INCREASE, NON_DUP_MARKER,
+};
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::program_error::ProgramError;
+use anchor_lang::solana_program::program_stubs::{self, SyscallStubs};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::sync::Once;
+
+/// Top-level instruction plus four levels of CPI
+const MAX_INVOKE_DEPTH: usize = 5;
+const SUCCESS: u64 = 0;
+
+/// State of the transaction being processed on this thread
+pub(crate) struct InvokeContext {
+    /// Committed account state, visible to the next invocation
+    pub accounts: HashMap<Pubkey, Account>,
+    pub inner_instructions: Vec<Instruction>,
+    clock: Clock,
+    rent: Rent,
+    stack: Vec<Pubkey>,
+    return_data: (Pubkey, Vec<u8>),
+    cpi_error: Option<InstructionError>,
+}
+
+thread_local! {
+    static CONTEXT: RefCell<Option<InvokeContext>> = const { RefCell::new(None) };
+}
+
+fn with_context<R>(f: impl FnOnce(&mut InvokeContext) -> R) -> R {
+    CONTEXT.with(|context| {
+        f(context
+            .borrow_mut()
+            .as_mut()
+            .expect("no transaction is being processed on this thread"))
+    })
+}
+
+pub(crate) fn begin_transaction(accounts: HashMap<Pubkey, Account>, clock: Clock, rent: Rent) {
+    CONTEXT.with(|context| {
+        *context.borrow_mut() = Some(InvokeContext {
+            accounts,
+            inner_instructions: vec![],
+            clock,
+            rent,
+            stack: vec![],
+            return_data: (Pubkey::default(), vec![]),
+            cpi_error: None,
+        })
+    });
+}
+
+pub(crate) fn end_transaction() -> InvokeContext {
+    CONTEXT.with(|context| context.borrow_mut().take().expect("no transaction"))
+}
+
+pub(crate) fn set_account(address: Pubkey, account: Account) {
+    with_context(|context| context.accounts.insert(address, account));
+}
+
+pub(crate) fn total_lamports() -> u128 {
+    with_context(|context| {
+        context
+            .accounts
+            .values()
+            .map(|account| account.lamports as u128)
+            .sum()
+    })
+}
+
+pub(crate) fn take_cpi_error() -> Option<InstructionError> {
+    with_context(|context| {
+        context.stack.clear();
+        context.cpi_error.take()
+    })
+}
+
+pub(crate) fn return_data() -> Option<(Pubkey, Vec<u8>)> {
+    with_context(|context| {
+        let (program_id, data) = std::mem::take(&mut context.return_data);
+        (!data.is_empty()).then_some((program_id, data))
+    })
+}
+
+fn dispatch<'a>(
+    program_id: &Pubkey,
+    accounts: &'a [AccountInfo<'a>],
+    data: &[u8],
+) -> ProgramResult {
+    if *program_id == continuum_cp_swap::ID {
+        continuum_cp_swap::entry(program_id, accounts, data)
+    } else if *program_id == mock_cp_swap::ID {
+        mock_cp_swap::entry(program_id, accounts, data)
+    } else if *program_id == spl_token::ID {
+        spl_token::processor::Processor::process(program_id, accounts, data)
+    } else if *program_id == spl_associated_token_account::ID {
+        spl_associated_token_account::processor::process_instruction(program_id, accounts, data)
+    } else {
+        system_program::process_instruction(program_id, accounts, data)
+    }
+}
+
+/// Run `program_id` over `metas` against the committed account state and
+/// commit its changes once they pass the account rules
+pub(crate) fn process_instruction(
+    program_id: &Pubkey,
+    metas: &[AccountMeta],
+    data: &[u8],
+) -> Result<(), InstructionError> {
+    let mut input = with_context(|context| {
+        if !context
+            .accounts
+            .get(program_id)
+            .is_some_and(|account| account.executable)
+        {
+            return Err(InstructionError::UnknownProgram(*program_id));
+        }
+        if context.stack.contains(program_id) && context.stack.last() != Some(program_id) {
+            return Err(InstructionError::ReentrancyNotAllowed);
+        }
+        if context.stack.len() >= MAX_INVOKE_DEPTH {
+            return Err(InstructionError::CallDepthExceeded);
+        }
+        context.stack.push(*program_id);
+        Ok(Input::serialize(program_id, metas, data, &context.accounts))
+    })?;
+
+    let result = unsafe {
+        let (program_id, accounts, data) = entrypoint::deserialize(input.as_mut_ptr());
+        dispatch(program_id, &accounts, data)
+    };
+
+    with_context(|context| {
+        context.stack.pop();
+        result.map_err(InstructionError::Program)?;
+        input.commit(program_id, &mut context.accounts)
+    })
+}
+
+/// Account rules every invocation is held to
+fn verify(
+    program_id: &Pubkey,
+    address: &Pubkey,
+    is_writable: bool,
+    pre: &Account,
+    post: &Account,
+) -> Result<(), InstructionError> {
+    let owner_changed = pre.owner != post.owner;
+    let data_changed = pre.data != post.data;
+    if !owner_changed && !data_changed && pre.lamports == post.lamports {
+        return Ok(());
+    }
+    if !is_writable {
+        return Err(InstructionError::ReadonlyAccountModified(*address));
+    }
+    if pre.executable {
+        return Err(InstructionError::ExecutableModified(*address));
+    }
+    if (owner_changed || data_changed || post.lamports < pre.lamports) && pre.owner != *program_id {
+        return Err(InstructionError::ExternalAccountModified(*address));
+    }
+    Ok(())
+}
+
+fn committed(accounts: &HashMap<Pubkey, Account>, address: &Pubkey) -> Account {
+    accounts
+        .get(address)
+        .cloned()
+        .unwrap_or_else(|| Account::new(0, vec![], anchor_lang::system_program::ID))
+}
+
+struct SerializedAccount {
+    address: Pubkey,
+    is_writable: bool,
+    executable: bool,
+    /// Offset of the owner field, the fields that can change follow it
+    offset: usize,
+}
+
+/// An invocation's input buffer, 8-byte aligned like the loader's
+struct Input {
+    buffer: Vec<u64>,
+    accounts: Vec<SerializedAccount>,
+}
+
+impl Input {
+    fn serialize(
+        program_id: &Pubkey,
+        metas: &[AccountMeta],
+        data: &[u8],
+        accounts: &HashMap<Pubkey, Account>,
+    ) -> Self {
+        let mut bytes = Vec::new();
+        let mut serialized: Vec<SerializedAccount> = Vec::new();
+
+        bytes.extend_from_slice(&(metas.len() as u64).to_le_bytes());
+        for (index, meta) in metas.iter().enumerate() {
+            // Duplicates point at the first occurrence, which carries the merged privileges
+            let first = metas
+                .iter()
+                .position(|other| other.pubkey == meta.pubkey)
+                .unwrap();
+            if first < index {
+                bytes.push(first as u8);
+                bytes.extend_from_slice(&[0; 7]);
+                continue;
+            }
+
+            let (is_signer, is_writable) = metas
+                .iter()
+                .filter(|other| other.pubkey == meta.pubkey)
+                .fold((false, false), |(signer, writable), other| {
+                    (signer || other.is_signer, writable || other.is_writable)
+                });
+            let account = committed(accounts, &meta.pubkey);
+
+            bytes.push(NON_DUP_MARKER);
+            bytes.push(is_signer as u8);
+            bytes.push(is_writable as u8);
+            bytes.push(account.executable as u8);
+            bytes.extend_from_slice(&(account.data.len() as u32).to_le_bytes());
+            bytes.extend_from_slice(meta.pubkey.as_ref());
+            let offset = bytes.len();
+            bytes.extend_from_slice(account.owner.as_ref());
+            bytes.extend_from_slice(&account.lamports.to_le_bytes());
+            bytes.extend_from_slice(&(account.data.len() as u64).to_le_bytes());
+            bytes.extend_from_slice(&account.data);
+            bytes.resize(bytes.len() + MAX_PERMITTED_DATA_INCREASE, 0);
+            bytes.resize(bytes.len().next_multiple_of(8), 0);
+            bytes.extend_from_slice(&u64::MAX.to_le_bytes());
+
+            serialized.push(SerializedAccount {
+                address: meta.pubkey,
+                is_writable,
+                executable: account.executable,
+                offset,
+            });
+        }
+        bytes.extend_from_slice(&(data.len() as u64).to_le_bytes());
+        bytes.extend_from_slice(data);
+        bytes.extend_from_slice(program_id.as_ref());
+
+        let mut buffer = vec![0u64; bytes.len().div_ceil(8)];
+        bytemuck_copy(&mut buffer, &bytes);
+        Self {
+            buffer,
+            accounts: serialized,
+        }
+    }
+
+    fn as_mut_ptr(&mut self) -> *mut u8 {
+        self.buffer.as_mut_ptr() as *mut u8
+    }
+
+    fn bytes(&self) -> &[u8] {
+        unsafe {
+            std::slice::from_raw_parts(self.buffer.as_ptr() as *const u8, self.buffer.len() * 8)
+        }
+    }
+
+    fn read(&self, account: &SerializedAccount) -> Account {
+        let bytes = &self.bytes()[account.offset..];
+        let owner = Pubkey::try_from(&bytes[..32]).unwrap();
+        let lamports = u64::from_le_bytes(bytes[32..40].try_into().unwrap());
+        let data_len = u64::from_le_bytes(bytes[40..48].try_into().unwrap()) as usize;
+        Account {
+            lamports,
+            data: bytes[48..48 + data_len].to_vec(),
+            owner,
+            executable: account.executable,
+        }
+    }
+
+    fn commit(
+        &self,
+        program_id: &Pubkey,
+        accounts: &mut HashMap<Pubkey, Account>,
+    ) -> Result<(), InstructionError> {
+        for account in &self.accounts {
+            let pre = committed(accounts, &account.address);
+            verify(
+                program_id,
+                &account.address,
+                account.is_writable,
+                &pre,
+                &self.read(account),
+            )?;
+        }
+        for account in self.accounts.iter().filter(|account| account.is_writable) {
+            accounts.insert(account.address, self.read(account));
+        }
+        Ok(())
+    }
+}
+
+fn bytemuck_copy(buffer: &mut [u64], bytes: &[u8]) {
+    let target =
+        unsafe { std::slice::from_raw_parts_mut(buffer.as_mut_ptr() as *mut u8, buffer.len() * 8) };
+    target[..bytes.len()].copy_from_slice(bytes);
+}
+
+fn account_from_info(info: &AccountInfo) -> Account {
+    Account {
+        lamports: info.lamports(),
+        data: info.data.borrow().to_vec(),
+        owner: *info.owner,
+        executable: info.executable,
+    }
+}
+
+/// Bring the caller's view of `info` in line with the committed state
+fn update_info(info: &AccountInfo, account: &Account) -> Result<(), ProgramError> {
+    if account_from_info(info) == *account {
+        return Ok(());
+    }
+    **info.try_borrow_mut_lamports()? = account.lamports;
+    if *info.owner != account.owner {
+        info.assign(&account.owner);
+    }
+    info.realloc(account.data.len(), false)?;
+    info.try_borrow_mut_data()?.copy_from_slice(&account.data);
+    Ok(())
+}
+
+fn invoke(
+    instruction: &Instruction,
+    account_infos: &[AccountInfo],
+    signers_seeds: &[&[&[u8]]],
+) -> Result<(), InstructionError> {
+    let caller = with_context(|context| *context.stack.last().expect("CPI outside a program"));
+    let pda_signers = signers_seeds
+        .iter()
+        .map(|seeds| Pubkey::create_program_address(seeds, &caller))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| InstructionError::InvalidSeeds)?;
+
+    let mut callee_infos: Vec<&AccountInfo> = Vec::new();
+    for meta in &instruction.accounts {
+        let info = account_infos
+            .iter()
+            .find(|info| *info.key == meta.pubkey)
+            .ok_or(InstructionError::MissingAccount(meta.pubkey))?;
+        if (meta.is_writable && !info.is_writable)
+            || (meta.is_signer && !info.is_signer && !pda_signers.contains(&meta.pubkey))
+        {
+            return Err(InstructionError::PrivilegeEscalation(meta.pubkey));
+        }
+        if !callee_infos.iter().any(|other| other.key == info.key) {
+            callee_infos.push(info);
+        }
+    }
+
+    // The caller's changes so far are checked and become visible to the callee
+    with_context(|context| {
+        for info in &callee_infos {
+            let post = account_from_info(info);
+            let pre = committed(&context.accounts, info.key);
+            verify(&caller, info.key, info.is_writable, &pre, &post)?;
+            if info.is_writable {
+                context.accounts.insert(*info.key, post);
+            }
+        }
+        context.inner_instructions.push(instruction.clone());
+        Ok(())
+    })?;
+
+    process_instruction(
+        &instruction.program_id,
+        &instruction.accounts,
+        &instruction.data,
+    )?;
+
+    // And the callee's changes become visible to the caller
+    with_context(|context| {
+        for info in callee_infos.iter().filter(|info| info.is_writable) {
+            update_info(info, &committed(&context.accounts, info.key))
+                .map_err(InstructionError::Program)?;
+        }
+        Ok(())
+    })
+}
+
+struct NativeStubs;
+
+impl SyscallStubs for NativeStubs {
+    fn sol_invoke_signed(
+        &self,
+        instruction: &Instruction,
+        account_infos: &[AccountInfo],
+        signers_seeds: &[&[&[u8]]],
+    ) -> ProgramResult {
+        invoke(instruction, account_infos, signers_seeds).map_err(|error| {
+            let program_error = match &error {
+                InstructionError::Program(error) => error.clone(),
+                _ => ProgramError::InvalidArgument,
+            };
+            // The innermost failure is the one the transaction reports
+            with_context(|context| {
+                context.cpi_error.get_or_insert(error);
+            });
+            program_error
+        })
+    }
+
+    fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
+        with_context(|context| unsafe { *(var_addr as *mut Clock) = context.clock.clone() });
+        SUCCESS
+    }
+
+    fn sol_get_rent_sysvar(&self, var_addr: *mut u8) -> u64 {
+        with_context(|context| unsafe { *(var_addr as *mut Rent) = context.rent.clone() });
+        SUCCESS
+    }
+
+    fn sol_get_return_data(&self) -> Option<(Pubkey, Vec<u8>)> {
+        with_context(|context| {
+            let (program_id, data) = &context.return_data;
+            (!data.is_empty()).then(|| (*program_id, data.clone()))
+        })
+    }
+
+    fn sol_set_return_data(&self, data: &[u8]) {
+        with_context(|context| {
+            let program_id = *context.stack.last().expect("return data outside a program");
+            context.return_data = (program_id, data.to_vec());
+        })
+    }
+
+    fn sol_get_stack_height(&self) -> u64 {
+        with_context(|context| context.stack.len() as u64)
+    }
+
+    fn sol_remaining_compute_units(&self) -> u64 {
+        u64::MAX
+    }
+}
+
+pub(crate) fn install_syscall_stubs() {
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| {
+        program_stubs::set_syscall_stubs(Box::new(NativeStubs));
+    });
+}
@@ -0,0 +1,47 @@
+//! Offline test runtime for the Continuum CP-Swap program.
+//!
+//! Programs run natively inside the test process. Each invocation serializes its
+//! accounts into the same input buffer the SBF loader builds and hands it to the
+//! program's entrypoint. Cross-program invocations, sysvars and return data are
+//! routed back into the runtime through `program_stubs`. Account ownership and
+//! privilege rules are enforced on every invocation.
+//!
+//! Loaded programs: Continuum, the mock CP-Swap (at the CP-Swap program id),
+//! SPL Token, the associated token program and the system program. Ed25519
+//! signature verification instructions are checked natively. Compute units are
+//! not metered.
+//!
+//! # Fidelity
+//!
+//! This is an emulator, not the Solana runtime. solana-program-test is only
+//! published for Solana 1.18 in the dependency set this workspace builds
+//! against, which cannot link with the Solana 2.x crates Anchor 0.31 uses, and
+//! LiteSVM and mollusk need the program's SBF build, which the offline
+//! toolchain cannot produce. Tests here therefore do not cover:
+//!
+//! - SBF execution limits: the 4 KiB stack frame, the 32 KiB heap, the compute
+//!   unit limit and the verifier. A handler that overflows its stack frame on
+//!   chain can pass here.
+//! - Loader and runtime behavior beyond the checks in `invoke`: transaction
+//!   size and account locks, rent collection, sysvars other than the clock,
+//!   rent and instructions sysvars, and the real system program, which
+//!   `system_program` reimplements for the instructions Anchor and SPL Token
+//!   use.
+//!
+//! Anything in those areas, and compute unit usage, needs an SBF build run on
+//! a local validator before a release. The emulator is maintained alongside
+//! the program: a builtin the program starts to call fails with
+//! `UnknownProgram` until it is loaded here, but a syscall without a stub in
+//! `invoke` silently falls back to the default `program_stubs` one, so new
+//! syscalls and sysvars must be stubbed here when the program starts to use
+//! them.
+
+mod ed25519;
+pub mod fixture;
+mod invoke;
+mod runtime;
+mod system_program;
+mod token;
+
+pub use ed25519::new_ed25519_instruction;
+pub use runtime::{Account, InstructionError, Runtime, TransactionError, TransactionMeta};
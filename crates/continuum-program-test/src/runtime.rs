@@ -0,0 +1,357 @@
+use crate::{ed25519, invoke};
+use anchor_lang::prelude::{Clock, Pubkey, Rent};
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::program_error::ProgramError;
+use anchor_lang::solana_program::{ed25519_program, sysvar};
+use anchor_lang::{system_program, AccountDeserialize, AnchorDeserialize, Event};
+use solana_instruction::{BorrowedAccountMeta, BorrowedInstruction};
+use std::collections::HashMap;
+use std::panic::{self, AssertUnwindSafe};
+
+const NATIVE_LOADER_ID: Pubkey =
+    anchor_lang::pubkey!("NativeLoader1111111111111111111111111111111");
+const BPF_LOADER_ID: Pubkey = anchor_lang::pubkey!("BPFLoader2111111111111111111111111111111111");
+const BPF_LOADER_UPGRADEABLE_ID: Pubkey =
+    anchor_lang::pubkey!("BPFLoaderUpgradeab1e11111111111111111111111");
+
+/// Unix timestamp of a fresh runtime's clock
+const GENESIS_TIMESTAMP: i64 = 1_700_000_000;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Account {
+    pub lamports: u64,
+    pub data: Vec<u8>,
+    pub owner: Pubkey,
+    pub executable: bool,
+}
+
+impl Account {
+    pub fn new(lamports: u64, data: Vec<u8>, owner: Pubkey) -> Self {
+        Self {
+            lamports,
+            data,
+            owner,
+            executable: false,
+        }
+    }
+}
+
+/// Why an instruction failed
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum InstructionError {
+    /// The program returned an error
+    Program(ProgramError),
+    /// The program panicked, e.g. on arithmetic overflow
+    ProgramPanicked(String),
+    /// No executable program is loaded at this address
+    UnknownProgram(Pubkey),
+    /// A CPI referenced an account the caller did not pass
+    MissingAccount(Pubkey),
+    /// A CPI asked for a signer or writable account the caller could not grant
+    PrivilegeEscalation(Pubkey),
+    /// A CPI signer seed set does not derive a valid program address
+    InvalidSeeds,
+    /// An account not marked writable was modified
+    ReadonlyAccountModified(Pubkey),
+    /// A program modified the data or owner of, or debited, an account it does not own
+    ExternalAccountModified(Pubkey),
+    /// An executable account was modified
+    ExecutableModified(Pubkey),
+    /// Lamports were created or destroyed
+    UnbalancedInstruction,
+    /// A program was invoked while already on the stack, other than by itself
+    ReentrancyNotAllowed,
+    CallDepthExceeded,
+    /// An ed25519 signature verification instruction failed
+    InvalidSignature,
+}
+
+impl InstructionError {
+    /// The custom program error code, Anchor and program errors included
+    pub fn custom_code(&self) -> Option<u32> {
+        match self {
+            Self::Program(ProgramError::Custom(code)) => Some(*code),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TransactionError {
+    /// An account is marked as signer but its key is not among the signers
+    MissingSigner(Pubkey),
+    /// The instruction at this index failed and the transaction was rolled back
+    InstructionError(u8, InstructionError),
+}
+
+impl TransactionError {
+    /// The custom program error code the failing instruction returned
+    pub fn custom_code(&self) -> Option<u32> {
+        match self {
+            Self::InstructionError(_, error) => error.custom_code(),
+            Self::MissingSigner(_) => None,
+        }
+    }
+}
+
+/// What a successful transaction left behind
+#[derive(Clone, Debug, Default)]
+pub struct TransactionMeta {
+    /// Every cross-program invocation, in execution order
+    pub inner_instructions: Vec<Instruction>,
+    /// Return data set by the last top-level instruction, and its program
+    pub return_data: Option<(Pubkey, Vec<u8>)>,
+}
+
+impl TransactionMeta {
+    /// Anchor events of type `T` emitted through `emit_cpi!`, in emission order
+    pub fn events<T: Event + AnchorDeserialize>(&self) -> Vec<T> {
+        self.inner_instructions
+            .iter()
+            .filter_map(|ix| ix.data.strip_prefix(anchor_lang::event::EVENT_IX_TAG_LE))
+            .filter_map(|data| data.strip_prefix(T::DISCRIMINATOR))
+            .map(|mut data| T::deserialize(&mut data).expect("malformed event"))
+            .collect()
+    }
+}
+
+/// Accounts, clock and rent of a single-node test ledger
+pub struct Runtime {
+    pub(crate) accounts: HashMap<Pubkey, Account>,
+    clock: Clock,
+    rent: Rent,
+}
+
+impl Default for Runtime {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Runtime {
+    pub fn new() -> Self {
+        invoke::install_syscall_stubs();
+
+        let mut runtime = Self {
+            accounts: HashMap::new(),
+            clock: Clock {
+                slot: 1,
+                epoch_start_timestamp: GENESIS_TIMESTAMP,
+                unix_timestamp: GENESIS_TIMESTAMP,
+                ..Clock::default()
+            },
+            rent: Rent::default(),
+        };
+
+        for (program_id, loader) in [
+            (continuum_cp_swap::ID, BPF_LOADER_UPGRADEABLE_ID),
+            (mock_cp_swap::ID, BPF_LOADER_UPGRADEABLE_ID),
+            (spl_token::ID, BPF_LOADER_ID),
+            (spl_associated_token_account::ID, BPF_LOADER_ID),
+            (system_program::ID, NATIVE_LOADER_ID),
+            (ed25519_program::ID, NATIVE_LOADER_ID),
+        ] {
+            runtime.accounts.insert(
+                program_id,
+                Account {
+                    lamports: 1,
+                    data: vec![],
+                    owner: loader,
+                    executable: true,
+                },
+            );
+        }
+
+        let rent_data = bincode::serialize(&runtime.rent).unwrap();
+        runtime.set_sysvar(sysvar::rent::ID, rent_data);
+        runtime.sync_clock();
+        runtime
+    }
+
+    pub fn get_account(&self, address: &Pubkey) -> Option<&Account> {
+        self.accounts.get(address)
+    }
+
+    pub fn set_account(&mut self, address: Pubkey, account: Account) {
+        self.accounts.insert(address, account);
+    }
+
+    /// Deserialize an Anchor account, `None` if it is missing or not a `T`
+    pub fn get<T: AccountDeserialize>(&self, address: &Pubkey) -> Option<T> {
+        let account = self.accounts.get(address)?;
+        T::try_deserialize(&mut account.data.as_slice()).ok()
+    }
+
+    pub fn lamports(&self, address: &Pubkey) -> u64 {
+        self.accounts
+            .get(address)
+            .map_or(0, |account| account.lamports)
+    }
+
+    /// Credit `lamports` to a system account, creating it if needed
+    pub fn airdrop(&mut self, address: &Pubkey, lamports: u64) {
+        self.accounts
+            .entry(*address)
+            .or_insert_with(|| Account::new(0, vec![], system_program::ID))
+            .lamports += lamports;
+    }
+
+    pub fn clock(&self) -> &Clock {
+        &self.clock
+    }
+
+    pub fn rent(&self) -> &Rent {
+        &self.rent
+    }
+
+    /// Move the clock forward, one slot per call
+    pub fn advance_clock(&mut self, seconds: i64) {
+        self.clock.unix_timestamp += seconds;
+        self.clock.slot += 1;
+        self.sync_clock();
+    }
+
+    fn sync_clock(&mut self) {
+        let data = bincode::serialize(&self.clock).unwrap();
+        self.set_sysvar(sysvar::clock::ID, data);
+    }
+
+    fn set_sysvar(&mut self, address: Pubkey, data: Vec<u8>) {
+        let lamports = self.rent.minimum_balance(data.len());
+        self.accounts
+            .insert(address, Account::new(lamports, data, sysvar::ID));
+    }
+
+    /// Execute `instructions` atomically. Any account marked as signer must be
+    /// listed in `signers`; signatures themselves are not checked.
+    pub fn process_transaction(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &[Pubkey],
+    ) -> Result<TransactionMeta, TransactionError> {
+        // Privileges are merged across the transaction, as in a compiled message
+        let mut privileges: HashMap<Pubkey, (bool, bool)> = HashMap::new();
+        for meta in instructions.iter().flat_map(|ix| &ix.accounts) {
+            let entry = privileges.entry(meta.pubkey).or_default();
+            entry.0 |= meta.is_signer;
+            entry.1 |= meta.is_writable;
+        }
+        if let Some((key, _)) = privileges
+            .iter()
+            .find(|(key, (is_signer, _))| *is_signer && !signers.contains(key))
+        {
+            return Err(TransactionError::MissingSigner(*key));
+        }
+        let compiled: Vec<Vec<AccountMeta>> = instructions
+            .iter()
+            .map(|ix| {
+                ix.accounts
+                    .iter()
+                    .map(|meta| {
+                        let (is_signer, is_writable) = privileges[&meta.pubkey];
+                        AccountMeta {
+                            pubkey: meta.pubkey,
+                            is_signer,
+                            is_writable,
+                        }
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let mut accounts = self.accounts.clone();
+        let mut instructions_sysvar = privileges
+            .contains_key(&sysvar::instructions::ID)
+            .then(|| instructions_sysvar_data(instructions, &compiled));
+
+        invoke::begin_transaction(accounts.clone(), self.clock.clone(), self.rent.clone());
+        let mut meta = TransactionMeta::default();
+
+        for (index, (ix, metas)) in instructions.iter().zip(&compiled).enumerate() {
+            let fail = |error| TransactionError::InstructionError(index as u8, error);
+
+            if let Some(data) = instructions_sysvar.as_mut() {
+                solana_instructions_sysvar::store_current_index_checked(data, index as u16)
+                    .unwrap();
+                invoke::set_account(
+                    sysvar::instructions::ID,
+                    Account::new(1, data.clone(), sysvar::ID),
+                );
+            }
+
+            if ix.program_id == ed25519_program::ID {
+                let datas: Vec<&[u8]> = instructions.iter().map(|ix| ix.data.as_slice()).collect();
+                if let Err(error) = ed25519::verify(&ix.data, &datas) {
+                    invoke::end_transaction();
+                    return Err(fail(error));
+                }
+                continue;
+            }
+
+            let lamports_before = invoke::total_lamports();
+            let result = panic::catch_unwind(AssertUnwindSafe(|| {
+                invoke::process_instruction(&ix.program_id, metas, &ix.data)
+            }))
+            .unwrap_or_else(|panic| {
+                let message = panic
+                    .downcast_ref::<String>()
+                    .cloned()
+                    .or_else(|| panic.downcast_ref::<&str>().map(|s| s.to_string()))
+                    .unwrap_or_default();
+                Err(InstructionError::ProgramPanicked(message))
+            });
+
+            // A failed CPI aborts the transaction even if its caller carried on
+            let result = match invoke::take_cpi_error() {
+                Some(error) => Err(error),
+                None => result,
+            };
+            let result = result.and_then(|()| {
+                if invoke::total_lamports() == lamports_before {
+                    Ok(())
+                } else {
+                    Err(InstructionError::UnbalancedInstruction)
+                }
+            });
+            if let Err(error) = result {
+                invoke::end_transaction();
+                return Err(fail(error));
+            }
+
+            meta.return_data = invoke::return_data();
+        }
+
+        let context = invoke::end_transaction();
+        meta.inner_instructions = context.inner_instructions;
+        accounts = context.accounts;
+        accounts.remove(&sysvar::instructions::ID);
+        // Accounts left without lamports are purged, like closed accounts on chain
+        accounts.retain(|_, account| account.lamports > 0);
+        self.accounts = accounts;
+
+        Ok(meta)
+    }
+}
+
+fn instructions_sysvar_data(
+    instructions: &[Instruction],
+    compiled: &[Vec<AccountMeta>],
+) -> Vec<u8> {
+    let borrowed: Vec<BorrowedInstruction> = instructions
+        .iter()
+        .zip(compiled)
+        .map(|(ix, metas)| BorrowedInstruction {
+            program_id: &ix.program_id,
+            accounts: metas
+                .iter()
+                .map(|meta| BorrowedAccountMeta {
+                    pubkey: &meta.pubkey,
+                    is_signer: meta.is_signer,
+                    is_writable: meta.is_writable,
+                })
+                .collect(),
+            data: &ix.data,
+        })
+        .collect();
+    solana_instructions_sysvar::construct_instructions_data(&borrowed)
+}
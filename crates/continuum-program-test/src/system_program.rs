@@ -0,0 +1,121 @@
+//! The system program instructions Anchor and the SPL programs rely on
+use anchor_lang::prelude::{AccountInfo, Pubkey};
+use anchor_lang::solana_program::entrypoint::ProgramResult;
+use anchor_lang::solana_program::program_error::ProgramError;
+
+/// `SystemError::AccountAlreadyInUse`
+const ACCOUNT_ALREADY_IN_USE: u32 = 0;
+/// `SystemError::ResultWithNegativeLamports`
+const RESULT_WITH_NEGATIVE_LAMPORTS: u32 = 1;
+
+const CREATE_ACCOUNT: u32 = 0;
+const ASSIGN: u32 = 1;
+const TRANSFER: u32 = 2;
+const ALLOCATE: u32 = 8;
+
+struct Reader<'a>(&'a [u8]);
+
+impl Reader<'_> {
+    fn take(&mut self, len: usize) -> Result<&[u8], ProgramError> {
+        if self.0.len() < len {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+        let (head, tail) = self.0.split_at(len);
+        self.0 = tail;
+        Ok(head)
+    }
+
+    fn u32(&mut self) -> Result<u32, ProgramError> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn u64(&mut self) -> Result<u64, ProgramError> {
+        Ok(u64::from_le_bytes(self.take(8)?.try_into().unwrap()))
+    }
+
+    fn pubkey(&mut self) -> Result<Pubkey, ProgramError> {
+        Ok(Pubkey::try_from(self.take(32)?).unwrap())
+    }
+}
+
+fn account<'a, 'info>(
+    accounts: &'a [AccountInfo<'info>],
+    index: usize,
+) -> Result<&'a AccountInfo<'info>, ProgramError> {
+    accounts
+        .get(index)
+        .ok_or(ProgramError::NotEnoughAccountKeys)
+}
+
+fn require_signer(account: &AccountInfo) -> ProgramResult {
+    if account.is_signer {
+        Ok(())
+    } else {
+        Err(ProgramError::MissingRequiredSignature)
+    }
+}
+
+fn transfer(from: &AccountInfo, to: &AccountInfo, lamports: u64) -> ProgramResult {
+    require_signer(from)?;
+    if !from.data_is_empty() {
+        return Err(ProgramError::InvalidArgument);
+    }
+    let remaining = from
+        .lamports()
+        .checked_sub(lamports)
+        .ok_or(ProgramError::Custom(RESULT_WITH_NEGATIVE_LAMPORTS))?;
+    **from.try_borrow_mut_lamports()? = remaining;
+    **to.try_borrow_mut_lamports()? += lamports;
+    Ok(())
+}
+
+fn allocate(account: &AccountInfo, space: u64) -> ProgramResult {
+    require_signer(account)?;
+    if !account.data_is_empty() || *account.owner != anchor_lang::system_program::ID {
+        return Err(ProgramError::Custom(ACCOUNT_ALREADY_IN_USE));
+    }
+    account.realloc(space as usize, true)
+}
+
+fn assign(account: &AccountInfo, owner: &Pubkey) -> ProgramResult {
+    if account.owner == owner {
+        return Ok(());
+    }
+    require_signer(account)?;
+    account.assign(owner);
+    Ok(())
+}
+
+pub(crate) fn process_instruction(
+    _program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    let mut data = Reader(data);
+    match data.u32()? {
+        CREATE_ACCOUNT => {
+            let (lamports, space, owner) = (data.u64()?, data.u64()?, data.pubkey()?);
+            let from = account(accounts, 0)?;
+            let to = account(accounts, 1)?;
+            if to.lamports() > 0 {
+                return Err(ProgramError::Custom(ACCOUNT_ALREADY_IN_USE));
+            }
+            allocate(to, space)?;
+            assign(to, &owner)?;
+            transfer(from, to, lamports)
+        }
+        ASSIGN => {
+            let owner = data.pubkey()?;
+            assign(account(accounts, 0)?, &owner)
+        }
+        TRANSFER => {
+            let lamports = data.u64()?;
+            transfer(account(accounts, 0)?, account(accounts, 1)?, lamports)
+        }
+        ALLOCATE => {
+            let space = data.u64()?;
+            allocate(account(accounts, 0)?, space)
+        }
+        _ => Err(ProgramError::InvalidInstructionData),
+    }
+}
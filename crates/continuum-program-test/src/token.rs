@@ -0,0 +1,94 @@
+//! SPL Token accounts written straight into the ledger, for test setup
+use crate::runtime::{Account, Runtime};
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::program_option::COption;
+use anchor_lang::solana_program::program_pack::Pack;
+use anchor_spl::associated_token::get_associated_token_address;
+use spl_token::state::{Account as TokenAccount, AccountState, Mint};
+
+impl Runtime {
+    fn write_packed<T: Pack>(&mut self, address: Pubkey, state: T) {
+        let mut data = vec![0; T::LEN];
+        state.pack_into_slice(&mut data);
+        let lamports = self.rent().minimum_balance(T::LEN);
+        self.set_account(address, Account::new(lamports, data, spl_token::ID));
+    }
+
+    fn read_packed<T: Pack>(&self, address: &Pubkey) -> T {
+        let account = self
+            .get_account(address)
+            .unwrap_or_else(|| panic!("missing token account {}", address));
+        T::unpack_from_slice(&account.data).expect("not an SPL Token account")
+    }
+
+    /// A mint without a mint authority, supply only grows through `mint_tokens`
+    pub fn create_mint(&mut self, decimals: u8) -> Pubkey {
+        let address = Pubkey::new_unique();
+        self.write_packed(
+            address,
+            Mint {
+                mint_authority: COption::None,
+                supply: 0,
+                decimals,
+                is_initialized: true,
+                freeze_authority: COption::None,
+            },
+        );
+        address
+    }
+
+    pub fn create_token_account(&mut self, mint: &Pubkey, owner: &Pubkey) -> Pubkey {
+        let address = Pubkey::new_unique();
+        self.write_token_account(address, mint, owner);
+        address
+    }
+
+    /// An empty token account at the owner's associated token address for `mint`
+    pub fn create_associated_token_account(&mut self, mint: &Pubkey, owner: &Pubkey) -> Pubkey {
+        let address = get_associated_token_address(owner, mint);
+        self.write_token_account(address, mint, owner);
+        address
+    }
+
+    fn write_token_account(&mut self, address: Pubkey, mint: &Pubkey, owner: &Pubkey) {
+        self.write_packed(
+            address,
+            TokenAccount {
+                mint: *mint,
+                owner: *owner,
+                state: AccountState::Initialized,
+                ..TokenAccount::default()
+            },
+        );
+    }
+
+    /// Credit `amount` to a token account and its mint's supply
+    pub fn mint_tokens(&mut self, token_account: &Pubkey, amount: u64) {
+        let mut account: TokenAccount = self.read_packed(token_account);
+        let mut mint: Mint = self.read_packed(&account.mint);
+        account.amount += amount;
+        mint.supply += amount;
+        self.write_packed(*token_account, account);
+        self.write_packed(account.mint, mint);
+    }
+
+    /// Let `delegate` move up to `amount` out of a token account
+    pub fn approve(&mut self, token_account: &Pubkey, delegate: &Pubkey, amount: u64) {
+        let mut account: TokenAccount = self.read_packed(token_account);
+        account.delegate = COption::Some(*delegate);
+        account.delegated_amount = amount;
+        self.write_packed(*token_account, account);
+    }
+
+    pub fn token_account(&self, address: &Pubkey) -> TokenAccount {
+        self.read_packed(address)
+    }
+
+    pub fn token_balance(&self, address: &Pubkey) -> u64 {
+        self.token_account(address).amount
+    }
+
+    pub fn mint_supply(&self, mint: &Pubkey) -> u64 {
+        self.read_packed::<Mint>(mint).supply
+    }
+}
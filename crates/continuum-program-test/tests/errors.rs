@@ -0,0 +1,46 @@
+//! The client decodes every program error code to its typed `ContinuumError`.
+//!
+//! Variants are read from the program's error declaration, so one added there
+//! but missing from the client's table fails here.
+use anchor_lang::error::ERROR_CODE_OFFSET;
+use continuum_client::ClientError;
+
+const ERRORS_RS: &str = include_str!("../../../programs/continuum-cp-swap/src/errors.rs");
+
+/// `ContinuumError` variant names in declaration order, so variant `i` has
+/// code `ERROR_CODE_OFFSET + i`
+fn declared_variants() -> Vec<&'static str> {
+    ERRORS_RS
+        .split_once("pub enum ContinuumError {")
+        .expect("ContinuumError is declared")
+        .1
+        .lines()
+        .map(str::trim)
+        .take_while(|line| *line != "}")
+        .filter_map(|line| line.strip_suffix(','))
+        .filter(|name| name.chars().all(|c| c.is_ascii_alphanumeric()))
+        .collect()
+}
+
+#[test]
+fn every_program_error_code_decodes_to_its_variant() {
+    let variants = declared_variants();
+    assert!(variants.len() > 1);
+
+    for (offset, name) in variants.iter().enumerate() {
+        let code = ERROR_CODE_OFFSET + offset as u32;
+        match ClientError::from_custom_code(code) {
+            ClientError::Program(error) => {
+                assert_eq!(format!("{error:?}"), *name);
+                assert_eq!(u32::from(error), code);
+            }
+            other => panic!("{name} (code {code}) decodes as {other:?}"),
+        }
+    }
+
+    let past_last = ERROR_CODE_OFFSET + variants.len() as u32;
+    assert!(matches!(
+        ClientError::from_custom_code(past_last),
+        ClientError::Custom(code) if code == past_last
+    ));
+}
@@ -0,0 +1,1354 @@
+//! Stateful fuzzing of every way in and out of the order queues: single-pool,
+//! partially filled, multi-hop and intent orders, amendments, cancellations,
+//! ring orders and immediate swaps from many users and relayers, checked
+//! against a model of the queues.
+//!
+//! The model alone decides which orders each execution passes over: older
+//! single-pool orders of its pool still pending, submitted in an earlier slot
+//! than the executed order. Honest relayers walk the queue from its head,
+//! failing orders that cannot execute, and must never pass one over. Other
+//! relayers execute any pending order, and reports against them must succeed
+//! exactly for the orders the model says they passed over. Executions must
+//! succeed exactly for the orders the model knows to be fillable.
+//! Invalid calls are mixed in: re-executing settled orders, partially filling
+//! fill-or-kill orders, amending filled orders, replaying intents and acting
+//! on other users' orders, which must all be refused without side effects.
+//! Set `PROPTEST_CASES` to run more sequences.
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::instruction::AccountMeta;
+use anchor_lang::AnchorSerialize;
+use continuum_client::instructions;
+use continuum_client::pda;
+use continuum_cp_swap::state::{
+    AdminConfig, ConfigChange, FillPolicy, OrderExecuted, OrderIntent, OrderPartiallyFilled,
+    OrderRing, OrderStatus, PoolConfigUpdate, RingOrderExecuted, UserPoolState, UserState,
+};
+use continuum_program_test::fixture::{Continuum, Pool, User, WALLET_LAMPORTS};
+use continuum_program_test::new_ed25519_instruction;
+use ed25519_dalek::{Keypair, PublicKey, SecretKey};
+use proptest::prelude::*;
+use std::collections::HashMap;
+
+/// Pools orders are queued in, trading A/B and C/D
+const POOLS: usize = 2;
+/// Pool trading B/E, the second hop of multi-hop orders from A to E
+const ROUTE_POOL: usize = 2;
+/// Pool holding the order ring
+const RING_POOL: usize = 0;
+const USERS: usize = 4;
+const RELAYERS: usize = 3;
+const RESERVE: u64 = 1_000_000_000;
+/// Enough that no user runs out of any token
+const BALANCE: u64 = 10_000_000_000;
+const STAKE: u64 = 1_000_000_000;
+/// Smallest order, large enough that half of it still swaps to a nonzero output
+const MIN_AMOUNT: u64 = 1_000;
+const MAX_AMOUNT: u64 = 2_000_000;
+
+#[derive(Clone, Debug)]
+enum Action {
+    Submit {
+        user: usize,
+        pool: usize,
+        zero_for_one: bool,
+        amount: u64,
+        is_base_input: bool,
+        /// Ask for more than the pool can give, so execution fails on slippage
+        unfillable: bool,
+        fill_policy: FillPolicy,
+    },
+    /// A relayer submits an order the user signed, for a fee
+    SubmitIntent {
+        user: usize,
+        pool: usize,
+        zero_for_one: bool,
+        amount: u64,
+        is_base_input: bool,
+        unfillable: bool,
+        relayer: usize,
+        relayer_fee: u64,
+    },
+    /// Swap A for E through the A/B and B/E pools
+    SubmitMultiHop {
+        user: usize,
+        amount: u64,
+    },
+    /// Amend an order as its owner, halving it in place or doubling it, which
+    /// moves it to the tail
+    Amend {
+        order: usize,
+        grow: bool,
+    },
+    /// Walk the queue of a pool from its head as an honest relayer
+    ExecuteHead {
+        relayer: usize,
+        pool: usize,
+    },
+    /// Execute any pending order, which need not be the head of its pool,
+    /// possibly filling half of it
+    ExecutePending {
+        relayer: usize,
+        order: usize,
+        partial: bool,
+    },
+    /// Try to execute an order that is no longer pending
+    ExecuteSettled {
+        relayer: usize,
+        order: usize,
+    },
+    /// Cancel an order, as its owner or as someone else
+    Cancel {
+        order: usize,
+        by_owner: bool,
+    },
+    SubmitRing {
+        user: usize,
+        amount: u64,
+    },
+    /// Try to execute any ring order, only the head can be
+    ExecuteRing {
+        relayer: usize,
+        order: usize,
+    },
+    CancelRing {
+        order: usize,
+        by_owner: bool,
+    },
+    SwapImmediate {
+        user: usize,
+        pool: usize,
+        zero_for_one: bool,
+        amount: u64,
+    },
+    AdvanceClock {
+        seconds: i64,
+    },
+}
+
+fn fill_policy() -> impl Strategy<Value = FillPolicy> {
+    prop_oneof![
+        2 => Just(FillPolicy::FillOrKill),
+        1 => Just(FillPolicy::PartialKeepRemainder),
+        1 => Just(FillPolicy::PartialReleaseRemainder),
+    ]
+}
+
+fn action() -> impl Strategy<Value = Action> {
+    prop_oneof![
+        4 => (
+            (0..USERS, 0..POOLS, any::<bool>(), MIN_AMOUNT..MAX_AMOUNT),
+            (any::<bool>(), prop::bool::weighted(0.15), fill_policy()),
+        )
+            .prop_map(
+                |((user, pool, zero_for_one, amount), (is_base_input, unfillable, fill_policy))| {
+                    Action::Submit {
+                        user,
+                        pool,
+                        zero_for_one,
+                        amount,
+                        is_base_input,
+                        unfillable,
+                        fill_policy,
+                    }
+                }
+            ),
+        1 => (
+            (0..USERS, 0..POOLS, any::<bool>(), MIN_AMOUNT..MAX_AMOUNT),
+            (any::<bool>(), prop::bool::weighted(0.15), 0..RELAYERS, 0..1_000u64),
+        )
+            .prop_map(
+                |((user, pool, zero_for_one, amount), (is_base_input, unfillable, relayer, fee))| {
+                    Action::SubmitIntent {
+                        user,
+                        pool,
+                        zero_for_one,
+                        amount,
+                        is_base_input,
+                        unfillable,
+                        relayer,
+                        relayer_fee: fee,
+                    }
+                }
+            ),
+        1 => (0..USERS, MIN_AMOUNT..MAX_AMOUNT)
+            .prop_map(|(user, amount)| Action::SubmitMultiHop { user, amount }),
+        2 => (any::<usize>(), any::<bool>())
+            .prop_map(|(order, grow)| Action::Amend { order, grow }),
+        3 => (0..RELAYERS, 0..POOLS)
+            .prop_map(|(relayer, pool)| Action::ExecuteHead { relayer, pool }),
+        3 => (0..RELAYERS, any::<usize>(), any::<bool>()).prop_map(|(relayer, order, partial)| {
+            Action::ExecutePending {
+                relayer,
+                order,
+                partial,
+            }
+        }),
+        1 => (0..RELAYERS, any::<usize>())
+            .prop_map(|(relayer, order)| Action::ExecuteSettled { relayer, order }),
+        2 => (any::<usize>(), prop::bool::weighted(0.8))
+            .prop_map(|(order, by_owner)| Action::Cancel { order, by_owner }),
+        1 => (0..USERS, MIN_AMOUNT..MAX_AMOUNT)
+            .prop_map(|(user, amount)| Action::SubmitRing { user, amount }),
+        1 => (0..RELAYERS, any::<usize>())
+            .prop_map(|(relayer, order)| Action::ExecuteRing { relayer, order }),
+        1 => (any::<usize>(), prop::bool::weighted(0.8))
+            .prop_map(|(order, by_owner)| Action::CancelRing { order, by_owner }),
+        1 => (0..USERS, 0..POOLS, any::<bool>(), 1..MAX_AMOUNT).prop_map(
+            |(user, pool, zero_for_one, amount)| Action::SwapImmediate {
+                user,
+                pool,
+                zero_for_one,
+                amount,
+            }
+        ),
+        1 => (1..120i64).prop_map(|seconds| Action::AdvanceClock { seconds }),
+    ]
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Status {
+    Pending,
+    Executed,
+    Cancelled,
+    Failed,
+    /// Amended to the tail, the order lives on in a new account
+    Replaced,
+}
+
+#[derive(Debug)]
+struct ModelOrder {
+    address: Pubkey,
+    sequence: u64,
+    /// Slot the order's current terms were submitted in
+    slot: u64,
+    user: usize,
+    pool: usize,
+    input_mint: Pubkey,
+    multi_hop: bool,
+    amount: u64,
+    limit: u64,
+    is_base_input: bool,
+    fill_policy: FillPolicy,
+    /// Whether the limit can never be met, no other order is
+    unfillable: bool,
+    /// Size filled so far, in the unit of `amount`
+    filled: u64,
+    filled_in: u64,
+    filled_out: u64,
+    status: Status,
+}
+
+#[derive(Debug)]
+struct RingOrder {
+    sequence: u64,
+    user: usize,
+    status: Status,
+}
+
+fn keypair(seed: u8) -> Keypair {
+    let secret = SecretKey::from_bytes(&[seed; 32]).unwrap();
+    let public = PublicKey::from(&secret);
+    Keypair { secret, public }
+}
+
+/// The order limit: the minimum output, or the maximum input of exact output orders
+fn limit(is_base_input: bool, unfillable: bool) -> u64 {
+    match (is_base_input, unfillable) {
+        (true, false) => 1,
+        (true, true) => RESERVE,
+        (false, false) => u64::MAX,
+        (false, true) => 1,
+    }
+}
+
+struct Harness {
+    world: Continuum,
+    pools: Vec<Pool>,
+    users: Vec<User>,
+    signers: Vec<Keypair>,
+    /// Each user's B account for the middle of multi-hop routes, delegated to
+    /// the B/E pool
+    hops: Vec<Pubkey>,
+    relayers: Vec<Pubkey>,
+    /// Relayer fee accounts by relayer and mint
+    fee_accounts: HashMap<(usize, Pubkey), Pubkey>,
+    reporter: Pubkey,
+    orders: Vec<ModelOrder>,
+    ring: Vec<RingOrder>,
+    /// FIFO sequence the model expects on chain
+    sequence: u64,
+    /// Last ring sequence the model expects on chain
+    ring_sequence: u64,
+    /// Next intent nonce of each user
+    nonces: Vec<u64>,
+    /// Supply of every traded mint, all of it held by users, relayers and
+    /// pool vaults
+    supplies: HashMap<Pubkey, u64>,
+}
+
+impl Harness {
+    fn new() -> Self {
+        let mut world = Continuum::new();
+        let mut pools: Vec<Pool> = (0..POOLS)
+            .map(|_| world.create_pool(RESERVE, RESERVE))
+            .collect();
+        let mint_b = pools[0].token_1_mint;
+        let mint_e = world.runtime.create_mint(6);
+        pools.push(world.create_pool_with_mints(&mint_b, &mint_e, RESERVE, RESERVE));
+        Self::update_ring_pool_config(&mut world, &pools[RING_POOL]);
+        let admin = world.admin;
+        world
+            .process(
+                &[instructions::initialize_order_ring(
+                    &admin,
+                    &admin,
+                    &pools[RING_POOL].pool_state,
+                )],
+                &[admin],
+            )
+            .expect("initialize order ring");
+
+        let signers: Vec<Keypair> = (1..=USERS as u8).map(keypair).collect();
+        let mut hops = vec![];
+        let users = signers
+            .iter()
+            .map(|signer| {
+                let key = Pubkey::new_from_array(signer.public.to_bytes());
+                let first = &pools[0];
+                let mut user = world.create_user_with_key(
+                    key,
+                    first,
+                    &[(first.token_0_mint, BALANCE), (first.token_1_mint, BALANCE)],
+                );
+                for pool in &pools[1..POOLS] {
+                    for mint in [pool.token_0_mint, pool.token_1_mint] {
+                        world.add_token_account(&mut user, &mint, BALANCE);
+                    }
+                    world.approve_pool(&user, pool);
+                }
+                // The user's B account stays delegated to the A/B pool, routes
+                // pass through another one
+                let route_pool = &pools[ROUTE_POOL];
+                world.add_token_account(&mut user, &mint_e, 0);
+                let hop = world.runtime.create_token_account(&mint_b, &key);
+                world
+                    .runtime
+                    .approve(&hop, &route_pool.authority(), u64::MAX);
+                hops.push(hop);
+                user
+            })
+            .collect();
+
+        let mut fee_accounts = HashMap::new();
+        let relayers = (0..RELAYERS)
+            .map(|index| {
+                let relayer = Pubkey::new_unique();
+                world.runtime.airdrop(&relayer, WALLET_LAMPORTS);
+                world
+                    .process(&[instructions::stake_relayer(&relayer, STAKE)], &[relayer])
+                    .expect("stake relayer");
+                for pool in &pools[..POOLS] {
+                    for mint in [pool.token_0_mint, pool.token_1_mint] {
+                        let account = world.runtime.create_token_account(&mint, &relayer);
+                        fee_accounts.insert((index, mint), account);
+                    }
+                }
+                relayer
+            })
+            .collect();
+        let reporter = Pubkey::new_unique();
+        world.runtime.airdrop(&reporter, WALLET_LAMPORTS);
+
+        let supplies = pools
+            .iter()
+            .flat_map(|pool| [pool.token_0_mint, pool.token_1_mint])
+            .map(|mint| (mint, world.runtime.mint_supply(&mint)))
+            .collect();
+
+        Self {
+            world,
+            pools,
+            users,
+            signers,
+            hops,
+            relayers,
+            fee_accounts,
+            reporter,
+            orders: vec![],
+            ring: vec![],
+            sequence: 0,
+            ring_sequence: 0,
+            nonces: vec![0; USERS],
+            supplies,
+        }
+    }
+
+    /// Ring orders need a TTL, long enough that nothing expires during a run
+    fn update_ring_pool_config(world: &mut Continuum, pool: &Pool) {
+        let admin = world.admin;
+        let admin_config: AdminConfig = world.runtime.get(&pda::admin_config().0).unwrap();
+        let change = ConfigChange::UpdatePoolConfig {
+            pool_id: pool.pool_state,
+            update: PoolConfigUpdate {
+                max_pending_per_user: None,
+                order_ttl: Some(1_000_000),
+                order_bond: None,
+                min_resting_period: None,
+                bond_slash_bps: None,
+                bond_recipient: None,
+                min_inclusion_delay: None,
+                reveal_window: None,
+                min_relayer_stake: None,
+                relayer_slash_bps: None,
+                self_execution_timeout: None,
+            },
+        };
+        let pool_id = change.pool_id();
+        world
+            .process(
+                &[instructions::queue_config_change(
+                    &admin,
+                    &admin,
+                    admin_config.change_count,
+                    change,
+                )],
+                &[admin],
+            )
+            .expect("queue config change");
+        world.runtime.advance_clock(admin_config.timelock_delay);
+        world
+            .process(
+                &[instructions::execute_config_change(
+                    admin_config.change_count,
+                    &admin,
+                    pool_id,
+                )],
+                &[admin],
+            )
+            .expect("execute config change");
+    }
+
+    fn input_mint(&self, pool: usize, zero_for_one: bool) -> Pubkey {
+        let pool = &self.pools[pool];
+        if zero_for_one {
+            pool.token_0_mint
+        } else {
+            pool.token_1_mint
+        }
+    }
+
+    fn apply(&mut self, action: &Action) {
+        match *action {
+            Action::Submit {
+                user,
+                pool,
+                zero_for_one,
+                amount,
+                is_base_input,
+                unfillable,
+                fill_policy,
+            } => self.submit(
+                user,
+                pool,
+                zero_for_one,
+                amount,
+                is_base_input,
+                unfillable,
+                fill_policy,
+            ),
+            Action::SubmitIntent {
+                user,
+                pool,
+                zero_for_one,
+                amount,
+                is_base_input,
+                unfillable,
+                relayer,
+                relayer_fee,
+            } => self.submit_intent(
+                user,
+                pool,
+                zero_for_one,
+                amount,
+                is_base_input,
+                unfillable,
+                relayer,
+                relayer_fee,
+            ),
+            Action::SubmitMultiHop { user, amount } => self.submit_multi_hop(user, amount),
+            Action::Amend { order, grow } => self.amend(order, grow),
+            Action::ExecuteHead { relayer, pool } => self.execute_head(relayer, pool),
+            Action::ExecutePending {
+                relayer,
+                order,
+                partial,
+            } => self.execute_pending(relayer, order, partial),
+            Action::ExecuteSettled { relayer, order } => self.execute_settled(relayer, order),
+            Action::Cancel { order, by_owner } => self.cancel(order, by_owner),
+            Action::SubmitRing { user, amount } => self.submit_ring(user, amount),
+            Action::ExecuteRing { relayer, order } => self.execute_ring(relayer, order),
+            Action::CancelRing { order, by_owner } => self.cancel_ring(order, by_owner),
+            Action::SwapImmediate {
+                user,
+                pool,
+                zero_for_one,
+                amount,
+            } => self.swap_immediate(user, pool, zero_for_one, amount),
+            Action::AdvanceClock { seconds } => self.world.runtime.advance_clock(seconds),
+        }
+    }
+
+    /// Check a new order on chain and add it to the model
+    #[allow(clippy::too_many_arguments)]
+    fn record_submission(
+        &mut self,
+        address: Pubkey,
+        user: usize,
+        pool: usize,
+        input_mint: Pubkey,
+        multi_hop: bool,
+        amount: u64,
+        is_base_input: bool,
+        unfillable: bool,
+        fill_policy: FillPolicy,
+    ) {
+        self.sequence += 1;
+        let order = self.world.order(&address);
+        assert_eq!(
+            order.sequence, self.sequence,
+            "order stores the new sequence"
+        );
+        assert_eq!(order.status, OrderStatus::Pending);
+        let slot = self.world.runtime.clock().slot;
+        assert_eq!(order.submitted_slot, slot);
+        self.orders.push(ModelOrder {
+            address,
+            sequence: order.sequence,
+            slot,
+            user,
+            pool,
+            input_mint,
+            multi_hop,
+            amount,
+            limit: order.min_amount_out,
+            is_base_input,
+            fill_policy,
+            unfillable,
+            filled: 0,
+            filled_in: 0,
+            filled_out: 0,
+            status: Status::Pending,
+        });
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn submit(
+        &mut self,
+        user: usize,
+        pool: usize,
+        zero_for_one: bool,
+        amount: u64,
+        is_base_input: bool,
+        unfillable: bool,
+        fill_policy: FillPolicy,
+    ) {
+        let address = self
+            .world
+            .submit_order_with_fill_policy(
+                &self.users[user],
+                &self.pools[pool],
+                amount,
+                limit(is_base_input, unfillable),
+                is_base_input,
+                fill_policy,
+            )
+            .expect("submission to an active pool succeeds");
+        let input_mint = self.input_mint(pool, zero_for_one);
+        self.record_submission(
+            address,
+            user,
+            pool,
+            input_mint,
+            false,
+            amount,
+            is_base_input,
+            unfillable,
+            fill_policy,
+        );
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn submit_intent(
+        &mut self,
+        user: usize,
+        pool: usize,
+        zero_for_one: bool,
+        amount: u64,
+        is_base_input: bool,
+        unfillable: bool,
+        relayer: usize,
+        relayer_fee: u64,
+    ) {
+        let input_mint = self.input_mint(pool, zero_for_one);
+        let source = self.users[user].token_account(&input_mint);
+        let fee_account = self.fee_accounts[&(relayer, input_mint)];
+        let intent = OrderIntent {
+            program_id: continuum_cp_swap::ID,
+            user: self.users[user].key,
+            pool_id: self.pools[pool].pool_state,
+            source,
+            amount_in: amount,
+            min_amount_out: limit(is_base_input, unfillable),
+            is_base_input,
+            relayer_fee,
+            nonce: self.nonces[user],
+            deadline: self.world.runtime.clock().unix_timestamp + 60,
+        };
+        let relayer_key = self.relayers[relayer];
+        let submit = |world: &Continuum, signer: &Keypair| {
+            vec![
+                new_ed25519_instruction(signer, &intent.try_to_vec().unwrap()),
+                instructions::submit_order_with_intent(
+                    &relayer_key,
+                    world.fifo_state().current_sequence,
+                    world.queue_tail(&intent.pool_id),
+                    &fee_account,
+                    intent.clone(),
+                ),
+            ]
+        };
+        let current_sequence = self.world.fifo_state().current_sequence;
+        let fee_before = self.world.runtime.token_balance(&fee_account);
+        let ixs = submit(&self.world, &self.signers[user]);
+        self.world
+            .process(&ixs, &[relayer_key])
+            .expect("signed intent is accepted");
+        assert_eq!(
+            self.world.runtime.token_balance(&fee_account),
+            fee_before + relayer_fee
+        );
+
+        // The same signature cannot be relayed twice
+        let replay = submit(&self.world, &self.signers[user]);
+        assert!(
+            self.world.process(&replay, &[relayer_key]).is_err(),
+            "intent replayed"
+        );
+
+        self.nonces[user] += 1;
+        let address = pda::order(&self.users[user].key, current_sequence).0;
+        self.record_submission(
+            address,
+            user,
+            pool,
+            input_mint,
+            false,
+            amount,
+            is_base_input,
+            unfillable,
+            FillPolicy::FillOrKill,
+        );
+    }
+
+    fn submit_multi_hop(&mut self, user: usize, amount: u64) {
+        let route = vec![self.pools[0].pool_state, self.pools[ROUTE_POOL].pool_state];
+        let current_sequence = self.world.fifo_state().current_sequence;
+        let ix = instructions::submit_multi_hop_order(
+            &self.users[user].key,
+            current_sequence,
+            self.world.queue_tail(&route[0]),
+            route,
+            amount,
+            1,
+        );
+        let key = self.users[user].key;
+        self.world
+            .process(&[ix], &[key])
+            .expect("routed submission succeeds");
+        let address = pda::order(&key, current_sequence).0;
+        let input_mint = self.pools[0].token_0_mint;
+        self.record_submission(
+            address,
+            user,
+            0,
+            input_mint,
+            true,
+            amount,
+            true,
+            false,
+            FillPolicy::FillOrKill,
+        );
+    }
+
+    fn amend(&mut self, pick: usize, grow: bool) {
+        let live: Vec<usize> = (0..self.orders.len())
+            .filter(|&index| self.orders[index].status != Status::Replaced)
+            .collect();
+        if live.is_empty() {
+            return;
+        }
+        let index = live[pick % live.len()];
+        let order = &self.orders[index];
+        let (amount, limit) = if grow {
+            (order.amount * 2, order.limit)
+        } else {
+            let amount = (order.amount / 2).max(MIN_AMOUNT).min(order.amount);
+            // Exact output orders keep their place with a proportional input limit
+            let limit = if order.is_base_input {
+                order.limit
+            } else {
+                (order.limit as u128 * amount as u128 / order.amount as u128) as u64
+            };
+            (amount, limit)
+        };
+
+        let order_state = self.world.order(&order.address);
+        let neighbors = self.world.queue_neighbors(&order_state);
+        let current_sequence = self.world.fifo_state().current_sequence;
+        let tail = self.world.queue_tail(&order_state.pool_id).filter(|tail| {
+            *tail != order.address && Some(*tail) != neighbors.prev && Some(*tail) != neighbors.next
+        });
+        let key = self.users[order.user].key;
+        let ix = instructions::amend_order(
+            &key,
+            &order.address,
+            &order_state,
+            grow.then_some(current_sequence),
+            neighbors,
+            tail,
+            amount,
+            limit,
+        );
+        let result = self.world.process(&[ix], &[key]);
+        // Fills were priced against the current terms
+        let should_succeed = order.status == Status::Pending && order.filled == 0;
+        assert_eq!(
+            result.is_ok(),
+            should_succeed,
+            "amend of {:?} order filled by {}",
+            order.status,
+            order.filled
+        );
+        if !should_succeed {
+            return;
+        }
+
+        let slot = self.world.runtime.clock().slot;
+        if grow {
+            self.sequence += 1;
+            let address = pda::order(&key, current_sequence).0;
+            assert!(
+                self.world
+                    .runtime
+                    .get_account(&self.orders[index].address)
+                    .is_none(),
+                "re-queued order left its old account"
+            );
+            let order = &mut self.orders[index];
+            order.status = Status::Replaced;
+            let moved = ModelOrder {
+                address,
+                sequence: self.sequence,
+                slot,
+                amount,
+                limit,
+                status: Status::Pending,
+                ..*order
+            };
+            self.orders.push(moved);
+        } else {
+            let order = &mut self.orders[index];
+            order.slot = slot;
+            order.amount = amount;
+            order.limit = limit;
+        }
+        let order = &self.orders[if grow { self.orders.len() - 1 } else { index }];
+        let on_chain = self.world.order(&order.address);
+        assert_eq!(on_chain.sequence, order.sequence);
+        assert_eq!(on_chain.submitted_slot, slot);
+        assert_eq!(
+            (on_chain.amount_in, on_chain.min_amount_out),
+            (amount, limit)
+        );
+    }
+
+    /// The user accounts an order swaps from and into
+    fn order_accounts(&self, index: usize) -> (Pubkey, Pubkey) {
+        let order = &self.orders[index];
+        let user = &self.users[order.user];
+        let output_mint = self.pools[order.pool].other_mint(&order.input_mint);
+        let destination = if order.multi_hop {
+            user.token_account(&self.pools[ROUTE_POOL].other_mint(&output_mint))
+        } else {
+            user.token_account(&output_mint)
+        };
+        (user.token_account(&order.input_mint), destination)
+    }
+
+    /// Execute, or fill `fill_amount` of, an order and record the fill in the
+    /// model. Returns whether the program accepted it.
+    fn execute(&mut self, relayer: usize, index: usize, fill_amount: Option<u64>) -> bool {
+        let (source, destination) = self.order_accounts(index);
+        let order = &self.orders[index];
+        let pool = &self.pools[order.pool];
+        let order_state = self.world.order(&order.address);
+        let neighbors = self.world.queue_neighbors(&order_state);
+        let executor = self.relayers[relayer];
+        let ix = if order.multi_hop {
+            let route_pool = &self.pools[ROUTE_POOL];
+            instructions::execute_multi_hop_order(
+                &executor,
+                true,
+                &order.address,
+                &order_state,
+                neighbors,
+                &source,
+                &destination,
+                &mock_cp_swap::ID,
+                &[self.hops[order.user]],
+                vec![
+                    pool.swap_accounts(&order.input_mint),
+                    route_pool.swap_accounts(&pool.other_mint(&order.input_mint)),
+                ],
+            )
+        } else {
+            instructions::execute_order(
+                &executor,
+                true,
+                &order.address,
+                &order_state,
+                neighbors,
+                &source,
+                &destination,
+                &mock_cp_swap::ID,
+                fill_amount,
+                pool.swap_accounts(&order.input_mint),
+            )
+        };
+        let balances = |world: &Continuum| {
+            (
+                world.runtime.token_balance(&source),
+                world.runtime.token_balance(&destination),
+            )
+        };
+        let before = balances(&self.world);
+        let Ok(meta) = self.world.process(&[ix], &[executor]) else {
+            assert_eq!(
+                balances(&self.world),
+                before,
+                "failed execution moved tokens"
+            );
+            return false;
+        };
+
+        let after = balances(&self.world);
+        let (spent, received) = (before.0 - after.0, after.1 - before.1);
+        let order = &mut self.orders[index];
+        let fill = fill_amount.unwrap_or(order.amount - order.filled);
+        // Exact input fills take exactly their size, exact output ones deliver it
+        if order.is_base_input {
+            assert_eq!(spent, fill, "order {} spent its fill", order.sequence);
+        } else {
+            assert_eq!(received, fill, "order {} received its fill", order.sequence);
+        }
+        order.filled += fill;
+        order.filled_in += spent;
+        order.filled_out += received;
+
+        let settles = order.filled == order.amount
+            || order.fill_policy == FillPolicy::PartialReleaseRemainder;
+        if settles {
+            order.status = Status::Executed;
+            let events = meta.events::<OrderExecuted>();
+            assert_eq!(events.len(), 1);
+            assert_eq!(events[0].sequence, order.sequence);
+            assert_eq!(events[0].amount_in, order.filled_in);
+            assert_eq!(events[0].amount_out, order.filled_out);
+        } else {
+            let events = meta.events::<OrderPartiallyFilled>();
+            assert_eq!(events.len(), 1);
+            assert_eq!(events[0].sequence, order.sequence);
+            assert_eq!(events[0].amount_in, spent);
+            assert_eq!(events[0].amount_out, received);
+            assert_eq!(events[0].remaining, order.amount - order.filled);
+        }
+        true
+    }
+
+    /// Older single-pool orders of the executed order's pool that were pending
+    /// and submitted in an earlier slot. Orders submitted in the same slot
+    /// have no order between them, and routed orders are not held to theirs.
+    fn passed_over(&self, index: usize) -> Vec<usize> {
+        let executed = &self.orders[index];
+        (0..self.orders.len())
+            .filter(|&other| {
+                let order = &self.orders[other];
+                order.pool == executed.pool
+                    && order.status == Status::Pending
+                    && !order.multi_hop
+                    && order.sequence < executed.sequence
+                    && order.slot < executed.slot
+            })
+            .collect()
+    }
+
+    /// Report the relayer of the latest fill of an order for skipping each
+    /// other order of its pool. Only an order it passed over is evidence, and
+    /// each fill can be reported once.
+    fn check_reports(&mut self, relayer: usize, index: usize, passed_over: &[usize]) {
+        let executed = &self.orders[index];
+        let report = |world: &mut Continuum, reporter: Pubkey, other: &ModelOrder| {
+            let ix = instructions::report_misbehavior(
+                &reporter,
+                &self.relayers[relayer],
+                &executed.address,
+                &world.order(&executed.address),
+                &other.address,
+                &world.order(&other.address),
+            );
+            world.process(&[ix], &[reporter])
+        };
+        for (other, order) in self.orders.iter().enumerate() {
+            if other == index
+                || order.pool != executed.pool
+                || order.status == Status::Replaced
+                || passed_over.contains(&other)
+            {
+                continue;
+            }
+            assert!(
+                report(&mut self.world, self.reporter, order).is_err(),
+                "order {} reported as skipped for order {}",
+                order.sequence,
+                executed.sequence
+            );
+        }
+        if let Some(&skipped) = passed_over.first() {
+            let skipped = &self.orders[skipped];
+            report(&mut self.world, self.reporter, skipped).unwrap_or_else(|error| {
+                panic!(
+                    "order {} passed over for order {} cannot be reported: {error:?}",
+                    skipped.sequence, executed.sequence
+                )
+            });
+            assert!(
+                report(&mut self.world, self.reporter, skipped).is_err(),
+                "order {} reported twice",
+                executed.sequence
+            );
+        }
+    }
+
+    /// Walk the pool queue from its head as an honest relayer: execute the head,
+    /// or fail it when it cannot execute and move on to the next one
+    fn execute_head(&mut self, relayer: usize, pool: usize) {
+        loop {
+            let pool_id = self.pools[pool].pool_state;
+            let registry = self.world.pool_registry(&pool_id);
+            let Some(address) = self
+                .world
+                .find_order(&pool_id, registry.queue.head_sequence)
+            else {
+                return;
+            };
+            let index = self
+                .orders
+                .iter()
+                .position(|order| order.address == address && order.status != Status::Replaced)
+                .expect("queue head is a known order");
+            let passed_over = self.passed_over(index);
+            assert!(
+                passed_over.is_empty(),
+                "queue head {} is not the oldest pending order",
+                self.orders[index].sequence
+            );
+
+            let executed = self.execute(relayer, index, None);
+            assert_eq!(
+                executed, !self.orders[index].unfillable,
+                "execution of queue head {}",
+                self.orders[index].sequence
+            );
+            if executed {
+                self.check_reports(relayer, index, &passed_over);
+                return;
+            }
+
+            let order = &self.orders[index];
+            let order_state = self.world.order(&address);
+            let ix = instructions::fail_order(
+                &self.relayers[relayer],
+                true,
+                &address,
+                &order_state,
+                self.world.find_order(&pool_id, order_state.queue_next),
+                &registry,
+                &self.pools[pool].amm_config,
+            );
+            let executor = self.relayers[relayer];
+            self.world
+                .process(&[ix], &[executor])
+                .unwrap_or_else(|error| {
+                    panic!(
+                        "unfillable order {} cannot be failed: {error:?}",
+                        order.sequence
+                    )
+                });
+            self.orders[index].status = Status::Failed;
+        }
+    }
+
+    fn execute_pending(&mut self, relayer: usize, pick: usize, partial: bool) {
+        let pending: Vec<usize> = (0..self.orders.len())
+            .filter(|&index| self.orders[index].status == Status::Pending)
+            .collect();
+        if pending.is_empty() {
+            return;
+        }
+        let index = pending[pick % pending.len()];
+        let order = &self.orders[index];
+        let half = (order.amount - order.filled) / 2;
+        let fill_amount = (partial && !order.multi_hop && half > 0).then_some(half);
+        if fill_amount.is_some() && order.fill_policy == FillPolicy::FillOrKill {
+            assert!(
+                !self.execute(relayer, index, fill_amount),
+                "fill-or-kill order {} partially filled",
+                self.orders[index].sequence
+            );
+            return;
+        }
+
+        let passed_over = self.passed_over(index);
+        let executed = self.execute(relayer, index, fill_amount);
+        assert_eq!(
+            executed, !self.orders[index].unfillable,
+            "execution of order {}",
+            self.orders[index].sequence
+        );
+        if executed {
+            self.check_reports(relayer, index, &passed_over);
+        }
+    }
+
+    fn execute_settled(&mut self, relayer: usize, pick: usize) {
+        let settled: Vec<usize> = (0..self.orders.len())
+            .filter(|&index| {
+                !matches!(
+                    self.orders[index].status,
+                    Status::Pending | Status::Replaced
+                )
+            })
+            .collect();
+        if settled.is_empty() {
+            return;
+        }
+        let index = settled[pick % settled.len()];
+        assert!(
+            !self.execute(relayer, index, None),
+            "order {} executed after it was {:?}",
+            self.orders[index].sequence,
+            self.orders[index].status
+        );
+    }
+
+    fn cancel(&mut self, pick: usize, by_owner: bool) {
+        let live: Vec<usize> = (0..self.orders.len())
+            .filter(|&index| self.orders[index].status != Status::Replaced)
+            .collect();
+        if live.is_empty() {
+            return;
+        }
+        let index = live[pick % live.len()];
+        let order = &self.orders[index];
+        let signer = if by_owner {
+            self.users[order.user].key
+        } else {
+            self.users[(order.user + 1) % USERS].key
+        };
+
+        let order_state = self.world.order(&order.address);
+        let ix = instructions::cancel_order(
+            &signer,
+            &order.address,
+            &order_state,
+            self.world.queue_neighbors(&order_state),
+            None,
+        );
+        let result = self.world.process(&[ix], &[signer]);
+        let should_succeed = by_owner && order.status == Status::Pending;
+        assert_eq!(
+            result.is_ok(),
+            should_succeed,
+            "cancel of {:?} order by owner: {}",
+            order.status,
+            by_owner
+        );
+        if should_succeed {
+            self.orders[index].status = Status::Cancelled;
+        }
+    }
+
+    fn submit_ring(&mut self, user: usize, amount: u64) {
+        let key = self.users[user].key;
+        let ix = instructions::submit_ring_order(
+            &key,
+            &self.pools[RING_POOL].pool_state,
+            amount,
+            1,
+            true,
+        );
+        self.world
+            .process(&[ix], &[key])
+            .expect("ring submission succeeds");
+        self.ring_sequence += 1;
+        self.ring.push(RingOrder {
+            sequence: self.ring_sequence,
+            user,
+            status: Status::Pending,
+        });
+    }
+
+    /// Ring orders run strictly in submission order, only the oldest pending
+    /// one can execute
+    fn execute_ring(&mut self, relayer: usize, pick: usize) {
+        if self.ring.is_empty() {
+            return;
+        }
+        let index = pick % self.ring.len();
+        let order = &self.ring[index];
+        let user = &self.users[order.user];
+        let pool = &self.pools[RING_POOL];
+        let executor = self.relayers[relayer];
+        let ix = instructions::execute_ring_order(
+            &executor,
+            true,
+            &pool.pool_state,
+            order.sequence,
+            &user.key,
+            &user.token_account(&pool.token_0_mint),
+            &user.token_account(&pool.token_1_mint),
+            &mock_cp_swap::ID,
+            pool.swap_accounts(&pool.token_0_mint),
+        );
+        let result = self.world.process(&[ix], &[executor]);
+        let oldest = self
+            .ring
+            .iter()
+            .position(|order| order.status == Status::Pending);
+        let should_succeed = oldest == Some(index);
+        assert_eq!(
+            result.is_ok(),
+            should_succeed,
+            "execution of {:?} ring order {}",
+            order.status,
+            order.sequence
+        );
+        if let Ok(meta) = result {
+            assert_eq!(
+                meta.events::<RingOrderExecuted>()[0].sequence,
+                order.sequence
+            );
+            self.ring[index].status = Status::Executed;
+        }
+    }
+
+    fn cancel_ring(&mut self, pick: usize, by_owner: bool) {
+        if self.ring.is_empty() {
+            return;
+        }
+        let index = pick % self.ring.len();
+        let order = &self.ring[index];
+        let signer = if by_owner {
+            self.users[order.user].key
+        } else {
+            self.users[(order.user + 1) % USERS].key
+        };
+        let ix = instructions::cancel_ring_order(
+            &signer,
+            &self.pools[RING_POOL].pool_state,
+            order.sequence,
+            None,
+        );
+        let result = self.world.process(&[ix], &[signer]);
+        let should_succeed = by_owner && order.status == Status::Pending;
+        assert_eq!(
+            result.is_ok(),
+            should_succeed,
+            "cancel of {:?} ring order by owner: {}",
+            order.status,
+            by_owner
+        );
+        if should_succeed {
+            self.ring[index].status = Status::Cancelled;
+        }
+    }
+
+    fn swap_immediate(&mut self, user: usize, pool: usize, zero_for_one: bool, amount: u64) {
+        let input_mint = self.input_mint(pool, zero_for_one);
+        let user = &self.users[user];
+        let pool = &self.pools[pool];
+        let mut swap_accounts = vec![
+            AccountMeta::new(user.token_account(&input_mint), false),
+            AccountMeta::new(user.token_account(&pool.other_mint(&input_mint)), false),
+        ];
+        swap_accounts.extend(pool.swap_accounts(&input_mint));
+
+        let ix = instructions::swap_immediate(
+            &pool.pool_state,
+            &mock_cp_swap::ID,
+            amount,
+            1,
+            true,
+            swap_accounts,
+        );
+        let key = user.key;
+        // Immediate swaps take a sequence too, leaving a gap in the order queue
+        if self.world.process(&[ix], &[key]).is_ok() {
+            self.sequence += 1;
+        }
+    }
+
+    fn check_invariants(&self) {
+        // The FIFO sequence only moves forward, by one per queued or immediate swap
+        assert_eq!(self.world.fifo_state().current_sequence, self.sequence);
+
+        for order in &self.orders {
+            let expected = match order.status {
+                Status::Pending => OrderStatus::Pending,
+                Status::Executed => OrderStatus::Executed,
+                Status::Cancelled => OrderStatus::Cancelled,
+                Status::Failed => OrderStatus::Failed,
+                Status::Replaced => continue,
+            };
+            let on_chain = self.world.order(&order.address);
+            assert_eq!(on_chain.sequence, order.sequence);
+            assert_eq!(on_chain.status, expected, "order {}", order.sequence);
+        }
+
+        // The registry queue holds exactly the pending orders, oldest first,
+        // even after orders were taken out of its middle
+        for (index, pool) in self.pools.iter().enumerate() {
+            let pending: Vec<u64> = self
+                .orders
+                .iter()
+                .filter(|order| order.pool == index && order.status == Status::Pending)
+                .map(|order| order.sequence)
+                .collect();
+            let queue = self.world.pool_registry(&pool.pool_state).queue;
+            assert_eq!(queue.pending_count, pending.len() as u64);
+            assert_eq!(queue.head_sequence, pending.first().copied().unwrap_or(0));
+            assert_eq!(queue.tail_sequence, pending.last().copied().unwrap_or(0));
+
+            let mut linked = vec![];
+            let mut prev = 0;
+            let mut next = self.world.find_order(&pool.pool_state, queue.head_sequence);
+            while let Some(address) = next {
+                let order = self.world.order(&address);
+                assert_eq!(
+                    order.queue_prev, prev,
+                    "order {} links back",
+                    order.sequence
+                );
+                linked.push(order.sequence);
+                prev = order.sequence;
+                next = self.world.find_order(&pool.pool_state, order.queue_next);
+            }
+            assert_eq!(linked, pending);
+        }
+
+        // The ring head is the oldest pending ring order, cancelled records
+        // behind it wait until they reach the head
+        let ring: OrderRing = self
+            .world
+            .runtime
+            .get(&pda::order_ring(&self.pools[RING_POOL].pool_state).0)
+            .unwrap();
+        let ring_head = self
+            .ring
+            .iter()
+            .find(|order| order.status == Status::Pending)
+            .map_or(0, |order| order.sequence);
+        assert_eq!(ring.head_sequence(), ring_head);
+        assert_eq!(ring.head + ring.len, self.ring_sequence);
+
+        // Pending counters follow every order and ring order that settles
+        for (index, user) in self.users.iter().enumerate() {
+            let pending_in = |pool: usize| {
+                let queued = self
+                    .orders
+                    .iter()
+                    .filter(|order| {
+                        order.user == index && order.pool == pool && order.status == Status::Pending
+                    })
+                    .count();
+                let ringed = self
+                    .ring
+                    .iter()
+                    .filter(|order| {
+                        pool == RING_POOL && order.user == index && order.status == Status::Pending
+                    })
+                    .count();
+                (queued + ringed) as u32
+            };
+            let user_state: Option<UserState> =
+                self.world.runtime.get(&pda::user_state(&user.key).0);
+            assert_eq!(
+                user_state.map_or(0, |state| state.pending_count),
+                (0..self.pools.len()).map(pending_in).sum::<u32>(),
+                "pending orders of user {index}"
+            );
+            for (pool_index, pool) in self.pools.iter().enumerate() {
+                let user_pool_state: Option<UserPoolState> = self
+                    .world
+                    .runtime
+                    .get(&pda::user_pool_state(&user.key, &pool.pool_state).0);
+                assert_eq!(
+                    user_pool_state.map_or(0, |state| state.pending_count),
+                    pending_in(pool_index),
+                    "pending orders of user {index} in pool {pool_index}"
+                );
+            }
+        }
+
+        // Tokens only move between users, relayers and vaults
+        let mint_b = self.pools[0].token_1_mint;
+        for (mint, supply) in &self.supplies {
+            let hops = self.hops.iter().filter(|_| *mint == mint_b);
+            let fees = self
+                .fee_accounts
+                .iter()
+                .filter(|((_, fee_mint), _)| fee_mint == mint)
+                .map(|(_, account)| account);
+            let held: u64 = self
+                .users
+                .iter()
+                .map(|user| user.token_account(mint))
+                .chain(hops.chain(fees).copied())
+                .map(|account| self.world.runtime.token_balance(&account))
+                .sum();
+            let vaults: u64 = self
+                .pools
+                .iter()
+                .filter(|pool| pool.token_0_mint == *mint || pool.token_1_mint == *mint)
+                .map(|pool| self.world.runtime.token_balance(&pool.vault(mint)))
+                .sum();
+            assert_eq!(held + vaults, *supply, "mint {} not conserved", mint);
+        }
+    }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig {
+        cases: 24,
+        ..ProptestConfig::default()
+    })]
+
+    #[test]
+    fn queue_invariants_hold(actions in prop::collection::vec(action(), 1..60)) {
+        let mut harness = Harness::new();
+        for action in &actions {
+            harness.apply(action);
+            harness.check_invariants();
+        }
+    }
+}
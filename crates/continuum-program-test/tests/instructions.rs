@@ -0,0 +1,3029 @@
+//! Every Continuum instruction, run against the mock CP-Swap
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::instruction::AccountMeta;
+use anchor_lang::{AnchorDeserialize, AnchorSerialize, Discriminator};
+use continuum_client::instructions::{self, QueueNeighbors};
+use continuum_client::pda;
+use continuum_cp_swap::errors::ContinuumError;
+use continuum_cp_swap::state::*;
+use continuum_program_test::fixture::{
+    mock_instruction, Continuum, Pool, User, DEFAULT_TRADE_FEE_RATE,
+};
+use continuum_program_test::{new_ed25519_instruction, Account, TransactionError, TransactionMeta};
+use ed25519_dalek::{Keypair, PublicKey, SecretKey};
+use std::fmt::Debug;
+
+const RESERVE_0: u64 = 1_000_000_000;
+const RESERVE_1: u64 = 2_000_000_000;
+const BALANCE: u64 = 100_000_000;
+
+fn assert_error<T: Debug>(result: Result<T, TransactionError>, error: impl Into<u32>) {
+    let code = error.into();
+    let error = result.expect_err("transaction should fail");
+    assert_eq!(
+        error.custom_code(),
+        Some(code),
+        "unexpected error {:?}",
+        error
+    );
+}
+
+/// A deployment with one pool and a user holding both of its tokens
+fn setup() -> (Continuum, Pool, User) {
+    let mut world = Continuum::new();
+    let pool = world.create_pool(RESERVE_0, RESERVE_1);
+    let user = world.create_user(
+        &pool,
+        &[(pool.token_0_mint, BALANCE), (pool.token_1_mint, BALANCE)],
+    );
+    (world, pool, user)
+}
+
+fn reserves(world: &Continuum, pool: &Pool, input_mint: &Pubkey) -> (u64, u64) {
+    (
+        world.runtime.token_balance(&pool.vault(input_mint)),
+        world
+            .runtime
+            .token_balance(&pool.vault(&pool.other_mint(input_mint))),
+    )
+}
+
+/// Apply a config change through the timelock
+fn apply_config_change(world: &mut Continuum, change: ConfigChange) {
+    let admin = world.admin;
+    let admin_config: AdminConfig = world.runtime.get(&pda::admin_config().0).unwrap();
+    let pool_id = change.pool_id();
+    world
+        .process(
+            &[instructions::queue_config_change(
+                &admin,
+                &admin,
+                admin_config.change_count,
+                change,
+            )],
+            &[admin],
+        )
+        .unwrap();
+    world.runtime.advance_clock(admin_config.timelock_delay);
+    world
+        .process(
+            &[instructions::execute_config_change(
+                admin_config.change_count,
+                &admin,
+                pool_id,
+            )],
+            &[admin],
+        )
+        .unwrap();
+}
+
+fn update_pool_config(world: &mut Continuum, pool: &Pool, update: PoolConfigUpdate) {
+    apply_config_change(
+        world,
+        ConfigChange::UpdatePoolConfig {
+            pool_id: pool.pool_state,
+            update,
+        },
+    );
+}
+
+fn no_update() -> PoolConfigUpdate {
+    PoolConfigUpdate {
+        max_pending_per_user: None,
+        order_ttl: None,
+        order_bond: None,
+        min_resting_period: None,
+        bond_slash_bps: None,
+        bond_recipient: None,
+        min_inclusion_delay: None,
+        reveal_window: None,
+        min_relayer_stake: None,
+        relayer_slash_bps: None,
+        self_execution_timeout: None,
+    }
+}
+
+#[test]
+fn initialize_creates_fifo_state_and_admin_config() {
+    let mut world = Continuum::new();
+
+    let fifo_state = world.fifo_state();
+    assert_eq!(fifo_state.current_sequence, 0);
+    assert_eq!(fifo_state.admin, world.admin);
+    assert!(!fifo_state.emergency_pause);
+    assert_eq!(fifo_state.version.get(), STATE_VERSION);
+
+    let admin_config: AdminConfig = world.runtime.get(&pda::admin_config().0).unwrap();
+    assert_eq!(admin_config.super_admin, world.admin);
+    assert_eq!(admin_config.pauser, world.admin);
+    assert_eq!(admin_config.timelock_delay, DEFAULT_TIMELOCK_DELAY);
+
+    // Both accounts exist already
+    let admin = world.admin;
+    assert!(world
+        .process(&[instructions::initialize(&admin)], &[admin])
+        .is_err());
+}
+
+#[test]
+fn initialize_cp_swap_pool_registers_the_pool() {
+    let (world, pool, _) = setup();
+
+    let registry: CpSwapPoolRegistry = world
+        .runtime
+        .get(&pda::pool_registry(&pool.pool_state).0)
+        .unwrap();
+    assert_eq!(registry.pool_id, pool.pool_state);
+    assert_eq!(registry.token_0, pool.token_0_mint);
+    assert_eq!(registry.token_1, pool.token_1_mint);
+    assert_eq!(registry.token_0_vault, pool.token_0_vault);
+    assert_eq!(registry.token_1_vault, pool.token_1_vault);
+    assert_eq!(registry.continuum_authority, pool.authority());
+    assert_eq!(registry.status(), PoolStatus::Active);
+
+    let pool_state: mock_cp_swap::state::PoolState = world.runtime.get(&pool.pool_state).unwrap();
+    assert_eq!(pool_state.custom_authority, pool.authority());
+    assert_eq!(world.runtime.token_balance(&pool.token_0_vault), RESERVE_0);
+    assert_eq!(world.runtime.token_balance(&pool.token_1_vault), RESERVE_1);
+}
+
+#[test]
+fn initialize_cp_swap_pool_requires_pool_manager() {
+    let mut world = Continuum::new();
+    let outsider = Pubkey::new_unique();
+    world.runtime.airdrop(&outsider, 1_000_000_000);
+    let mint_a = world.runtime.create_mint(6);
+    let mint_b = world.runtime.create_mint(6);
+    let amm_config = world.create_amm_config(DEFAULT_TRADE_FEE_RATE, 0, 0);
+    let pool = Pool::derive(amm_config, mint_a.min(mint_b), mint_a.max(mint_b));
+    let token_0 = world
+        .runtime
+        .create_token_account(&pool.token_0_mint, &outsider);
+    let token_1 = world
+        .runtime
+        .create_token_account(&pool.token_1_mint, &outsider);
+
+    let ix = instructions::initialize_cp_swap_pool(
+        &outsider,
+        &outsider,
+        &pool.pool_state,
+        &mock_cp_swap::ID,
+        1_000,
+        1_000,
+        0,
+        pool.initialize_accounts(&outsider, &token_0, &token_1),
+    );
+    assert_error(
+        world.process(&[ix], &[outsider]),
+        ContinuumError::Unauthorized,
+    );
+}
+
+/// A pool created straight on the mock CP-Swap, outside Continuum. Its address
+/// is derived up front, so it can be created under its Continuum authority.
+fn create_external_pool(world: &mut Continuum, continuum_controlled: bool) -> Pool {
+    let creator = world.admin;
+    let mint_a = world.runtime.create_mint(6);
+    let mint_b = world.runtime.create_mint(6);
+    let amm_config = world.create_amm_config(DEFAULT_TRADE_FEE_RATE, 0, 0);
+    let pool = Pool::derive(amm_config, mint_a.min(mint_b), mint_a.max(mint_b));
+    let token_0 = world
+        .runtime
+        .create_token_account(&pool.token_0_mint, &creator);
+    let token_1 = world
+        .runtime
+        .create_token_account(&pool.token_1_mint, &creator);
+    world.runtime.mint_tokens(&token_0, RESERVE_0);
+    world.runtime.mint_tokens(&token_1, RESERVE_1);
+
+    let (authority_type, custom_authority) = if continuum_controlled {
+        (
+            mock_cp_swap::state::AUTHORITY_TYPE_CUSTOM,
+            Some(pool.authority()),
+        )
+    } else {
+        (mock_cp_swap::state::AUTHORITY_TYPE_DEFAULT, None)
+    };
+    let ix = mock_instruction(
+        pool.initialize_accounts(&creator, &token_0, &token_1),
+        mock_cp_swap::instruction::Initialize {
+            init_amount_0: RESERVE_0,
+            init_amount_1: RESERVE_1,
+            open_time: 0,
+            authority_type,
+            custom_authority,
+        },
+    );
+    world.process(&[ix], &[creator]).unwrap();
+    pool
+}
+
+#[test]
+fn register_existing_pool_accepts_pools_under_continuum_authority() {
+    let mut world = Continuum::new();
+    let admin = world.admin;
+
+    // A pool anyone can trade on directly is refused
+    let open_pool = create_external_pool(&mut world, false);
+    assert_error(
+        world.process(
+            &[instructions::register_existing_pool(
+                &admin,
+                &admin,
+                &open_pool.pool_state,
+            )],
+            &[admin],
+        ),
+        ContinuumError::InvalidPoolConfig,
+    );
+
+    let pool = create_external_pool(&mut world, true);
+    world
+        .process(
+            &[instructions::register_existing_pool(
+                &admin,
+                &admin,
+                &pool.pool_state,
+            )],
+            &[admin],
+        )
+        .unwrap();
+    let registry: CpSwapPoolRegistry = world
+        .runtime
+        .get(&pda::pool_registry(&pool.pool_state).0)
+        .unwrap();
+    assert_eq!(registry.continuum_authority, pool.authority());
+    assert_eq!(registry.token_0_vault, pool.token_0_vault);
+}
+
+#[test]
+fn submit_order_queues_at_the_tail() {
+    let (mut world, pool, user) = setup();
+
+    let first = world.submit_order(&user, &pool, 1_000, 1, true).unwrap();
+    let second = world.submit_order(&user, &pool, 2_000, 1, false).unwrap();
+
+    assert_eq!(world.fifo_state().current_sequence, 2);
+    let first = world.order(&first);
+    let second = world.order(&second);
+    assert_eq!((first.sequence, second.sequence), (1, 2));
+    assert_eq!(first.seed_sequence(), 0);
+    assert_eq!(first.status, OrderStatus::Pending);
+    assert_eq!(first.pool_id, pool.pool_state);
+    assert_eq!(second.amount_in, 2_000);
+    assert!(!second.is_base_input);
+
+    let user_state: UserState = world.runtime.get(&pda::user_state(&user.key).0).unwrap();
+    assert_eq!(user_state.pending_count, 2);
+    assert_eq!(user_state.order_count, 2);
+}
+
+#[test]
+fn pending_cap_counts_orders_per_pool() {
+    let (mut world, pool, user) = setup();
+    let mint_c = world.runtime.create_mint(6);
+    let second = world.create_pool_with_mints(&pool.token_1_mint, &mint_c, RESERVE_1, RESERVE_0);
+    for pool in [&pool, &second] {
+        update_pool_config(
+            &mut world,
+            pool,
+            PoolConfigUpdate {
+                max_pending_per_user: Some(1),
+                ..no_update()
+            },
+        );
+    }
+
+    let order = world.submit_order(&user, &pool, 1_000, 1, true).unwrap();
+    assert_error(
+        world.submit_order(&user, &pool, 1_000, 1, true),
+        ContinuumError::TooManyPendingOrders,
+    );
+    // An order in another pool does not count against this pool's cap
+    world.submit_order(&user, &second, 1_000, 1, true).unwrap();
+
+    let user_pool_state: UserPoolState = world
+        .runtime
+        .get(&pda::user_pool_state(&user.key, &pool.pool_state).0)
+        .unwrap();
+    assert_eq!(user_pool_state.pending_count, 1);
+    let user_state: UserState = world.runtime.get(&pda::user_state(&user.key).0).unwrap();
+    assert_eq!(user_state.pending_count, 2);
+
+    world
+        .execute_order(&order, &user, &pool, &pool.token_0_mint)
+        .unwrap();
+    world.submit_order(&user, &pool, 1_000, 1, true).unwrap();
+}
+
+#[test]
+fn execute_order_swaps_exact_input() {
+    let (mut world, pool, user) = setup();
+    let input = pool.token_0_mint;
+    let amount_in = 1_000_000;
+    let (reserve_in, reserve_out) = reserves(&world, &pool, &input);
+    let expected_out = mock_cp_swap::curve::swap_base_input(
+        amount_in,
+        reserve_in,
+        reserve_out,
+        DEFAULT_TRADE_FEE_RATE,
+    )
+    .unwrap();
+
+    let order = world
+        .submit_order(&user, &pool, amount_in, expected_out, true)
+        .unwrap();
+    let meta = world.execute_order(&order, &user, &pool, &input).unwrap();
+
+    let source = user.token_account(&input);
+    let destination = user.token_account(&pool.token_1_mint);
+    assert_eq!(world.runtime.token_balance(&source), BALANCE - amount_in);
+    assert_eq!(
+        world.runtime.token_balance(&destination),
+        BALANCE + expected_out
+    );
+
+    let executed = world.order(&order);
+    assert_eq!(executed.status, OrderStatus::Executed);
+    assert_eq!(
+        executed.executed_at,
+        Some(world.runtime.clock().unix_timestamp)
+    );
+    let user_state: UserState = world.runtime.get(&pda::user_state(&user.key).0).unwrap();
+    assert_eq!(user_state.pending_count, 0);
+
+    let events = meta.events::<OrderExecuted>();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].sequence, 1);
+    assert_eq!(events[0].executor, world.relayer);
+    assert_eq!(events[0].amount_in, amount_in);
+    assert_eq!(events[0].amount_out, expected_out);
+}
+
+#[test]
+fn execute_order_swaps_exact_output() {
+    let (mut world, pool, user) = setup();
+    let input = pool.token_1_mint;
+    let amount_out = 500_000;
+    let (reserve_in, reserve_out) = reserves(&world, &pool, &input);
+    let expected_in = mock_cp_swap::curve::swap_base_output(
+        amount_out,
+        reserve_in,
+        reserve_out,
+        DEFAULT_TRADE_FEE_RATE,
+    )
+    .unwrap();
+
+    // For exact output orders `min_amount_out` is the maximum input
+    let order = world
+        .submit_order(&user, &pool, amount_out, expected_in, false)
+        .unwrap();
+    world.execute_order(&order, &user, &pool, &input).unwrap();
+
+    assert_eq!(
+        world.runtime.token_balance(&user.token_account(&input)),
+        BALANCE - expected_in
+    );
+    assert_eq!(
+        world
+            .runtime
+            .token_balance(&user.token_account(&pool.token_0_mint)),
+        BALANCE + amount_out
+    );
+}
+
+#[test]
+fn execute_order_enforces_slippage_and_single_execution() {
+    let (mut world, pool, user) = setup();
+    let input = pool.token_0_mint;
+
+    let greedy = world
+        .submit_order(&user, &pool, 1_000, u64::MAX, true)
+        .unwrap();
+    assert_error(
+        world.execute_order(&greedy, &user, &pool, &input),
+        mock_cp_swap::errors::ErrorCode::ExceededSlippage,
+    );
+    assert_eq!(world.order(&greedy).status, OrderStatus::Pending);
+
+    let order = world.submit_order(&user, &pool, 1_000, 1, true).unwrap();
+    world.execute_order(&order, &user, &pool, &input).unwrap();
+    assert_error(
+        world.execute_order(&order, &user, &pool, &input),
+        ContinuumError::InvalidOrderStatus,
+    );
+}
+
+#[test]
+fn execute_order_rejects_a_mismatched_sequence() {
+    let (mut world, pool, user) = setup();
+    let order_address = world.submit_order(&user, &pool, 1_000, 1, true).unwrap();
+    let mut order = world.order(&order_address);
+    order.sequence += 1;
+
+    let ix = instructions::execute_order(
+        &world.relayer,
+        false,
+        &order_address,
+        &order,
+        QueueNeighbors::default(),
+        &user.token_account(&pool.token_0_mint),
+        &user.token_account(&pool.token_1_mint),
+        &mock_cp_swap::ID,
+        None,
+        pool.swap_accounts(&pool.token_0_mint),
+    );
+    let relayer = world.relayer;
+    assert_error(
+        world.process(&[ix], &[relayer]),
+        ContinuumError::InvalidSequence,
+    );
+}
+
+#[test]
+fn execute_order_waits_for_the_pool_inclusion_delay() {
+    let (mut world, pool, user) = setup();
+    update_pool_config(
+        &mut world,
+        &pool,
+        PoolConfigUpdate {
+            min_inclusion_delay: Some(2),
+            ..no_update()
+        },
+    );
+    let input = pool.token_0_mint;
+    let order = world.submit_order(&user, &pool, 1_000, 1, true).unwrap();
+    assert_eq!(
+        world.order(&order).submitted_slot,
+        world.runtime.clock().slot
+    );
+
+    // Neither in the submission slot nor the next one
+    for _ in 0..2 {
+        assert_error(
+            world.execute_order(&order, &user, &pool, &input),
+            ContinuumError::InclusionDelayNotElapsed,
+        );
+        world.runtime.advance_clock(1);
+    }
+    world.execute_order(&order, &user, &pool, &input).unwrap();
+    assert_eq!(world.order(&order).status, OrderStatus::Executed);
+}
+
+#[test]
+fn cancel_order_settles_the_order_for_its_user_only() {
+    let (mut world, pool, user) = setup();
+    let other = world.create_user(&pool, &[]);
+    let order_address = world.submit_order(&user, &pool, 1_000, 1, true).unwrap();
+    let order = world.order(&order_address);
+
+    assert!(world
+        .process(
+            &[instructions::cancel_order(
+                &other.key,
+                &order_address,
+                &order,
+                QueueNeighbors::default(),
+                None
+            )],
+            &[other.key],
+        )
+        .is_err());
+
+    let meta = world.cancel_order(&order_address, None).unwrap();
+    assert_eq!(world.order(&order_address).status, OrderStatus::Cancelled);
+    assert_eq!(meta.events::<OrderCancelled>()[0].sequence, order.sequence);
+
+    assert_error(
+        world.execute_order(&order_address, &user, &pool, &pool.token_0_mint),
+        ContinuumError::InvalidOrderStatus,
+    );
+}
+
+/// Rent held by an order's index, returned to the user when the order settles
+fn index_rent(world: &Continuum, order_address: &Pubkey) -> u64 {
+    let order = world.order(order_address);
+    world
+        .runtime
+        .lamports(&pda::order_index(&order.pool_id, order.sequence).0)
+}
+
+#[test]
+fn cancel_order_slashes_bond_before_resting_period() {
+    let (mut world, pool, user) = setup();
+    let treasury = Pubkey::new_unique();
+    world.runtime.airdrop(&treasury, 1_000_000_000);
+    update_pool_config(
+        &mut world,
+        &pool,
+        PoolConfigUpdate {
+            order_bond: Some(1_000_000),
+            min_resting_period: Some(60),
+            bond_slash_bps: Some(2_500),
+            bond_recipient: Some(treasury),
+            ..no_update()
+        },
+    );
+
+    let order_address = world.submit_order(&user, &pool, 1_000, 1, true).unwrap();
+    assert_eq!(world.order(&order_address).bond_lamports, 1_000_000);
+    let user_lamports = world.runtime.lamports(&user.key);
+    let index_rent = index_rent(&world, &order_address);
+
+    world.cancel_order(&order_address, Some(treasury)).unwrap();
+    assert_eq!(world.runtime.lamports(&treasury), 1_000_000_000 + 250_000);
+    assert_eq!(
+        world.runtime.lamports(&user.key),
+        user_lamports + 750_000 + index_rent
+    );
+}
+
+#[test]
+fn amend_order_keeps_or_loses_queue_priority() {
+    let (mut world, pool, user) = setup();
+    let order_address = world.submit_order(&user, &pool, 10_000, 100, true).unwrap();
+    let order = world.order(&order_address);
+
+    // Smaller and stricter keeps the order where it is
+    world
+        .process(
+            &[instructions::amend_order(
+                &user.key,
+                &order_address,
+                &order,
+                None,
+                QueueNeighbors::default(),
+                None,
+                5_000,
+                200,
+            )],
+            &[user.key],
+        )
+        .unwrap();
+    let amended = world.order(&order_address);
+    assert_eq!((amended.sequence, amended.amount_in), (1, 5_000));
+
+    // Anything else moves it to a new account at the tail
+    let current_sequence = world.fifo_state().current_sequence;
+    let meta = world
+        .process(
+            &[instructions::amend_order(
+                &user.key,
+                &order_address,
+                &amended,
+                Some(current_sequence),
+                QueueNeighbors::default(),
+                None,
+                20_000,
+                200,
+            )],
+            &[user.key],
+        )
+        .unwrap();
+    assert!(world.runtime.get_account(&order_address).is_none());
+    let requeued_address = pda::order(&user.key, current_sequence).0;
+    let requeued = world.order(&requeued_address);
+    assert_eq!((requeued.sequence, requeued.amount_in), (2, 20_000));
+    assert!(!meta.events::<OrderAmended>()[0].kept_queue_position);
+
+    world
+        .execute_order(&requeued_address, &user, &pool, &pool.token_0_mint)
+        .unwrap();
+}
+
+#[test]
+fn amend_exact_output_order_keeps_priority_only_for_a_proportional_limit() {
+    let (mut world, pool, user) = setup();
+    // 10_000 out for at most 20_000 in
+    let order_address = world
+        .submit_order(&user, &pool, 10_000, 20_000, false)
+        .unwrap();
+    let order = world.order(&order_address);
+
+    // Half the output for the same maximum input pays more per unit
+    let current_sequence = world.fifo_state().current_sequence;
+    let meta = world
+        .process(
+            &[instructions::amend_order(
+                &user.key,
+                &order_address,
+                &order,
+                Some(current_sequence),
+                QueueNeighbors::default(),
+                None,
+                5_000,
+                20_000,
+            )],
+            &[user.key],
+        )
+        .unwrap();
+    assert!(!meta.events::<OrderAmended>()[0].kept_queue_position);
+    let requeued_address = pda::order(&user.key, current_sequence).0;
+    let requeued = world.order(&requeued_address);
+    assert_eq!(requeued.sequence, 2);
+
+    // Shrinking the maximum input in proportion keeps the order's place
+    let meta = world
+        .process(
+            &[instructions::amend_order(
+                &user.key,
+                &requeued_address,
+                &requeued,
+                None,
+                QueueNeighbors::default(),
+                None,
+                2_500,
+                10_000,
+            )],
+            &[user.key],
+        )
+        .unwrap();
+    assert!(meta.events::<OrderAmended>()[0].kept_queue_position);
+    let amended = world.order(&requeued_address);
+    assert_eq!(
+        (amended.sequence, amended.amount_in, amended.min_amount_out),
+        (2, 2_500, 10_000)
+    );
+}
+
+fn queue_status(world: &mut Continuum, pool: &Pool) -> QueueStatus {
+    let ix = instructions::get_queue_status(&pool.pool_state);
+    let (_, data) = world.process(&[ix], &[]).unwrap().return_data.unwrap();
+    QueueStatus::try_from_slice(&data).unwrap()
+}
+
+#[test]
+fn queue_status_follows_every_way_out_of_the_queue() {
+    let (mut world, pool, user) = setup();
+    update_pool_config(
+        &mut world,
+        &pool,
+        PoolConfigUpdate {
+            order_ttl: Some(600),
+            ..no_update()
+        },
+    );
+    let start = world.runtime.clock().unix_timestamp;
+    let mut orders = vec![];
+    for _ in 0..4 {
+        orders.push(world.submit_order(&user, &pool, 1_000, 1, true).unwrap());
+        world.runtime.advance_clock(10);
+    }
+    assert_eq!(
+        queue_status(&mut world, &pool),
+        QueueStatus {
+            head_sequence: 1,
+            tail_sequence: 4,
+            pending_count: 4,
+            oldest_pending_at: start,
+        }
+    );
+
+    // Cancelling from the middle relinks the neighbors
+    world.cancel_order(&orders[1], None).unwrap();
+    assert_eq!(world.order(&orders[0]).queue_next, 3);
+    assert_eq!(world.order(&orders[2]).queue_prev, 1);
+    assert_eq!(queue_status(&mut world, &pool).pending_count, 3);
+
+    // Executing the head moves it, and the oldest timestamp, to the next order
+    world
+        .execute_order(&orders[0], &user, &pool, &pool.token_0_mint)
+        .unwrap();
+    let status = queue_status(&mut world, &pool);
+    assert_eq!(
+        (status.head_sequence, status.oldest_pending_at),
+        (3, start + 20)
+    );
+
+    // Neighbors must be the linked orders
+    world.runtime.advance_clock(600);
+    let last = world.order(&orders[3]);
+    let caller = world.relayer;
+    assert_error(
+        world.process(
+            &[instructions::expire_order(
+                &caller,
+                &orders[3],
+                &last,
+                QueueNeighbors::default(),
+            )],
+            &[caller],
+        ),
+        ContinuumError::InvalidQueueNeighbor,
+    );
+
+    // Re-queueing an amendment moves the order behind the current tail
+    let current_sequence = world.fifo_state().current_sequence;
+    let order = world.order(&orders[2]);
+    let neighbors = world.queue_neighbors(&order);
+    assert_eq!(neighbors.next, Some(orders[3]));
+    world
+        .process(
+            &[instructions::amend_order(
+                &user.key,
+                &orders[2],
+                &order,
+                Some(current_sequence),
+                neighbors,
+                None,
+                2_000,
+                1,
+            )],
+            &[user.key],
+        )
+        .unwrap();
+    let requeued = pda::order(&user.key, current_sequence).0;
+    let status = queue_status(&mut world, &pool);
+    assert_eq!(
+        (
+            status.head_sequence,
+            status.tail_sequence,
+            status.pending_count
+        ),
+        (4, 5, 2)
+    );
+
+    // Expiry empties the queue
+    world.runtime.advance_clock(600);
+    for address in [orders[3], requeued] {
+        let order = world.order(&address);
+        let neighbors = world.queue_neighbors(&order);
+        world
+            .process(
+                &[instructions::expire_order(
+                    &caller, &address, &order, neighbors,
+                )],
+                &[caller],
+            )
+            .unwrap();
+    }
+    assert_eq!(queue_status(&mut world, &pool), QueueStatus::default());
+}
+
+#[test]
+fn order_index_walks_a_pool_queue_without_knowing_users() {
+    let (mut world, pool, user) = setup();
+    let other_pool = world.create_pool(RESERVE_0, RESERVE_1);
+    let mut other = world.create_user(&pool, &[(pool.token_0_mint, BALANCE)]);
+    world.add_token_account(&mut other, &other_pool.token_0_mint, BALANCE);
+
+    let first = world.submit_order(&user, &pool, 1_000, 1, true).unwrap();
+    world
+        .submit_order(&other, &other_pool, 1_000, 1, true)
+        .unwrap();
+    let second = world.submit_order(&other, &pool, 2_000, 1, true).unwrap();
+    let third = world.submit_order(&user, &pool, 3_000, 1, true).unwrap();
+
+    // From the head, each index leads to the order and its link to the next sequence
+    let mut walked = vec![];
+    let mut sequence = world.pool_registry(&pool.pool_state).queue.head_sequence;
+    while sequence != 0 {
+        let index: OrderIndex = world
+            .runtime
+            .get(&pda::order_index(&pool.pool_state, sequence).0)
+            .unwrap();
+        walked.push(index.order);
+        sequence = world.order(&index.order).queue_next;
+    }
+    assert_eq!(walked, vec![first, second, third]);
+
+    // Re-queueing moves the index with the order
+    let current_sequence = world.fifo_state().current_sequence;
+    let order = world.order(&first);
+    let neighbors = world.queue_neighbors(&order);
+    let queue_tail = world.queue_tail(&pool.pool_state);
+    world
+        .process(
+            &[instructions::amend_order(
+                &user.key,
+                &first,
+                &order,
+                Some(current_sequence),
+                neighbors,
+                queue_tail,
+                5_000,
+                1,
+            )],
+            &[user.key],
+        )
+        .unwrap();
+    assert!(world
+        .runtime
+        .get_account(&pda::order_index(&pool.pool_state, order.sequence).0)
+        .is_none());
+    assert_eq!(
+        world.find_order(&pool.pool_state, current_sequence + 1),
+        Some(pda::order(&user.key, current_sequence).0)
+    );
+
+    // Settled orders close their index to the user
+    let user_lamports = world.runtime.lamports(&user.key);
+    world
+        .execute_order(&second, &other, &pool, &pool.token_0_mint)
+        .unwrap();
+    world.cancel_order(&third, None).unwrap();
+    for order in [second, third] {
+        let sequence = world.order(&order).sequence;
+        assert!(world
+            .runtime
+            .get_account(&pda::order_index(&pool.pool_state, sequence).0)
+            .is_none());
+    }
+    assert!(world.runtime.lamports(&user.key) > user_lamports);
+}
+
+fn initialize_order_ring(world: &mut Continuum, pool: &Pool) {
+    update_pool_config(
+        world,
+        pool,
+        PoolConfigUpdate {
+            order_ttl: Some(600),
+            ..no_update()
+        },
+    );
+    let admin = world.admin;
+    world
+        .process(
+            &[instructions::initialize_order_ring(
+                &admin,
+                &admin,
+                &pool.pool_state,
+            )],
+            &[admin],
+        )
+        .unwrap();
+}
+
+fn order_ring(world: &Continuum, pool: &Pool) -> OrderRing {
+    world
+        .runtime
+        .get(&pda::order_ring(&pool.pool_state).0)
+        .unwrap()
+}
+
+fn execute_ring_order(
+    world: &mut Continuum,
+    pool: &Pool,
+    user: &User,
+    expected_sequence: u64,
+) -> Result<TransactionMeta, TransactionError> {
+    let ix = instructions::execute_ring_order(
+        &world.relayer,
+        false,
+        &pool.pool_state,
+        expected_sequence,
+        &user.key,
+        &user.token_account(&pool.token_0_mint),
+        &user.token_account(&pool.token_1_mint),
+        &mock_cp_swap::ID,
+        pool.swap_accounts(&pool.token_0_mint),
+    );
+    let relayer = world.relayer;
+    world.process(&[ix], &[relayer])
+}
+
+#[test]
+fn order_ring_queues_compact_orders_in_one_account() {
+    let (mut world, pool, user) = setup();
+    let other = world.create_user(&pool, &[(pool.token_0_mint, BALANCE)]);
+    initialize_order_ring(&mut world, &pool);
+    let address = pda::order_ring(&pool.pool_state).0;
+    assert_eq!(
+        world.runtime.get_account(&address).unwrap().data.len(),
+        8 + std::mem::size_of::<OrderRing>()
+    );
+
+    let submit = |world: &mut Continuum, user: &User, amount_in: u64| {
+        let ix = instructions::submit_ring_order(&user.key, &pool.pool_state, amount_in, 1, true);
+        world.process(&[ix], &[user.key])
+    };
+    let fifo_sequence = world.fifo_state().current_sequence;
+    submit(&mut world, &user, 1_000).unwrap();
+    submit(&mut world, &other, 2_000).unwrap();
+    let meta = submit(&mut world, &user, 3_000).unwrap();
+    assert_eq!(meta.events::<RingOrderSubmitted>()[0].sequence, 3);
+    assert_eq!(world.fifo_state().current_sequence, fifo_sequence);
+
+    // Only the head executes, with its own user's token accounts
+    assert_error(
+        execute_ring_order(&mut world, &pool, &user, 2),
+        ContinuumError::InvalidSequence,
+    );
+    assert_error(
+        execute_ring_order(&mut world, &pool, &other, 1),
+        ContinuumError::Unauthorized,
+    );
+    let before = world
+        .runtime
+        .token_balance(&user.token_account(&pool.token_0_mint));
+    let meta = execute_ring_order(&mut world, &pool, &user, 1).unwrap();
+    let executed = &meta.events::<RingOrderExecuted>()[0];
+    assert_eq!((executed.sequence, executed.amount_in), (1, 1_000));
+    assert_eq!(
+        world
+            .runtime
+            .token_balance(&user.token_account(&pool.token_0_mint)),
+        before - 1_000
+    );
+
+    // A cancelled head is skipped right away
+    assert_error(
+        world.process(
+            &[instructions::cancel_ring_order(
+                &user.key,
+                &pool.pool_state,
+                2,
+                None,
+            )],
+            &[user.key],
+        ),
+        ContinuumError::Unauthorized,
+    );
+    world
+        .process(
+            &[instructions::cancel_ring_order(
+                &other.key,
+                &pool.pool_state,
+                2,
+                None,
+            )],
+            &[other.key],
+        )
+        .unwrap();
+    let ring = order_ring(&world, &pool);
+    assert_eq!((ring.head_sequence(), ring.len), (3, 1));
+    execute_ring_order(&mut world, &pool, &user, 3).unwrap();
+    assert_eq!(order_ring(&world, &pool).head_sequence(), 0);
+
+    // Slots are reused until the ring is full
+    for _ in 0..ORDER_RING_CAPACITY {
+        submit(&mut world, &user, 1_000).unwrap();
+    }
+    assert_error(
+        submit(&mut world, &user, 1_000),
+        ContinuumError::OrderRingFull,
+    );
+    execute_ring_order(&mut world, &pool, &user, 4).unwrap();
+    submit(&mut world, &user, 1_000).unwrap();
+    assert_eq!(order_ring(&world, &pool).len, ORDER_RING_CAPACITY as u64);
+}
+
+#[test]
+fn execute_ring_order_waits_for_the_pool_inclusion_delay() {
+    let (mut world, pool, user) = setup();
+    initialize_order_ring(&mut world, &pool);
+    update_pool_config(
+        &mut world,
+        &pool,
+        PoolConfigUpdate {
+            min_inclusion_delay: Some(2),
+            ..no_update()
+        },
+    );
+    let ix = instructions::submit_ring_order(&user.key, &pool.pool_state, 1_000, 1, true);
+    world.process(&[ix], &[user.key]).unwrap();
+    let ring = order_ring(&world, &pool);
+    assert_eq!(
+        ring.get(1).unwrap().submitted_slot,
+        world.runtime.clock().slot
+    );
+
+    for _ in 0..2 {
+        assert_error(
+            execute_ring_order(&mut world, &pool, &user, 1),
+            ContinuumError::InclusionDelayNotElapsed,
+        );
+        world.runtime.advance_clock(1);
+    }
+    execute_ring_order(&mut world, &pool, &user, 1).unwrap();
+}
+
+#[test]
+fn order_ring_bonds_caps_and_expires_records() {
+    let (mut world, pool, user) = setup();
+    let admin = world.admin;
+    // Without a TTL an unexecutable head would block the ring for good
+    assert_error(
+        world.process(
+            &[instructions::initialize_order_ring(
+                &admin,
+                &admin,
+                &pool.pool_state,
+            )],
+            &[admin],
+        ),
+        ContinuumError::InvalidPoolConfig,
+    );
+    initialize_order_ring(&mut world, &pool);
+    assert!(world.pool_registry(&pool.pool_state).has_order_ring);
+    update_pool_config(
+        &mut world,
+        &pool,
+        PoolConfigUpdate {
+            max_pending_per_user: Some(2),
+            order_bond: Some(1_000_000),
+            ..no_update()
+        },
+    );
+
+    // Nor can the TTL be cleared once the ring exists
+    let admin_config: AdminConfig = world.runtime.get(&pda::admin_config().0).unwrap();
+    let change = ConfigChange::UpdatePoolConfig {
+        pool_id: pool.pool_state,
+        update: PoolConfigUpdate {
+            order_ttl: Some(0),
+            ..no_update()
+        },
+    };
+    world
+        .process(
+            &[instructions::queue_config_change(
+                &admin,
+                &admin,
+                admin_config.change_count,
+                change,
+            )],
+            &[admin],
+        )
+        .unwrap();
+    world.runtime.advance_clock(admin_config.timelock_delay);
+    assert_error(
+        world.process(
+            &[instructions::execute_config_change(
+                admin_config.change_count,
+                &admin,
+                Some(pool.pool_state),
+            )],
+            &[admin],
+        ),
+        ContinuumError::InvalidPoolConfig,
+    );
+    let submit = |world: &mut Continuum, amount_in: u64, min_amount_out: u64| {
+        let ix = instructions::submit_ring_order(
+            &user.key,
+            &pool.pool_state,
+            amount_in,
+            min_amount_out,
+            true,
+        );
+        world.process(&[ix], &[user.key])
+    };
+    let address = pda::order_ring(&pool.pool_state).0;
+    let ring_lamports = world.runtime.lamports(&address);
+
+    assert_error(submit(&mut world, 0, 1), ContinuumError::InvalidAmount);
+    // A head that can never fill, then one that can
+    submit(&mut world, 1_000, u64::MAX).unwrap();
+    submit(&mut world, 1_000, 1).unwrap();
+    assert_error(
+        submit(&mut world, 1_000, 1),
+        ContinuumError::TooManyPendingOrders,
+    );
+    assert_eq!(world.runtime.lamports(&address), ring_lamports + 2_000_000);
+    assert!(execute_ring_order(&mut world, &pool, &user, 1).is_err());
+
+    // Anyone can expire the stuck head once the TTL has passed
+    let relayer = world.relayer;
+    let expire = |world: &mut Continuum, sequence: u64| {
+        let ix = instructions::expire_ring_order(&relayer, &user.key, &pool.pool_state, sequence);
+        world.process(&[ix], &[relayer])
+    };
+    assert_error(expire(&mut world, 1), ContinuumError::OrderNotExpired);
+    world.runtime.advance_clock(600);
+    let user_lamports = world.runtime.lamports(&user.key);
+    let meta = expire(&mut world, 1).unwrap();
+    assert_eq!(
+        meta.events::<RingOrderExpired>()[0].bond_refunded,
+        1_000_000
+    );
+    assert_eq!(world.runtime.lamports(&user.key), user_lamports + 1_000_000);
+    assert_eq!(order_ring(&world, &pool).head_sequence(), 2);
+
+    execute_ring_order(&mut world, &pool, &user, 2).unwrap();
+    assert_eq!(world.runtime.lamports(&address), ring_lamports);
+    let user_pool_state: UserPoolState = world
+        .runtime
+        .get(&pda::user_pool_state(&user.key, &pool.pool_state).0)
+        .unwrap();
+    assert_eq!(user_pool_state.pending_count, 0);
+    submit(&mut world, 1_000, 1).unwrap();
+}
+
+#[test]
+fn submit_order_simple_only_advances_the_sequence() {
+    let (mut world, pool, user) = setup();
+    let ix = instructions::submit_order_simple(
+        &user.key,
+        &pool.pool_state,
+        &user.token_account(&pool.token_0_mint),
+        &user.token_account(&pool.token_1_mint),
+        1_000,
+        1,
+        true,
+    );
+    let meta = world.process(&[ix], &[user.key]).unwrap();
+
+    assert_eq!(world.fifo_state().current_sequence, 1);
+    let events = meta.events::<OrderSubmitted>();
+    assert_eq!((events[0].sequence, events[0].amount_in), (1, 1_000));
+}
+
+#[test]
+fn swap_immediate_swaps_through_the_pool_authority() {
+    let (mut world, pool, user) = setup();
+    let input = pool.token_0_mint;
+    let amount_in = 1_000_000;
+    let (reserve_in, reserve_out) = reserves(&world, &pool, &input);
+    let expected_out = mock_cp_swap::curve::swap_base_input(
+        amount_in,
+        reserve_in,
+        reserve_out,
+        DEFAULT_TRADE_FEE_RATE,
+    )
+    .unwrap();
+
+    let mut swap_accounts = vec![
+        AccountMeta::new(user.token_account(&input), false),
+        AccountMeta::new(user.token_account(&pool.token_1_mint), false),
+    ];
+    swap_accounts.extend(pool.swap_accounts(&input));
+
+    // The pool authority only ever signs for CP-Swap
+    let ix = instructions::swap_immediate(
+        &pool.pool_state,
+        &anchor_lang::system_program::ID,
+        amount_in,
+        expected_out,
+        true,
+        swap_accounts.clone(),
+    );
+    assert_error(
+        world.process(&[ix], &[user.key]),
+        ContinuumError::InvalidPoolConfig,
+    );
+
+    let ix = instructions::swap_immediate(
+        &pool.pool_state,
+        &mock_cp_swap::ID,
+        amount_in,
+        expected_out,
+        true,
+        swap_accounts,
+    );
+    let meta = world.process(&[ix], &[user.key]).unwrap();
+
+    assert_eq!(
+        world
+            .runtime
+            .token_balance(&user.token_account(&pool.token_1_mint)),
+        BALANCE + expected_out
+    );
+    assert_eq!(meta.events::<SwapExecuted>()[0].sequence, 1);
+}
+
+fn quote(
+    world: &mut Continuum,
+    pool: &Pool,
+    amount: u64,
+    zero_for_one: bool,
+    is_base_input: bool,
+) -> SwapQuote {
+    let registry: CpSwapPoolRegistry = world
+        .runtime
+        .get(&pda::pool_registry(&pool.pool_state).0)
+        .unwrap();
+    let ix = instructions::quote_swap(
+        &registry,
+        &pool.amm_config,
+        amount,
+        zero_for_one,
+        is_base_input,
+    );
+    let (program_id, data) = world.process(&[ix], &[]).unwrap().return_data.unwrap();
+    assert_eq!(program_id, continuum_cp_swap::ID);
+    SwapQuote::try_from_slice(&data).unwrap()
+}
+
+fn swap_immediately(
+    world: &mut Continuum,
+    pool: &Pool,
+    user: &User,
+    input: &Pubkey,
+    amount: u64,
+    limit: u64,
+    is_base_input: bool,
+) -> Result<TransactionMeta, TransactionError> {
+    let mut swap_accounts = vec![
+        AccountMeta::new(user.token_account(input), false),
+        AccountMeta::new(user.token_account(&pool.other_mint(input)), false),
+    ];
+    swap_accounts.extend(pool.swap_accounts(input));
+    let ix = instructions::swap_immediate(
+        &pool.pool_state,
+        &mock_cp_swap::ID,
+        amount,
+        limit,
+        is_base_input,
+        swap_accounts,
+    );
+    world.process(&[ix], &[user.key])
+}
+
+#[test]
+fn quote_swap_matches_execution_with_accrued_fees() {
+    let mut world = Continuum::new();
+    // 0.3% trade fee, 12% of it to the protocol and 4% to the fund
+    let amm_config = world.create_amm_config(3_000, 120_000, 40_000);
+    let pool = world.create_pool_with_config(&amm_config, RESERVE_0, RESERVE_1);
+    let user = world.create_user(
+        &pool,
+        &[(pool.token_0_mint, BALANCE), (pool.token_1_mint, BALANCE)],
+    );
+
+    // Accrue protocol and fund fees, so vault balances are no longer the reserves
+    swap_immediately(
+        &mut world,
+        &pool,
+        &user,
+        &pool.token_0_mint,
+        10_000_000,
+        1,
+        true,
+    )
+    .unwrap();
+    let pool_state: mock_cp_swap::state::PoolState = world.runtime.get(&pool.pool_state).unwrap();
+    assert!(pool_state.protocol_fees_token_0 > 0 && pool_state.fund_fees_token_0 > 0);
+
+    let amount_in = 5_000_000;
+    let quoted = quote(&mut world, &pool, amount_in, true, true);
+    assert_eq!(quoted.amount_in, amount_in);
+    assert_eq!(quoted.trade_fee, 15_000);
+    assert_eq!((quoted.protocol_fee, quoted.fund_fee), (1_800, 600));
+    assert!(quoted.price_impact_bps > 0);
+
+    let output = user.token_account(&pool.token_1_mint);
+    let before = world.runtime.token_balance(&output);
+    swap_immediately(
+        &mut world,
+        &pool,
+        &user,
+        &pool.token_0_mint,
+        amount_in,
+        quoted.amount_out,
+        true,
+    )
+    .unwrap();
+    assert_eq!(
+        world.runtime.token_balance(&output) - before,
+        quoted.amount_out
+    );
+
+    // Exact output in the other direction reports the input it will cost
+    let amount_out = 1_000_000;
+    let quoted = quote(&mut world, &pool, amount_out, false, false);
+    assert_eq!(quoted.amount_out, amount_out);
+    let input = user.token_account(&pool.token_1_mint);
+    let before = world.runtime.token_balance(&input);
+    swap_immediately(
+        &mut world,
+        &pool,
+        &user,
+        &pool.token_1_mint,
+        amount_out,
+        quoted.amount_in,
+        false,
+    )
+    .unwrap();
+    assert_eq!(
+        before - world.runtime.token_balance(&input),
+        quoted.amount_in
+    );
+
+    // A bigger trade moves the price further
+    assert!(
+        quote(&mut world, &pool, amount_in * 20, true, true).price_impact_bps
+            > quoted.price_impact_bps
+    );
+}
+
+#[test]
+fn direct_swaps_on_a_continuum_pool_are_refused() {
+    let (mut world, pool, user) = setup();
+    let input = pool.token_0_mint;
+    let mut accounts = vec![
+        AccountMeta::new_readonly(user.key, true),
+        AccountMeta::new(user.token_account(&input), false),
+        AccountMeta::new(user.token_account(&pool.token_1_mint), false),
+    ];
+    accounts.extend(pool.swap_accounts(&input));
+
+    let ix = mock_instruction(
+        accounts,
+        mock_cp_swap::instruction::SwapBaseInput {
+            amount_in: 1_000,
+            minimum_amount_out: 0,
+        },
+    );
+    assert_error(
+        world.process(&[ix], &[user.key]),
+        mock_cp_swap::errors::ErrorCode::NotApproved,
+    );
+}
+
+#[test]
+fn deposit_and_withdraw_liquidity() {
+    let (mut world, pool, mut user) = setup();
+    let lp_account = world.add_token_account(&mut user, &pool.lp_mint, 0);
+    world.approve_pool(&user, &pool);
+    let token_0 = user.token_account(&pool.token_0_mint);
+    let token_1 = user.token_account(&pool.token_1_mint);
+    let lp_supply = |world: &Continuum| {
+        world
+            .runtime
+            .get::<mock_cp_swap::state::PoolState>(&pool.pool_state)
+            .unwrap()
+            .lp_supply
+    };
+    let supply_before = lp_supply(&world);
+    let lp_amount = supply_before / 100;
+
+    let deposit = instructions::deposit_liquidity(
+        &pool.pool_state,
+        &mock_cp_swap::ID,
+        lp_amount,
+        BALANCE,
+        BALANCE,
+        pool.liquidity_accounts(&lp_account, &token_0, &token_1),
+    );
+    world.process(&[deposit], &[user.key]).unwrap();
+    assert_eq!(world.runtime.token_balance(&lp_account), lp_amount);
+    assert_eq!(lp_supply(&world), supply_before + lp_amount);
+    let deposited_0 = BALANCE - world.runtime.token_balance(&token_0);
+    let deposited_1 = BALANCE - world.runtime.token_balance(&token_1);
+    assert!(deposited_0 >= RESERVE_0 / 100 && deposited_1 >= RESERVE_1 / 100);
+
+    let withdraw = instructions::withdraw_liquidity(
+        &pool.pool_state,
+        &mock_cp_swap::ID,
+        lp_amount,
+        0,
+        0,
+        pool.liquidity_accounts(&lp_account, &token_0, &token_1),
+    );
+    world.process(&[withdraw], &[user.key]).unwrap();
+    assert_eq!(world.runtime.token_balance(&lp_account), 0);
+    assert_eq!(lp_supply(&world), supply_before);
+    // Rounding favours the pool on both legs
+    let balance_0 = world.runtime.token_balance(&token_0);
+    assert!((BALANCE - 1..=BALANCE).contains(&balance_0));
+}
+
+#[test]
+fn multi_hop_order_routes_through_every_pool() {
+    let mut world = Continuum::new();
+    let first = world.create_pool(RESERVE_0, RESERVE_1);
+    let mint_a = first.token_0_mint;
+    let mint_b = first.token_1_mint;
+    let mint_c = world.runtime.create_mint(6);
+    let second = world.create_pool_with_mints(&mint_b, &mint_c, RESERVE_1, RESERVE_0);
+
+    let mut user = world.create_user(&first, &[(mint_a, BALANCE)]);
+    world.add_token_account(&mut user, &mint_c, 0);
+    // Each hop's source account is delegated to the authority of the pool it trades on
+    world.approve_pool(&user, &second);
+    world
+        .runtime
+        .approve(&user.token_account(&mint_a), &first.authority(), u64::MAX);
+
+    let amount_in = 1_000_000;
+    let (reserve_a, reserve_b) = reserves(&world, &first, &mint_a);
+    let hop_out = mock_cp_swap::curve::swap_base_input(
+        amount_in,
+        reserve_a,
+        reserve_b,
+        DEFAULT_TRADE_FEE_RATE,
+    )
+    .unwrap();
+    let (reserve_b, reserve_c) = reserves(&world, &second, &mint_b);
+    let expected_out =
+        mock_cp_swap::curve::swap_base_input(hop_out, reserve_b, reserve_c, DEFAULT_TRADE_FEE_RATE)
+            .unwrap();
+
+    let current_sequence = world.fifo_state().current_sequence;
+    let submit = instructions::submit_multi_hop_order(
+        &user.key,
+        current_sequence,
+        None,
+        vec![first.pool_state, second.pool_state],
+        amount_in,
+        expected_out,
+    );
+    world.process(&[submit], &[user.key]).unwrap();
+    let order_address = pda::order(&user.key, current_sequence).0;
+    let order = world.order(&order_address);
+    assert_eq!(order.route(), vec![first.pool_state, second.pool_state]);
+
+    // Single-pool execution does not apply to routed orders
+    assert_error(
+        world.execute_order(&order_address, &user, &first, &mint_a),
+        ContinuumError::InvalidOrderType,
+    );
+
+    let execute = instructions::execute_multi_hop_order(
+        &world.relayer,
+        false,
+        &order_address,
+        &order,
+        QueueNeighbors::default(),
+        &user.token_account(&mint_a),
+        &user.token_account(&mint_c),
+        &mock_cp_swap::ID,
+        &[user.token_account(&mint_b)],
+        vec![first.swap_accounts(&mint_a), second.swap_accounts(&mint_b)],
+    );
+    let relayer = world.relayer;
+    let meta = world.process(&[execute], &[relayer]).unwrap();
+
+    assert_eq!(
+        world.runtime.token_balance(&user.token_account(&mint_a)),
+        BALANCE - amount_in
+    );
+    assert_eq!(world.runtime.token_balance(&user.token_account(&mint_b)), 0);
+    assert_eq!(
+        world.runtime.token_balance(&user.token_account(&mint_c)),
+        expected_out
+    );
+    assert_eq!(meta.events::<OrderExecuted>()[0].amount_out, expected_out);
+    assert_eq!(world.order(&order_address).status, OrderStatus::Executed);
+}
+
+/// Pools trading A/B, B/C and C/D, and a user holding A with an account for
+/// every mint, each hop's source delegated to the pool it trades on
+fn three_hop_route(world: &mut Continuum) -> ([Pool; 3], [Pubkey; 4], User) {
+    let first = world.create_pool(RESERVE_0, RESERVE_1);
+    let mint_a = first.token_0_mint;
+    let mint_b = first.token_1_mint;
+    let mint_c = world.runtime.create_mint(6);
+    let mint_d = world.runtime.create_mint(6);
+    let second = world.create_pool_with_mints(&mint_b, &mint_c, RESERVE_1, RESERVE_0);
+    let third = world.create_pool_with_mints(&mint_c, &mint_d, RESERVE_0, RESERVE_1);
+
+    let mut user = world.create_user(&first, &[(mint_a, BALANCE)]);
+    world.add_token_account(&mut user, &mint_c, 0);
+    world.add_token_account(&mut user, &mint_d, 0);
+    world.approve_pool(&user, &second);
+    world.approve_pool(&user, &third);
+    world
+        .runtime
+        .approve(&user.token_account(&mint_a), &first.authority(), u64::MAX);
+    (
+        [first, second, third],
+        [mint_a, mint_b, mint_c, mint_d],
+        user,
+    )
+}
+
+fn set_pool_status(world: &mut Continuum, pool: &Pool, status: PoolStatus) {
+    apply_config_change(
+        world,
+        ConfigChange::SetPoolStatus {
+            pool_id: pool.pool_state,
+            status,
+        },
+    );
+}
+
+#[test]
+fn multi_hop_order_routes_through_three_pools() {
+    let mut world = Continuum::new();
+    let (pools, mints, user) = three_hop_route(&mut world);
+
+    let amount_in = 1_000_000;
+    let mut expected_out = amount_in;
+    for (pool, mint) in pools.iter().zip(mints) {
+        let (reserve_in, reserve_out) = reserves(&world, pool, &mint);
+        expected_out = mock_cp_swap::curve::swap_base_input(
+            expected_out,
+            reserve_in,
+            reserve_out,
+            DEFAULT_TRADE_FEE_RATE,
+        )
+        .unwrap();
+    }
+
+    let current_sequence = world.fifo_state().current_sequence;
+    let route = pools.iter().map(|pool| pool.pool_state).collect::<Vec<_>>();
+    let submit = instructions::submit_multi_hop_order(
+        &user.key,
+        current_sequence,
+        None,
+        route.clone(),
+        amount_in,
+        expected_out,
+    );
+    world.process(&[submit], &[user.key]).unwrap();
+    let order_address = pda::order(&user.key, current_sequence).0;
+    let order = world.order(&order_address);
+    assert_eq!(order.route(), route);
+    // Only the first pool queues the order
+    assert_eq!(world.queue_tail(&pools[0].pool_state), Some(order_address));
+    assert_eq!(world.queue_tail(&pools[1].pool_state), None);
+    assert_eq!(world.queue_tail(&pools[2].pool_state), None);
+
+    let execute = instructions::execute_multi_hop_order(
+        &world.relayer,
+        false,
+        &order_address,
+        &order,
+        QueueNeighbors::default(),
+        &user.token_account(&mints[0]),
+        &user.token_account(&mints[3]),
+        &mock_cp_swap::ID,
+        &[user.token_account(&mints[1]), user.token_account(&mints[2])],
+        pools
+            .iter()
+            .zip(mints)
+            .map(|(pool, mint)| pool.swap_accounts(&mint))
+            .collect(),
+    );
+    let relayer = world.relayer;
+    let meta = world.process(&[execute], &[relayer]).unwrap();
+
+    assert_eq!(
+        world.runtime.token_balance(&user.token_account(&mints[0])),
+        BALANCE - amount_in
+    );
+    assert_eq!(
+        world.runtime.token_balance(&user.token_account(&mints[1])),
+        0
+    );
+    assert_eq!(
+        world.runtime.token_balance(&user.token_account(&mints[2])),
+        0
+    );
+    assert_eq!(
+        world.runtime.token_balance(&user.token_account(&mints[3])),
+        expected_out
+    );
+    assert_eq!(meta.events::<OrderExecuted>()[0].amount_out, expected_out);
+    assert_eq!(world.order(&order_address).status, OrderStatus::Executed);
+}
+
+#[test]
+fn multi_hop_routes_must_connect_through_matching_accounts() {
+    let mut world = Continuum::new();
+    let (pools, mints, user) = three_hop_route(&mut world);
+    let [first, second, third] = &pools;
+
+    // A/B then C/D shares no mint, and B/C, C/D, A/B leaves D untraded
+    for route in [
+        vec![first.pool_state, third.pool_state],
+        vec![second.pool_state, third.pool_state, first.pool_state],
+    ] {
+        let submit = instructions::submit_multi_hop_order(
+            &user.key,
+            world.fifo_state().current_sequence,
+            world.queue_tail(&route[0]),
+            route,
+            1_000,
+            1,
+        );
+        assert_error(
+            world.process(&[submit], &[user.key]),
+            ContinuumError::InvalidRoute,
+        );
+    }
+
+    let current_sequence = world.fifo_state().current_sequence;
+    let submit = instructions::submit_multi_hop_order(
+        &user.key,
+        current_sequence,
+        None,
+        vec![first.pool_state, second.pool_state],
+        1_000,
+        1,
+    );
+    world.process(&[submit], &[user.key]).unwrap();
+    let order_address = pda::order(&user.key, current_sequence).0;
+    let order = world.order(&order_address);
+
+    let execute = |source: &Pubkey, destination: &Pubkey, intermediate: &Pubkey| {
+        instructions::execute_multi_hop_order(
+            &world.relayer,
+            false,
+            &order_address,
+            &order,
+            QueueNeighbors::default(),
+            source,
+            destination,
+            &mock_cp_swap::ID,
+            &[*intermediate],
+            vec![
+                first.swap_accounts(&mints[0]),
+                second.swap_accounts(&mints[1]),
+            ],
+        )
+    };
+    let account = |mint: usize| user.token_account(&mints[mint]);
+    let bad_hops = [
+        // The intermediate holds C rather than the B the first pool outputs
+        execute(&account(0), &account(2), &account(2)),
+        // The destination is not the route's output mint
+        execute(&account(0), &account(3), &account(1)),
+        // Nor is the source its input mint
+        execute(&account(1), &account(2), &account(1)),
+    ];
+    let ix = execute(&account(0), &account(2), &account(1));
+    let relayer = world.relayer;
+    for ix in bad_hops {
+        assert_error(
+            world.process(&[ix], &[relayer]),
+            ContinuumError::InvalidRoute,
+        );
+    }
+    world.process(&[ix], &[relayer]).unwrap();
+    assert_eq!(world.order(&order_address).status, OrderStatus::Executed);
+}
+
+#[test]
+fn multi_hop_order_needs_every_pool_active() {
+    let mut world = Continuum::new();
+    let (pools, mints, user) = three_hop_route(&mut world);
+    let route = pools.iter().map(|pool| pool.pool_state).collect::<Vec<_>>();
+    let submit = |world: &Continuum| {
+        instructions::submit_multi_hop_order(
+            &user.key,
+            world.fifo_state().current_sequence,
+            world.queue_tail(&route[0]),
+            route.clone(),
+            1_000,
+            1,
+        )
+    };
+
+    set_pool_status(&mut world, &pools[1], PoolStatus::Inactive);
+    let ix = submit(&world);
+    assert_error(
+        world.process(&[ix], &[user.key]),
+        ContinuumError::PoolNotAcceptingOrders,
+    );
+
+    set_pool_status(&mut world, &pools[1], PoolStatus::Active);
+    let current_sequence = world.fifo_state().current_sequence;
+    let ix = submit(&world);
+    world.process(&[ix], &[user.key]).unwrap();
+    let order_address = pda::order(&user.key, current_sequence).0;
+    let order = world.order(&order_address);
+
+    // The middle pool stops trading after the order was queued
+    set_pool_status(&mut world, &pools[1], PoolStatus::Inactive);
+    let execute = instructions::execute_multi_hop_order(
+        &world.relayer,
+        false,
+        &order_address,
+        &order,
+        QueueNeighbors::default(),
+        &user.token_account(&mints[0]),
+        &user.token_account(&mints[3]),
+        &mock_cp_swap::ID,
+        &[user.token_account(&mints[1]), user.token_account(&mints[2])],
+        pools
+            .iter()
+            .zip(mints)
+            .map(|(pool, mint)| pool.swap_accounts(&mint))
+            .collect(),
+    );
+    let relayer = world.relayer;
+    assert_error(
+        world.process(&[execute], &[relayer]),
+        ContinuumError::PoolNotRegistered,
+    );
+
+    // The order can still be withdrawn from the first pool's queue
+    world.cancel_order(&order_address, None).unwrap();
+    assert_eq!(world.order(&order_address).status, OrderStatus::Cancelled);
+}
+
+fn keypair(seed: u8) -> Keypair {
+    let secret = SecretKey::from_bytes(&[seed; 32]).unwrap();
+    let public = PublicKey::from(&secret);
+    Keypair { secret, public }
+}
+
+#[test]
+fn submit_order_with_intent_verifies_the_user_signature() {
+    let mut world = Continuum::new();
+    let pool = world.create_pool(RESERVE_0, RESERVE_1);
+    let signer = keypair(7);
+    let user_key = Pubkey::new_from_array(signer.public.to_bytes());
+    let user = world.create_user_with_key(user_key, &pool, &[(pool.token_0_mint, BALANCE)]);
+    let relayer = world.relayer;
+    let fee_account = world
+        .runtime
+        .create_token_account(&pool.token_0_mint, &relayer);
+    let treasury = Pubkey::new_unique();
+    world.runtime.airdrop(&treasury, 1_000_000_000);
+    update_pool_config(
+        &mut world,
+        &pool,
+        PoolConfigUpdate {
+            order_bond: Some(1_000_000),
+            min_resting_period: Some(60),
+            bond_slash_bps: Some(2_500),
+            bond_recipient: Some(treasury),
+            ..no_update()
+        },
+    );
+
+    let intent = OrderIntent {
+        program_id: continuum_cp_swap::ID,
+        user: user_key,
+        pool_id: pool.pool_state,
+        source: user.token_account(&pool.token_0_mint),
+        amount_in: 1_000,
+        min_amount_out: 1,
+        is_base_input: true,
+        relayer_fee: 10,
+        nonce: 0,
+        deadline: world.runtime.clock().unix_timestamp + 60,
+    };
+    let submit = |world: &Continuum, intent: &OrderIntent, signer: &Keypair| {
+        vec![
+            new_ed25519_instruction(signer, &intent.try_to_vec().unwrap()),
+            instructions::submit_order_with_intent(
+                &world.relayer,
+                world.fifo_state().current_sequence,
+                world.queue_tail(&pool.pool_state),
+                &fee_account,
+                intent.clone(),
+            ),
+        ]
+    };
+
+    // Signed by someone else
+    let ixs = submit(&world, &intent, &keypair(8));
+    assert_error(
+        world.process(&ixs, &[relayer]),
+        ContinuumError::InvalidIntentSignature,
+    );
+
+    // The relayer cannot swap in another of the user's accounts
+    let mut ixs = submit(&world, &intent, &signer);
+    let other_source = user.token_account(&pool.token_1_mint);
+    ixs[1]
+        .accounts
+        .iter_mut()
+        .find(|meta| meta.pubkey == intent.source)
+        .unwrap()
+        .pubkey = other_source;
+    assert_error(
+        world.process(&ixs, &[relayer]),
+        ContinuumError::InvalidSourceAccount,
+    );
+
+    // Nor can the user sign for an account the pool does not trade
+    let foreign_mint = world.runtime.create_mint(6);
+    let foreign_source = world.runtime.create_token_account(&foreign_mint, &user_key);
+    let foreign = OrderIntent {
+        source: foreign_source,
+        ..intent.clone()
+    };
+    let ixs = submit(&world, &foreign, &signer);
+    assert_error(
+        world.process(&ixs, &[relayer]),
+        ContinuumError::InvalidSourceAccount,
+    );
+
+    let ixs = submit(&world, &intent, &signer);
+    let meta = world.process(&ixs, &[relayer]).unwrap();
+    assert_eq!(world.runtime.token_balance(&fee_account), 10);
+    assert_eq!(meta.events::<IntentRelayed>()[0].relayer, relayer);
+    let order_address = pda::order(&user_key, 0).0;
+    let order = world.order(&order_address);
+    assert_eq!((order.user, order.amount_in), (user_key, 1_000));
+    assert_eq!(order.source, intent.source);
+
+    // The relayer posts the bond and gets back what an early cancellation
+    // does not slash
+    assert_eq!(order.bond_lamports, 1_000_000);
+    assert_eq!(order.bond_payer, relayer);
+    // The user cannot take the bond back for themselves
+    let mut unbonded = order.clone();
+    unbonded.bond_payer = Pubkey::default();
+    let cancel = instructions::cancel_order(
+        &user_key,
+        &order_address,
+        &unbonded,
+        QueueNeighbors::default(),
+        Some(treasury),
+    );
+    assert_error(
+        world.process(&[cancel], &[user_key]),
+        ContinuumError::InvalidBondPayer,
+    );
+    let user_lamports = world.runtime.lamports(&user_key);
+    let relayer_lamports = world.runtime.lamports(&relayer);
+    let index_rent = index_rent(&world, &order_address);
+    world.cancel_order(&order_address, Some(treasury)).unwrap();
+    assert_eq!(world.runtime.lamports(&treasury), 1_000_000_000 + 250_000);
+    assert_eq!(world.runtime.lamports(&relayer), relayer_lamports + 750_000);
+    assert_eq!(
+        world.runtime.lamports(&user_key),
+        user_lamports + index_rent
+    );
+
+    // The nonce is spent
+    let ixs = submit(&world, &intent, &signer);
+    assert_error(
+        world.process(&ixs, &[relayer]),
+        ContinuumError::InvalidIntentNonce,
+    );
+
+    let late = OrderIntent {
+        nonce: 1,
+        deadline: world.runtime.clock().unix_timestamp - 1,
+        ..intent
+    };
+    let ixs = submit(&world, &late, &signer);
+    assert_error(
+        world.process(&ixs, &[relayer]),
+        ContinuumError::IntentExpired,
+    );
+}
+
+#[test]
+fn expire_order_after_pool_ttl() {
+    let (mut world, pool, user) = setup();
+    update_pool_config(
+        &mut world,
+        &pool,
+        PoolConfigUpdate {
+            order_ttl: Some(600),
+            ..no_update()
+        },
+    );
+    let order_address = world.submit_order(&user, &pool, 1_000, 1, true).unwrap();
+    let order = world.order(&order_address);
+    let caller = world.relayer;
+
+    let expire =
+        instructions::expire_order(&caller, &order_address, &order, QueueNeighbors::default());
+    assert_error(
+        world.process(std::slice::from_ref(&expire), &[caller]),
+        ContinuumError::OrderNotExpired,
+    );
+
+    world.runtime.advance_clock(600);
+    world.process(&[expire], &[caller]).unwrap();
+    assert_eq!(world.order(&order_address).status, OrderStatus::Expired);
+}
+
+fn commit_order(world: &mut Continuum, user: &User, pool: &Pool, commitment: [u8; 32]) -> Pubkey {
+    let current_sequence = world.fifo_state().current_sequence;
+    let ix = instructions::commit_order(
+        &user.key,
+        &pool.pool_state,
+        current_sequence,
+        world.queue_tail(&pool.pool_state),
+        commitment,
+    );
+    world.process(&[ix], &[user.key]).unwrap();
+    pda::order(&user.key, current_sequence).0
+}
+
+#[test]
+fn commit_reveal_orders_hold_their_queue_position() {
+    let (mut world, pool, user) = setup();
+    let input = pool.token_0_mint;
+    let salt = [7; 32];
+    let commitment = OrderCommitment::hash(&user.key, &pool.pool_state, 1_000, 1, true, &salt);
+
+    // Refused until the pool has a reveal window
+    let ix = instructions::commit_order(&user.key, &pool.pool_state, 0, None, commitment);
+    assert_error(
+        world.process(&[ix], &[user.key]),
+        ContinuumError::CommitRevealDisabled,
+    );
+    update_pool_config(
+        &mut world,
+        &pool,
+        PoolConfigUpdate {
+            reveal_window: Some(60),
+            ..no_update()
+        },
+    );
+
+    let committed = commit_order(&mut world, &user, &pool, commitment);
+    let behind = world.submit_order(&user, &pool, 1_000, 1, true).unwrap();
+    let order = world.order(&committed);
+    assert_eq!(order.status, OrderStatus::Committed);
+    assert_eq!(world.order(&behind).queue_prev, order.sequence);
+    assert_error(
+        world.execute_order(&committed, &user, &pool, &input),
+        ContinuumError::InvalidOrderStatus,
+    );
+
+    // The reveal must match the commitment
+    let reveal = |amount_in, salt| {
+        instructions::reveal_order(&user.key, &committed, amount_in, 1, true, salt)
+    };
+    assert_error(
+        world.process(&[reveal(1_000, [8; 32])], &[user.key]),
+        ContinuumError::InvalidCommitment,
+    );
+    assert_error(
+        world.process(&[reveal(2_000, salt)], &[user.key]),
+        ContinuumError::InvalidCommitment,
+    );
+    let meta = world.process(&[reveal(1_000, salt)], &[user.key]).unwrap();
+    assert_eq!(meta.events::<OrderRevealed>()[0].amount_in, 1_000);
+    let commitment_address = pda::order_commitment(&committed).0;
+    assert!(world.runtime.get_account(&commitment_address).is_none());
+
+    let order = world.order(&committed);
+    assert_eq!(order.status, OrderStatus::Pending);
+    assert_eq!(order.amount_in, 1_000);
+    assert_eq!(
+        world.pool_registry(&pool.pool_state).queue.head_sequence,
+        order.sequence
+    );
+    world
+        .execute_order(&committed, &user, &pool, &input)
+        .unwrap();
+    assert_eq!(world.order(&committed).status, OrderStatus::Executed);
+}
+
+#[test]
+fn unrevealed_commitments_expire_after_the_reveal_window() {
+    let (mut world, pool, user) = setup();
+    update_pool_config(
+        &mut world,
+        &pool,
+        PoolConfigUpdate {
+            reveal_window: Some(60),
+            order_bond: Some(1_000_000),
+            ..no_update()
+        },
+    );
+    let salt = [7; 32];
+    let commitment = OrderCommitment::hash(&user.key, &pool.pool_state, 1_000, 1, true, &salt);
+    let committed = commit_order(&mut world, &user, &pool, commitment);
+    let order = world.order(&committed);
+    let caller = world.relayer;
+
+    let expire = instructions::expire_order(&caller, &committed, &order, QueueNeighbors::default());
+    assert_error(
+        world.process(std::slice::from_ref(&expire), &[caller]),
+        ContinuumError::OrderNotExpired,
+    );
+
+    world.runtime.advance_clock(61);
+    assert_error(
+        world.process(
+            &[instructions::reveal_order(
+                &user.key, &committed, 1_000, 1, true, salt,
+            )],
+            &[user.key],
+        ),
+        ContinuumError::RevealWindowClosed,
+    );
+
+    // The bond and the commitment and index rent go back to the user
+    let commitment_address = pda::order_commitment(&committed).0;
+    let refund = world.runtime.lamports(&commitment_address)
+        + index_rent(&world, &committed)
+        + order.bond_lamports;
+    let user_lamports = world.runtime.lamports(&user.key);
+    world.process(&[expire], &[caller]).unwrap();
+    assert_eq!(world.order(&committed).status, OrderStatus::Expired);
+    assert!(world.runtime.get_account(&commitment_address).is_none());
+    assert_eq!(world.runtime.lamports(&user.key), user_lamports + refund);
+    assert_eq!(world.pool_registry(&pool.pool_state).queue.pending_count, 0);
+}
+
+fn fail_order(
+    world: &mut Continuum,
+    pool: &Pool,
+    order_address: &Pubkey,
+) -> Result<TransactionMeta, TransactionError> {
+    let order = world.order(order_address);
+    let ix = instructions::fail_order(
+        &world.relayer,
+        false,
+        order_address,
+        &order,
+        world.queue_neighbors(&order).next,
+        &world.pool_registry(&pool.pool_state),
+        &pool.amm_config,
+    );
+    let relayer = world.relayer;
+    world.process(&[ix], &[relayer])
+}
+
+#[test]
+fn fail_order_settles_an_unexecutable_head() {
+    let (mut world, pool, user) = setup();
+    let input = pool.token_0_mint;
+    let unfunded = world.create_user(&pool, &[]);
+    let unreachable = world
+        .submit_order(&user, &pool, 1_000, u64::MAX, true)
+        .unwrap();
+    let executable = world.submit_order(&user, &pool, 1_000, 1, true).unwrap();
+    let empty_source = world
+        .submit_order(&unfunded, &pool, 1_000, 1, true)
+        .unwrap();
+
+    // Only the head can be failed, and only when no fill can meet its limit
+    assert_error(
+        fail_order(&mut world, &pool, &executable),
+        ContinuumError::InvalidSequence,
+    );
+    let user_lamports = world.runtime.lamports(&user.key);
+    let index_rent = index_rent(&world, &unreachable);
+    let meta = fail_order(&mut world, &pool, &unreachable).unwrap();
+    assert_eq!(meta.events::<OrderFailed>()[0].sequence, 1);
+    assert_eq!(world.order(&unreachable).status, OrderStatus::Failed);
+    assert_eq!(
+        world.runtime.lamports(&user.key),
+        user_lamports + index_rent
+    );
+    assert_eq!(world.pool_registry(&pool.pool_state).queue.head_sequence, 2);
+    assert_error(
+        fail_order(&mut world, &pool, &executable),
+        ContinuumError::OrderStillExecutable,
+    );
+    world
+        .execute_order(&executable, &user, &pool, &input)
+        .unwrap();
+    assert!(!world
+        .order(&unreachable)
+        .was_skipped_for(&world.order(&executable)));
+
+    // Nothing the pool authority can move is as good as nothing at all
+    fail_order(&mut world, &pool, &empty_source).unwrap();
+    assert_eq!(world.order(&empty_source).status, OrderStatus::Failed);
+    let user_state: UserState = world
+        .runtime
+        .get(&pda::user_state(&unfunded.key).0)
+        .unwrap();
+    assert_eq!(user_state.pending_count, 0);
+}
+
+#[test]
+fn fail_order_judges_the_order_funding_source() {
+    let (mut world, pool, user) = setup();
+    let input = pool.token_0_mint;
+    let output = pool.token_1_mint;
+    let relayer = world.relayer;
+
+    // A user without associated token accounts, closed after submitting
+    let closer = world.create_user(&pool, &[]);
+    let unbound = world.submit_order(&closer, &pool, 1_000, 1, true).unwrap();
+    let close_accounts: Vec<_> = [input, output]
+        .iter()
+        .map(|mint| {
+            spl_token::instruction::close_account(
+                &spl_token::ID,
+                &closer.token_account(mint),
+                &closer.key,
+                &closer.key,
+                &[],
+            )
+            .unwrap()
+        })
+        .collect();
+    world.process(&close_accounts, &[closer.key]).unwrap();
+
+    // Orders funded from an account other than the associated one
+    let source = world.runtime.create_token_account(&input, &user.key);
+    world.runtime.mint_tokens(&source, BALANCE);
+    world.runtime.approve(&source, &pool.authority(), u64::MAX);
+    let submit_bound = |world: &mut Continuum| {
+        let current_sequence = world.fifo_state().current_sequence;
+        let ix = instructions::submit_order(
+            &user.key,
+            &pool.pool_state,
+            current_sequence,
+            world.queue_tail(&pool.pool_state),
+            Some(source),
+            1_000,
+            1,
+            true,
+            FillPolicy::FillOrKill,
+        );
+        world.process(&[ix], &[user.key]).unwrap();
+        pda::order(&user.key, current_sequence).0
+    };
+    let executed = submit_bound(&mut world);
+    let failed = submit_bound(&mut world);
+    assert_eq!(world.order(&executed).source, source);
+
+    // Only a token account of the user for one of the pool's mints can fund one
+    let foreign = world.runtime.create_token_account(&input, &closer.key);
+    let current_sequence = world.fifo_state().current_sequence;
+    let ix = instructions::submit_order(
+        &user.key,
+        &pool.pool_state,
+        current_sequence,
+        world.queue_tail(&pool.pool_state),
+        Some(foreign),
+        1_000,
+        1,
+        true,
+        FillPolicy::FillOrKill,
+    );
+    assert_error(
+        world.process(&[ix], &[user.key]),
+        ContinuumError::InvalidSourceAccount,
+    );
+
+    // A closed associated token account funds nothing
+    fail_order(&mut world, &pool, &unbound).unwrap();
+    assert_eq!(world.order(&unbound).status, OrderStatus::Failed);
+
+    // A bound order is judged on, and executed from, its source alone
+    assert_error(
+        fail_order(&mut world, &pool, &executed),
+        ContinuumError::OrderStillExecutable,
+    );
+    let execute_from = |world: &Continuum, order_address: &Pubkey, from: &Pubkey| {
+        let order = world.order(order_address);
+        instructions::execute_order(
+            &relayer,
+            false,
+            order_address,
+            &order,
+            world.queue_neighbors(&order),
+            from,
+            &user.token_account(&output),
+            &mock_cp_swap::ID,
+            None,
+            pool.swap_accounts(&input),
+        )
+    };
+    let ix = execute_from(&world, &executed, &user.token_account(&input));
+    assert_error(
+        world.process(&[ix], &[relayer]),
+        ContinuumError::InvalidSourceAccount,
+    );
+    let ix = execute_from(&world, &executed, &source);
+    world.process(&[ix], &[relayer]).unwrap();
+    assert_eq!(world.runtime.token_balance(&source), BALANCE - 1_000);
+
+    // An emptied source fails the order, funded associated accounts or not
+    let ix = spl_token::instruction::transfer(
+        &spl_token::ID,
+        &source,
+        &user.token_account(&input),
+        &user.key,
+        &[],
+        BALANCE - 1_000,
+    )
+    .unwrap();
+    world.process(&[ix], &[user.key]).unwrap();
+    fail_order(&mut world, &pool, &failed).unwrap();
+    assert_eq!(world.order(&failed).status, OrderStatus::Failed);
+}
+
+#[test]
+fn relayers_stake_to_execute_and_are_slashed_for_skipping_orders() {
+    let (mut world, pool, user) = setup();
+    let input = pool.token_0_mint;
+    let skipped_user = world.create_user(&pool, &[(input, BALANCE)]);
+    let relayer = world.relayer;
+    let stake = 10_000_000;
+    update_pool_config(
+        &mut world,
+        &pool,
+        PoolConfigUpdate {
+            min_relayer_stake: Some(stake),
+            relayer_slash_bps: Some(2_000),
+            ..no_update()
+        },
+    );
+
+    // One order per slot, the relayer skips the oldest
+    let skipped = world
+        .submit_order(&skipped_user, &pool, 1_000, 1, true)
+        .unwrap();
+    world.runtime.advance_clock(1);
+    let executed = world.submit_order(&user, &pool, 1_000, 1, true).unwrap();
+    world.runtime.advance_clock(1);
+    let newer = world.submit_order(&user, &pool, 1_000, 1, true).unwrap();
+
+    assert_error(
+        world.execute_order(&executed, &user, &pool, &input),
+        ContinuumError::RelayerNotEligible,
+    );
+    world
+        .process(&[instructions::stake_relayer(&relayer, stake)], &[relayer])
+        .unwrap();
+    world
+        .execute_order(&executed, &user, &pool, &input)
+        .unwrap();
+    let relayer_stake: RelayerStake = world.runtime.get(&pda::relayer_stake(&relayer).0).unwrap();
+    assert_eq!(world.order(&executed).executed_by, relayer_stake.id);
+
+    let reporter = Pubkey::new_unique();
+    world.runtime.airdrop(&reporter, 1_000_000_000);
+    let report = |world: &Continuum, skipped: &Pubkey| {
+        instructions::report_misbehavior(
+            &reporter,
+            &relayer,
+            &executed,
+            &world.order(&executed),
+            skipped,
+            &world.order(skipped),
+        )
+    };
+
+    // A newer order was not skipped, and the skipped user cannot report
+    let ix = report(&world, &newer);
+    assert_error(
+        world.process(&[ix], &[reporter]),
+        ContinuumError::InvalidMisbehaviorEvidence,
+    );
+
+    let ix = instructions::report_misbehavior(
+        &skipped_user.key,
+        &relayer,
+        &executed,
+        &world.order(&executed),
+        &skipped,
+        &world.order(&skipped),
+    );
+    assert_error(
+        world.process(&[ix], &[skipped_user.key]),
+        ContinuumError::Unauthorized,
+    );
+
+    let reporter_lamports = world.runtime.lamports(&reporter);
+    let user_lamports = world.runtime.lamports(&skipped_user.key);
+    let ix = report(&world, &skipped);
+    let meta = world.process(&[ix], &[reporter]).unwrap();
+    let slashed = &meta.events::<RelayerSlashed>()[0];
+    assert_eq!(slashed.skipped_sequence, world.order(&skipped).sequence);
+    assert_eq!(slashed.reporter_reward, stake / 10);
+    assert_eq!(slashed.user_compensation, stake / 10);
+    assert_eq!(
+        world.runtime.lamports(&reporter),
+        reporter_lamports + stake / 10
+    );
+    assert_eq!(
+        world.runtime.lamports(&skipped_user.key),
+        user_lamports + stake / 10
+    );
+
+    // Each execution is reported once, and the slashed stake is no longer enough
+    let ix = report(&world, &skipped);
+    assert_error(
+        world.process(&[ix], &[reporter]),
+        ContinuumError::InvalidMisbehaviorEvidence,
+    );
+    assert_error(
+        world.execute_order(&skipped, &skipped_user, &pool, &input),
+        ContinuumError::RelayerNotEligible,
+    );
+
+    // Unstaking waits out the delay left for reports
+    world
+        .process(&[instructions::request_unstake(&relayer)], &[relayer])
+        .unwrap();
+    let withdraw = instructions::withdraw_stake(&relayer);
+    assert_error(
+        world.process(std::slice::from_ref(&withdraw), &[relayer]),
+        ContinuumError::UnstakeDelayNotElapsed,
+    );
+    world.runtime.advance_clock(RELAYER_UNSTAKE_DELAY);
+    let stake_address = pda::relayer_stake(&relayer).0;
+    let refund = world.runtime.lamports(&stake_address);
+    let relayer_lamports = world.runtime.lamports(&relayer);
+    let meta = world.process(&[withdraw], &[relayer]).unwrap();
+    assert_eq!(
+        meta.events::<RelayerStakeWithdrawn>()[0].amount,
+        stake - stake / 5
+    );
+    assert!(world.runtime.get_account(&stake_address).is_none());
+    assert_eq!(world.runtime.lamports(&relayer), relayer_lamports + refund);
+}
+
+#[test]
+fn users_execute_their_own_order_once_it_stalls_at_the_head() {
+    let (mut world, pool, user) = setup();
+    let input = pool.token_0_mint;
+    update_pool_config(
+        &mut world,
+        &pool,
+        PoolConfigUpdate {
+            min_relayer_stake: Some(1_000_000),
+            self_execution_timeout: Some(60),
+            ..no_update()
+        },
+    );
+    let head = world.submit_order(&user, &pool, 1_000, 1, true).unwrap();
+    let next = world.submit_order(&user, &pool, 1_000, 1, true).unwrap();
+
+    let execute = |world: &mut Continuum, order_address: &Pubkey| {
+        let order = world.order(order_address);
+        let ix = instructions::execute_order(
+            &user.key,
+            false,
+            order_address,
+            &order,
+            world.queue_neighbors(&order),
+            &user.token_account(&input),
+            &user.token_account(&pool.token_1_mint),
+            &mock_cp_swap::ID,
+            None,
+            pool.swap_accounts(&input),
+        );
+        world.process(&[ix], &[user.key])
+    };
+
+    // Only a relayer can execute until the head order has waited out the timeout
+    assert_error(
+        execute(&mut world, &head),
+        ContinuumError::RelayerNotEligible,
+    );
+    world.runtime.advance_clock(60);
+    assert_error(
+        execute(&mut world, &next),
+        ContinuumError::RelayerNotEligible,
+    );
+    execute(&mut world, &head).unwrap();
+    assert_eq!(world.order(&head).status, OrderStatus::Executed);
+    assert_eq!(world.order(&head).executed_by, 0);
+
+    // The next order's wait starts when it reaches the head
+    let registry = world.pool_registry(&pool.pool_state);
+    assert_eq!(registry.head_since, world.runtime.clock().unix_timestamp);
+    assert_error(
+        execute(&mut world, &next),
+        ContinuumError::RelayerNotEligible,
+    );
+    world.runtime.advance_clock(60);
+    execute(&mut world, &next).unwrap();
+    assert_eq!(world.order(&next).status, OrderStatus::Executed);
+}
+
+#[test]
+fn partial_fills_out_of_turn_are_slashable() {
+    let (mut world, pool, user) = setup();
+    let input = pool.token_0_mint;
+    let skipped_user = world.create_user(&pool, &[(input, BALANCE)]);
+    let relayer = world.relayer;
+    let stake = 10_000_000;
+    update_pool_config(
+        &mut world,
+        &pool,
+        PoolConfigUpdate {
+            min_relayer_stake: Some(stake),
+            relayer_slash_bps: Some(2_000),
+            ..no_update()
+        },
+    );
+    world
+        .process(&[instructions::stake_relayer(&relayer, stake)], &[relayer])
+        .unwrap();
+
+    let skipped = world
+        .submit_order(&skipped_user, &pool, 1_000, 1, true)
+        .unwrap();
+    world.runtime.advance_clock(1);
+    let filled = world
+        .submit_order_with_fill_policy(
+            &user,
+            &pool,
+            1_000,
+            1,
+            true,
+            FillPolicy::PartialKeepRemainder,
+        )
+        .unwrap();
+
+    // All but one unit, the order stays pending
+    world
+        .fill_order(&filled, &user, &pool, &input, Some(999))
+        .unwrap();
+    let state = world.order(&filled);
+    assert_eq!(state.status, OrderStatus::Pending);
+    assert_eq!(
+        state.executed_at,
+        Some(world.runtime.clock().unix_timestamp)
+    );
+    assert_ne!(state.executed_by, 0);
+
+    let reporter = Pubkey::new_unique();
+    world.runtime.airdrop(&reporter, 1_000_000_000);
+    let ix = instructions::report_misbehavior(
+        &reporter,
+        &relayer,
+        &filled,
+        &state,
+        &skipped,
+        &world.order(&skipped),
+    );
+    let meta = world.process(&[ix], &[reporter]).unwrap();
+    assert_eq!(
+        meta.events::<RelayerSlashed>()[0].reporter_reward,
+        stake / 10
+    );
+}
+
+#[test]
+fn partial_fills_follow_the_order_fill_policy() {
+    let (mut world, pool, user) = setup();
+    let input = pool.token_0_mint;
+    let output = pool.token_1_mint;
+
+    // Fill-or-kill orders are only ever filled whole
+    let order = world.submit_order(&user, &pool, 1_000, 1, true).unwrap();
+    assert_error(
+        world.fill_order(&order, &user, &pool, &input, Some(400)),
+        ContinuumError::PartialFillNotAllowed,
+    );
+    world.execute_order(&order, &user, &pool, &input).unwrap();
+
+    // The remainder keeps its place at the head until it is filled
+    let order = world
+        .submit_order_with_fill_policy(
+            &user,
+            &pool,
+            1_000,
+            1_000,
+            true,
+            FillPolicy::PartialKeepRemainder,
+        )
+        .unwrap();
+    let behind = world.submit_order(&user, &pool, 1_000, 1, true).unwrap();
+    let output_before = world.runtime.token_balance(&user.token_account(&output));
+    let meta = world
+        .fill_order(&order, &user, &pool, &input, Some(400))
+        .unwrap();
+    let fill = meta.events::<OrderPartiallyFilled>().pop().unwrap();
+    assert_eq!((fill.amount_in, fill.remaining), (400, 600));
+    let state = world.order(&order);
+    assert_eq!(state.status, OrderStatus::Pending);
+    assert_eq!(
+        (state.filled_amount_in, state.filled_amount_out),
+        (400, fill.amount_out)
+    );
+    assert_eq!(
+        world.pool_registry(&pool.pool_state).queue.head_sequence,
+        state.sequence
+    );
+    assert_error(
+        world.fill_order(&order, &user, &pool, &input, Some(601)),
+        ContinuumError::InvalidAmount,
+    );
+
+    let meta = world.execute_order(&order, &user, &pool, &input).unwrap();
+    let executed = meta.events::<OrderExecuted>().pop().unwrap();
+    assert_eq!(executed.amount_in, 1_000);
+    assert_eq!(
+        executed.amount_out,
+        world.runtime.token_balance(&user.token_account(&output)) - output_before
+    );
+    assert_eq!(world.order(&order).status, OrderStatus::Executed);
+    assert_eq!(
+        world.pool_registry(&pool.pool_state).queue.head_sequence,
+        world.order(&behind).sequence
+    );
+    world.execute_order(&behind, &user, &pool, &input).unwrap();
+
+    // Or the order settles with its first fill and the rest is dropped
+    let order = world
+        .submit_order_with_fill_policy(
+            &user,
+            &pool,
+            1_000,
+            1,
+            true,
+            FillPolicy::PartialReleaseRemainder,
+        )
+        .unwrap();
+    world
+        .fill_order(&order, &user, &pool, &input, Some(250))
+        .unwrap();
+    let state = world.order(&order);
+    assert_eq!(state.status, OrderStatus::Executed);
+    assert_eq!((state.filled_amount_in, state.remaining_size()), (250, 750));
+    let user_state: UserState = world.runtime.get(&pda::user_state(&user.key).0).unwrap();
+    assert_eq!(user_state.pending_count, 0);
+    assert_eq!(world.pool_registry(&pool.pool_state).queue.pending_count, 0);
+}
+
+#[test]
+fn super_admin_transfer_is_timelocked_and_takes_two_steps() {
+    let mut world = Continuum::new();
+    let admin = world.admin;
+    let successor = Pubkey::new_unique();
+
+    // Proposing waits out the timelock like any other config change
+    apply_config_change(
+        &mut world,
+        ConfigChange::ProposeSuperAdmin {
+            proposed: successor,
+        },
+    );
+    assert_error(
+        world.process(&[instructions::accept_super_admin(&admin)], &[admin]),
+        ContinuumError::Unauthorized,
+    );
+
+    world
+        .process(
+            &[instructions::accept_super_admin(&successor)],
+            &[successor],
+        )
+        .unwrap();
+    let admin_config: AdminConfig = world.runtime.get(&pda::admin_config().0).unwrap();
+    assert_eq!(admin_config.super_admin, successor);
+    assert_eq!(admin_config.pending_super_admin, Pubkey::default());
+    assert_eq!(world.fifo_state().admin, successor);
+}
+
+#[test]
+fn emergency_pause_blocks_new_orders() {
+    let (mut world, pool, user) = setup();
+    let admin = world.admin;
+    let pending = world.submit_order(&user, &pool, 1_000, 1, true).unwrap();
+
+    world
+        .process(&[instructions::set_emergency_pause(&admin, true)], &[admin])
+        .unwrap();
+    assert!(world.fifo_state().emergency_pause);
+    assert_error(
+        world.submit_order(&user, &pool, 1_000, 1, true),
+        ContinuumError::EmergencyPause,
+    );
+    // Users can still get out
+    world.cancel_order(&pending, None).unwrap();
+
+    assert_error(
+        world.process(
+            &[instructions::set_emergency_pause(&user.key, false)],
+            &[user.key],
+        ),
+        ContinuumError::Unauthorized,
+    );
+    world
+        .process(
+            &[instructions::set_emergency_pause(&admin, false)],
+            &[admin],
+        )
+        .unwrap();
+    world.submit_order(&user, &pool, 1_000, 1, true).unwrap();
+}
+
+fn amend(
+    world: &mut Continuum,
+    user: &User,
+    order_address: &Pubkey,
+    amount_in: u64,
+) -> Result<TransactionMeta, TransactionError> {
+    let order = world.order(order_address);
+    let ix = instructions::amend_order(
+        &user.key,
+        order_address,
+        &order,
+        Some(world.fifo_state().current_sequence),
+        world.queue_neighbors(&order),
+        world.queue_tail(&order.pool_id),
+        amount_in,
+        order.min_amount_out,
+    );
+    world.process(&[ix], &[user.key])
+}
+
+#[test]
+fn draining_pools_settle_queued_orders_but_take_no_new_ones() {
+    let (mut world, pool, user) = setup();
+    let input = pool.token_0_mint;
+    let executed = world.submit_order(&user, &pool, 1_000, 1, true).unwrap();
+    let cancelled = world.submit_order(&user, &pool, 1_000, 1, true).unwrap();
+    let amended = world.submit_order(&user, &pool, 1_000, 1, true).unwrap();
+    set_pool_status(&mut world, &pool, PoolStatus::Draining);
+
+    assert_error(
+        world.submit_order(&user, &pool, 1_000, 1, true),
+        ContinuumError::PoolNotAcceptingOrders,
+    );
+    assert_error(
+        swap_immediately(&mut world, &pool, &user, &input, 1_000, 1, true),
+        ContinuumError::PoolNotAcceptingOrders,
+    );
+    // Neither growing an order nor shrinking it in place
+    for amount_in in [2_000, 500] {
+        assert_error(
+            amend(&mut world, &user, &amended, amount_in),
+            ContinuumError::PoolNotAcceptingOrders,
+        );
+    }
+
+    world
+        .execute_order(&executed, &user, &pool, &input)
+        .unwrap();
+    assert_eq!(world.order(&executed).status, OrderStatus::Executed);
+    world.cancel_order(&cancelled, None).unwrap();
+    assert_eq!(world.order(&cancelled).status, OrderStatus::Cancelled);
+    world.execute_order(&amended, &user, &pool, &input).unwrap();
+    assert_eq!(world.pool_registry(&pool.pool_state).queue.pending_count, 0);
+}
+
+#[test]
+fn inactive_pools_refuse_everything_but_withdrawing_orders() {
+    let (mut world, pool, user) = setup();
+    let input = pool.token_0_mint;
+    let queued = world.submit_order(&user, &pool, 1_000, 1, true).unwrap();
+    let cancelled = world.submit_order(&user, &pool, 1_000, 1, true).unwrap();
+    set_pool_status(&mut world, &pool, PoolStatus::Inactive);
+
+    assert_error(
+        world.submit_order(&user, &pool, 1_000, 1, true),
+        ContinuumError::PoolNotAcceptingOrders,
+    );
+    assert_error(
+        swap_immediately(&mut world, &pool, &user, &input, 1_000, 1, true),
+        ContinuumError::PoolNotAcceptingOrders,
+    );
+    assert_error(
+        amend(&mut world, &user, &queued, 500),
+        ContinuumError::PoolNotAcceptingOrders,
+    );
+    assert_error(
+        world.execute_order(&queued, &user, &pool, &input),
+        ContinuumError::PoolNotRegistered,
+    );
+
+    // Users can still take their orders, and bonds, back
+    world.cancel_order(&cancelled, None).unwrap();
+    assert_eq!(world.order(&cancelled).status, OrderStatus::Cancelled);
+
+    // Reactivated, the queued order executes where it was
+    set_pool_status(&mut world, &pool, PoolStatus::Active);
+    world.execute_order(&queued, &user, &pool, &input).unwrap();
+    assert_eq!(world.order(&queued).status, OrderStatus::Executed);
+}
+
+#[test]
+fn config_changes_wait_for_the_timelock() {
+    let (mut world, pool, user) = setup();
+    let admin = world.admin;
+    let change = ConfigChange::SetPoolStatus {
+        pool_id: pool.pool_state,
+        status: PoolStatus::Draining,
+    };
+
+    world
+        .process(
+            &[instructions::queue_config_change(
+                &admin,
+                &admin,
+                0,
+                change.clone(),
+            )],
+            &[admin],
+        )
+        .unwrap();
+    let pending: PendingChange = world.runtime.get(&pda::pending_change(0).0).unwrap();
+    assert_eq!(
+        pending.effective_after,
+        world.runtime.clock().unix_timestamp + DEFAULT_TIMELOCK_DELAY
+    );
+
+    let execute = instructions::execute_config_change(0, &admin, Some(pool.pool_state));
+    assert_error(
+        world.process(std::slice::from_ref(&execute), &[admin]),
+        ContinuumError::TimelockNotElapsed,
+    );
+    world.runtime.advance_clock(DEFAULT_TIMELOCK_DELAY);
+    world.process(&[execute], &[admin]).unwrap();
+    assert!(world
+        .runtime
+        .get_account(&pda::pending_change(0).0)
+        .is_none());
+    assert_error(
+        world.submit_order(&user, &pool, 1_000, 1, true),
+        ContinuumError::PoolNotAcceptingOrders,
+    );
+
+    // A queued change can be dropped before it applies
+    world
+        .process(
+            &[instructions::queue_config_change(&admin, &admin, 1, change)],
+            &[admin],
+        )
+        .unwrap();
+    world
+        .process(
+            &[instructions::cancel_config_change(&admin, 1, &admin)],
+            &[admin],
+        )
+        .unwrap();
+    assert!(world
+        .runtime
+        .get_account(&pda::pending_change(1).0)
+        .is_none());
+}
+
+#[test]
+fn config_changes_lapse_when_the_proposer_loses_the_role() {
+    let (mut world, pool, _) = setup();
+    let admin = world.admin;
+    let manager = Pubkey::new_unique();
+    world.runtime.airdrop(&manager, 1_000_000_000);
+    apply_config_change(
+        &mut world,
+        ConfigChange::SetRole {
+            role: AdminRole::PoolManager,
+            new_holder: manager,
+        },
+    );
+
+    // The manager queues a status change, then is replaced before it applies
+    let change_count = |world: &Continuum| {
+        world
+            .runtime
+            .get::<AdminConfig>(&pda::admin_config().0)
+            .unwrap()
+            .change_count
+    };
+    let status_change = change_count(&world);
+    world
+        .process(
+            &[instructions::queue_config_change(
+                &manager,
+                &manager,
+                status_change,
+                ConfigChange::SetPoolStatus {
+                    pool_id: pool.pool_state,
+                    status: PoolStatus::Inactive,
+                },
+            )],
+            &[manager],
+        )
+        .unwrap();
+    apply_config_change(
+        &mut world,
+        ConfigChange::SetRole {
+            role: AdminRole::PoolManager,
+            new_holder: admin,
+        },
+    );
+
+    assert_error(
+        world.process(
+            &[instructions::execute_config_change(
+                status_change,
+                &manager,
+                Some(pool.pool_state),
+            )],
+            &[admin],
+        ),
+        ContinuumError::Unauthorized,
+    );
+    assert_eq!(
+        world.pool_registry(&pool.pool_state).status(),
+        PoolStatus::Active
+    );
+}
+
+#[test]
+fn accounts_from_a_newer_layout_version_are_refused() {
+    let (mut world, pool, user) = setup();
+    let order = world
+        .submit_order(&user, &pool, 1_000_000, 1, true)
+        .unwrap();
+
+    // The version byte follows the fixed fields, the unset `executed_at` and
+    // the route
+    let order_state = world.order(&order);
+    let offset = 8 + 8 + 32 + 32 + 8 + 8 + 1 + 1 + 8 + 1 + 1 + 32 * (MAX_ROUTE_LEN - 1) + 8;
+    let mut account = world.runtime.get_account(&order).unwrap().clone();
+    assert_eq!(account.data[offset], STATE_VERSION);
+    account.data[offset] = STATE_VERSION + 1;
+    world.runtime.set_account(order, account);
+    assert!(world.runtime.get::<OrderState>(&order).is_none());
+
+    let ix = instructions::cancel_order(
+        &user.key,
+        &order,
+        &order_state,
+        QueueNeighbors::default(),
+        None,
+    );
+    assert_error(
+        world.process(&[ix], &[user.key]),
+        anchor_lang::error::ErrorCode::AccountDidNotDeserialize,
+    );
+}
+
+/// Overwrite `address` with a pre-versioning account of type `A`, `len` bytes
+/// long like the accounts the old program allocated
+fn write_legacy<A: Discriminator>(
+    world: &mut Continuum,
+    address: Pubkey,
+    len: usize,
+    legacy: impl AnchorSerialize,
+) {
+    let mut data = A::DISCRIMINATOR.to_vec();
+    legacy.serialize(&mut data).unwrap();
+    data.resize(len, 0);
+    let lamports = world.runtime.rent().minimum_balance(data.len());
+    world
+        .runtime
+        .set_account(address, Account::new(lamports, data, continuum_cp_swap::ID));
+}
+
+#[test]
+fn migrations_upgrade_legacy_accounts_in_place() {
+    let (mut world, pool, user) = setup();
+    let admin = world.admin;
+
+    write_legacy::<FifoState>(
+        &mut world,
+        pda::fifo_state().0,
+        FifoStateV0::LEN,
+        // current_sequence, admin, emergency_pause
+        (41u64, admin, false),
+    );
+    write_legacy::<CpSwapPoolRegistry>(
+        &mut world,
+        pda::pool_registry(&pool.pool_state).0,
+        CpSwapPoolRegistryV0::LEN,
+        // pool_id, token_0, token_1, continuum_authority, created_at, is_active
+        (
+            pool.pool_state,
+            pool.token_0_mint,
+            pool.token_1_mint,
+            pool.authority(),
+            1i64,
+            true,
+        ),
+    );
+    // Written before pending orders were counted, at its PDA so it can settle
+    let legacy_order = pda::order(&user.key, 6).0;
+    write_legacy::<OrderState>(
+        &mut world,
+        legacy_order,
+        OrderStateV0::LEN,
+        // sequence, user, pool_id, amount_in, min_amount_out, is_base_input,
+        // status, submitted_at, executed_at
+        (
+            7u64,
+            user.key,
+            pool.pool_state,
+            1_000u64,
+            1u64,
+            true,
+            OrderStatus::Pending,
+            1i64,
+            None::<i64>,
+        ),
+    );
+    // An order written before partial fills
+    let v1_order = Pubkey::new_unique();
+    write_legacy::<OrderState>(
+        &mut world,
+        v1_order,
+        OrderStateV1::LEN,
+        // sequence, user, pool_id, amount_in, min_amount_out, is_base_input,
+        // status, submitted_at, executed_at, route_len
+        (
+            8u64,
+            user.key,
+            pool.pool_state,
+            2_000u64,
+            1u64,
+            true,
+            OrderStatus::Pending,
+            1i64,
+            None::<i64>,
+            1u8,
+        ),
+    );
+    // An order written before its reserve was sized for the planned fields
+    let v2_order = Pubkey::new_unique();
+    write_legacy::<OrderState>(
+        &mut world,
+        v2_order,
+        OrderStateV2::LEN,
+        // sequence, user, pool_id, amount_in, min_amount_out, is_base_input,
+        // status, submitted_at, executed_at, route_len
+        (
+            9u64,
+            user.key,
+            pool.pool_state,
+            3_000u64,
+            1u64,
+            true,
+            OrderStatus::Pending,
+            1i64,
+            None::<i64>,
+            1u8,
+        ),
+    );
+    assert!(world
+        .runtime
+        .get::<FifoState>(&pda::fifo_state().0)
+        .is_none());
+    assert!(world.runtime.get::<OrderState>(&v2_order).is_none());
+
+    // Only the super admin can migrate
+    assert_error(
+        world.process(
+            &[instructions::migrate_fifo_state(&user.key, &user.key)],
+            &[user.key],
+        ),
+        ContinuumError::Unauthorized,
+    );
+
+    // The counters created are the order's own
+    assert_error(
+        world.process(
+            &[instructions::migrate_order(
+                &admin,
+                &admin,
+                &legacy_order,
+                &admin,
+                &pool.pool_state,
+            )],
+            &[admin],
+        ),
+        ContinuumError::Unauthorized,
+    );
+
+    let meta = world
+        .process(
+            &[
+                instructions::migrate_fifo_state(&admin, &admin),
+                instructions::migrate_pool_registry(&admin, &admin, &pool.pool_state),
+                instructions::migrate_order(
+                    &admin,
+                    &admin,
+                    &legacy_order,
+                    &user.key,
+                    &pool.pool_state,
+                ),
+                instructions::migrate_order(&admin, &admin, &v1_order, &user.key, &pool.pool_state),
+                instructions::migrate_order(&admin, &admin, &v2_order, &user.key, &pool.pool_state),
+            ],
+            &[admin],
+        )
+        .unwrap();
+    assert_eq!(meta.events::<AccountMigrated>().len(), 5);
+
+    let fifo_state = world.fifo_state();
+    assert_eq!(
+        (fifo_state.current_sequence, fifo_state.version.get()),
+        (41, STATE_VERSION)
+    );
+    let registry: CpSwapPoolRegistry = world
+        .runtime
+        .get(&pda::pool_registry(&pool.pool_state).0)
+        .unwrap();
+    assert_eq!(registry.token_0_vault, pool.token_0_vault);
+    assert_eq!(registry.version.get(), STATE_VERSION);
+    let order = world.order(&legacy_order);
+    assert_eq!((order.sequence, order.route_len), (7, 1));
+    let order = world.order(&v1_order);
+    assert_eq!((order.sequence, order.amount_in), (8, 2_000));
+    assert_eq!(
+        (
+            order.filled_amount_in,
+            order.fill_policy,
+            order.version.get()
+        ),
+        (0, FillPolicy::FillOrKill, STATE_VERSION)
+    );
+    let order = world.order(&v2_order);
+    assert_eq!(
+        (order.sequence, order.amount_in, order.version.get()),
+        (9, 3_000, STATE_VERSION)
+    );
+
+    // Only the order from before the counters is counted, and it settles
+    // like any other
+    let pending_counts = |world: &Continuum| {
+        let user_state: UserState = world.runtime.get(&pda::user_state(&user.key).0).unwrap();
+        let user_pool_state: UserPoolState = world
+            .runtime
+            .get(&pda::user_pool_state(&user.key, &pool.pool_state).0)
+            .unwrap();
+        (user_state.pending_count, user_pool_state.pending_count)
+    };
+    assert_eq!(pending_counts(&world), (1, 1));
+    world.cancel_order(&legacy_order, None).unwrap();
+    assert_eq!(world.order(&legacy_order).status, OrderStatus::Cancelled);
+    assert_eq!(pending_counts(&world), (0, 0));
+
+    // Migrated accounts are refused a second time
+    assert_error(
+        world.process(
+            &[instructions::migrate_fifo_state(&admin, &admin)],
+            &[admin],
+        ),
+        ContinuumError::UnsupportedAccountVersion,
+    );
+}
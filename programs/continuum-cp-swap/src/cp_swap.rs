@@ -0,0 +1,236 @@
+//! Raw layout of the CP-Swap accounts Continuum reads directly, and the swap
+//! math it mirrors to quote against them
+use crate::errors::ContinuumError;
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{
+    instruction::{AccountMeta, Instruction},
+    program::invoke_signed,
+};
+
+/// The CP-Swap program pools are registered against
+pub const ID: Pubkey = anchor_lang::pubkey!("GkenxCtvEabZrwFf15D3E6LjoZTywH2afNwiqDwthyDp");
+
+/// `authority_type` value for pools controlled by a custom authority
+pub const AUTHORITY_TYPE_CUSTOM: u8 = 1;
+
+/// `swap_base_input` and `swap_base_output` instruction discriminators
+const SWAP_BASE_INPUT: [u8; 8] = [143, 190, 90, 218, 196, 30, 51, 222];
+const SWAP_BASE_OUTPUT: [u8; 8] = [55, 217, 98, 86, 163, 74, 180, 173];
+
+/// Swap instruction data for an order's amounts. Exact-output orders hold the
+/// output amount in `amount_in` and the maximum input in `min_amount_out`.
+pub fn swap_instruction_data(is_base_input: bool, amount_in: u64, min_amount_out: u64) -> [u8; 24] {
+    let (discriminator, first, second) = if is_base_input {
+        (SWAP_BASE_INPUT, amount_in, min_amount_out)
+    } else {
+        (SWAP_BASE_OUTPUT, min_amount_out, amount_in)
+    };
+
+    let mut data = [0u8; 24];
+    data[..8].copy_from_slice(&discriminator);
+    data[8..16].copy_from_slice(&first.to_le_bytes());
+    data[16..].copy_from_slice(&second.to_le_bytes());
+    data
+}
+
+/// Invokes a CP-Swap swap signed by a pool authority PDA. The authority comes
+/// first, then `user_accounts` and the `swap_accounts` passed through as-is,
+/// which is the order the swap instruction expects.
+pub fn invoke_swap<'info>(
+    cp_swap_program: &AccountInfo<'info>,
+    pool_authority: &AccountInfo<'info>,
+    pool_authority_seeds: &[&[u8]],
+    user_accounts: &[AccountInfo<'info>],
+    swap_accounts: &[AccountInfo<'info>],
+    data: [u8; 24],
+) -> Result<()> {
+    let len = 1 + user_accounts.len() + swap_accounts.len();
+    let mut accounts = Vec::with_capacity(len);
+    let mut account_infos = Vec::with_capacity(len);
+
+    accounts.push(AccountMeta::new_readonly(*pool_authority.key, true));
+    account_infos.push(pool_authority.clone());
+    for account in user_accounts.iter().chain(swap_accounts) {
+        accounts.push(AccountMeta {
+            pubkey: *account.key,
+            is_signer: false,
+            is_writable: account.is_writable,
+        });
+        account_infos.push(account.clone());
+    }
+
+    let ix = Instruction {
+        program_id: *cp_swap_program.key,
+        accounts,
+        data: data.to_vec(),
+    };
+    invoke_signed(&ix, &account_infos, &[pool_authority_seeds])?;
+
+    Ok(())
+}
+
+/// Byte offsets into the packed `PoolState` account, discriminator included
+pub mod pool_state {
+    pub const AMM_CONFIG: usize = 8;
+    pub const TOKEN_0_VAULT: usize = 72;
+    pub const TOKEN_1_VAULT: usize = 104;
+    pub const TOKEN_0_MINT: usize = 168;
+    pub const TOKEN_1_MINT: usize = 200;
+    pub const PROTOCOL_FEES_TOKEN_0: usize = 341;
+    pub const PROTOCOL_FEES_TOKEN_1: usize = 349;
+    pub const FUND_FEES_TOKEN_0: usize = 357;
+    pub const FUND_FEES_TOKEN_1: usize = 365;
+    pub const AUTHORITY_TYPE: usize = 389;
+    pub const CUSTOM_AUTHORITY: usize = 390;
+    pub const MIN_LEN: usize = CUSTOM_AUTHORITY + 32;
+}
+
+/// The fields of a CP-Swap `PoolState` Continuum relies on
+pub struct PoolStateView {
+    pub amm_config: Pubkey,
+    pub token_0_vault: Pubkey,
+    pub token_1_vault: Pubkey,
+    pub token_0_mint: Pubkey,
+    pub token_1_mint: Pubkey,
+    /// Protocol and fund fees accrued in each vault, not part of the reserves
+    pub accrued_fees_token_0: u64,
+    pub accrued_fees_token_1: u64,
+    pub authority_type: u8,
+    pub custom_authority: Pubkey,
+}
+
+impl PoolStateView {
+    pub fn load(pool_state: &AccountInfo) -> Result<Self> {
+        require_keys_eq!(*pool_state.owner, ID, ContinuumError::InvalidPoolConfig);
+
+        let data = pool_state.try_borrow_data()?;
+        require!(
+            data.len() >= pool_state::MIN_LEN,
+            ContinuumError::InvalidPoolConfig
+        );
+
+        let read_pubkey = |at: usize| {
+            let mut bytes = [0u8; 32];
+            bytes.copy_from_slice(&data[at..at + 32]);
+            Pubkey::new_from_array(bytes)
+        };
+        let read_u64 = |at: usize| read_u64(&data, at);
+
+        Ok(Self {
+            amm_config: read_pubkey(pool_state::AMM_CONFIG),
+            token_0_vault: read_pubkey(pool_state::TOKEN_0_VAULT),
+            token_1_vault: read_pubkey(pool_state::TOKEN_1_VAULT),
+            token_0_mint: read_pubkey(pool_state::TOKEN_0_MINT),
+            token_1_mint: read_pubkey(pool_state::TOKEN_1_MINT),
+            accrued_fees_token_0: read_u64(pool_state::PROTOCOL_FEES_TOKEN_0)
+                + read_u64(pool_state::FUND_FEES_TOKEN_0),
+            accrued_fees_token_1: read_u64(pool_state::PROTOCOL_FEES_TOKEN_1)
+                + read_u64(pool_state::FUND_FEES_TOKEN_1),
+            authority_type: data[pool_state::AUTHORITY_TYPE],
+            custom_authority: read_pubkey(pool_state::CUSTOM_AUTHORITY),
+        })
+    }
+
+    /// Whether `authority` is the pool's custom authority
+    pub fn is_controlled_by(&self, authority: &Pubkey) -> bool {
+        self.authority_type == AUTHORITY_TYPE_CUSTOM && self.custom_authority == *authority
+    }
+
+    /// Reserves behind the pool price, vault balances less the accrued fees
+    pub fn reserves(&self, vault_0_amount: u64, vault_1_amount: u64) -> Result<(u64, u64)> {
+        Ok((
+            vault_0_amount
+                .checked_sub(self.accrued_fees_token_0)
+                .ok_or(ContinuumError::MathOverflow)?,
+            vault_1_amount
+                .checked_sub(self.accrued_fees_token_1)
+                .ok_or(ContinuumError::MathOverflow)?,
+        ))
+    }
+}
+
+fn read_u64(data: &[u8], at: usize) -> u64 {
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(&data[at..at + 8]);
+    u64::from_le_bytes(bytes)
+}
+
+/// Byte offsets into the packed `AmmConfig` account, discriminator included
+pub mod amm_config {
+    pub const TRADE_FEE_RATE: usize = 12;
+    pub const PROTOCOL_FEE_RATE: usize = 20;
+    pub const FUND_FEE_RATE: usize = 28;
+    pub const MIN_LEN: usize = FUND_FEE_RATE + 8;
+}
+
+/// Fee rates are in hundredths of a basis point
+pub const FEE_RATE_DENOMINATOR: u64 = 1_000_000;
+
+/// The fee rates of a CP-Swap `AmmConfig`. The protocol and fund rates are
+/// shares of the trade fee, not charged on top of it.
+pub struct AmmConfigView {
+    pub trade_fee_rate: u64,
+    pub protocol_fee_rate: u64,
+    pub fund_fee_rate: u64,
+}
+
+impl AmmConfigView {
+    pub fn load(amm_config: &AccountInfo) -> Result<Self> {
+        require_keys_eq!(*amm_config.owner, ID, ContinuumError::InvalidPoolConfig);
+
+        let data = amm_config.try_borrow_data()?;
+        require!(
+            data.len() >= amm_config::MIN_LEN,
+            ContinuumError::InvalidPoolConfig
+        );
+
+        Ok(Self {
+            trade_fee_rate: read_u64(&data, amm_config::TRADE_FEE_RATE),
+            protocol_fee_rate: read_u64(&data, amm_config::PROTOCOL_FEE_RATE),
+            fund_fee_rate: read_u64(&data, amm_config::FUND_FEE_RATE),
+        })
+    }
+
+    /// Fee CP-Swap charges on `amount_in`, rounded up
+    pub fn trade_fee(&self, amount_in: u64) -> Result<u64> {
+        let fee = (amount_in as u128 * self.trade_fee_rate as u128)
+            .div_ceil(FEE_RATE_DENOMINATOR as u128);
+        u64::try_from(fee).map_err(|_| error!(ContinuumError::MathOverflow))
+    }
+
+    /// Protocol and fund shares of `trade_fee`, rounded down
+    pub fn split_trade_fee(&self, trade_fee: u64) -> (u64, u64) {
+        let share =
+            |rate: u64| (trade_fee as u128 * rate as u128 / FEE_RATE_DENOMINATOR as u128) as u64;
+        (share(self.protocol_fee_rate), share(self.fund_fee_rate))
+    }
+}
+
+/// Constant-product output for an exact `amount_in`, as CP-Swap computes it
+pub fn swap_base_input(
+    config: &AmmConfigView,
+    amount_in: u64,
+    input_reserve: u64,
+    output_reserve: u64,
+) -> Result<u64> {
+    let amount_in_less_fee = (amount_in - config.trade_fee(amount_in)?) as u128;
+    let denominator = input_reserve as u128 + amount_in_less_fee;
+    require!(denominator > 0, ContinuumError::MathOverflow);
+    u64::try_from(amount_in_less_fee * output_reserve as u128 / denominator)
+        .map_err(|_| error!(ContinuumError::MathOverflow))
+}
+
+/// Constant-product input, fee included, for an exact `amount_out`
+pub fn swap_base_output(
+    config: &AmmConfigView,
+    amount_out: u64,
+    input_reserve: u64,
+    output_reserve: u64,
+) -> Result<u64> {
+    require!(amount_out < output_reserve, ContinuumError::InvalidAmount);
+    let amount_in_less_fee = (amount_out as u128 * input_reserve as u128)
+        .div_ceil((output_reserve - amount_out) as u128);
+    let amount_in = (amount_in_less_fee * FEE_RATE_DENOMINATOR as u128)
+        .div_ceil((FEE_RATE_DENOMINATOR - config.trade_fee_rate) as u128);
+    u64::try_from(amount_in).map_err(|_| error!(ContinuumError::MathOverflow))
+}
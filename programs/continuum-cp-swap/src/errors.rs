@@ -31,4 +31,88 @@ pub enum ContinuumError {
 
     #[msg("Invalid order status")]
     InvalidOrderStatus,
+
+    #[msg("Invalid multi-hop route")]
+    InvalidRoute,
+
+    #[msg("Order type not supported by this instruction")]
+    InvalidOrderType,
+
+    #[msg("Missing or invalid ed25519 intent signature")]
+    InvalidIntentSignature,
+
+    #[msg("Intent nonce does not match the user nonce")]
+    InvalidIntentNonce,
+
+    #[msg("Intent deadline has passed")]
+    IntentExpired,
+
+    #[msg("Invalid amount")]
+    InvalidAmount,
+
+    #[msg("Tail order account must be passed exactly when an amendment loses queue priority")]
+    InvalidAmendment,
+
+    #[msg("Too many pending orders for this user")]
+    TooManyPendingOrders,
+
+    #[msg("Order has not expired yet")]
+    OrderNotExpired,
+
+    #[msg("Pool is inactive or draining")]
+    PoolNotAcceptingOrders,
+
+    #[msg("Invalid config change")]
+    InvalidConfigChange,
+
+    #[msg("Config change timelock has not elapsed")]
+    TimelockNotElapsed,
+
+    #[msg("Account is already migrated or has an unknown layout")]
+    UnsupportedAccountVersion,
+
+    #[msg("Math overflow")]
+    MathOverflow,
+
+    #[msg("Queue neighbor order is missing or does not match")]
+    InvalidQueueNeighbor,
+
+    #[msg("Order ring is full")]
+    OrderRingFull,
+
+    #[msg("Order was submitted too few slots ago to be executed")]
+    InclusionDelayNotElapsed,
+
+    #[msg("Pool does not accept committed orders")]
+    CommitRevealDisabled,
+
+    #[msg("Revealed parameters do not match the order commitment")]
+    InvalidCommitment,
+
+    #[msg("Reveal window has closed")]
+    RevealWindowClosed,
+
+    #[msg("Executor has no eligible relayer stake for this pool")]
+    RelayerNotEligible,
+
+    #[msg("Relayer stake is being withdrawn")]
+    RelayerUnstaking,
+
+    #[msg("Relayer unstake delay has not elapsed")]
+    UnstakeDelayNotElapsed,
+
+    #[msg("Orders do not show the relayer skipping an eligible order")]
+    InvalidMisbehaviorEvidence,
+
+    #[msg("Order does not allow partial fills")]
+    PartialFillNotAllowed,
+
+    #[msg("Order can still be executed")]
+    OrderStillExecutable,
+
+    #[msg("Token account is not the order's funding source")]
+    InvalidSourceAccount,
+
+    #[msg("Account is not the order's bond payer")]
+    InvalidBondPayer,
 }
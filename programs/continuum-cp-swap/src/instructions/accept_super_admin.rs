@@ -0,0 +1,46 @@
+use crate::errors::ContinuumError;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct AcceptSuperAdmin<'info> {
+    #[account(
+        mut,
+        seeds = [b"admin_config"],
+        bump,
+        constraint = admin_config.pending_super_admin == new_super_admin.key() @ ContinuumError::Unauthorized,
+    )]
+    pub admin_config: Account<'info, AdminConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"fifo_state"],
+        bump,
+    )]
+    pub fifo_state: Account<'info, FifoState>,
+
+    /// The proposed super admin, may be a multisig PDA signing through CPI
+    pub new_super_admin: Signer<'info>,
+}
+
+pub fn accept_super_admin(ctx: Context<AcceptSuperAdmin>) -> Result<()> {
+    let admin_config = &mut ctx.accounts.admin_config;
+    let old_super_admin = admin_config.super_admin;
+    let new_super_admin = ctx.accounts.new_super_admin.key();
+
+    admin_config.super_admin = new_super_admin;
+    admin_config.pending_super_admin = Pubkey::default();
+    // Keep the legacy FIFO admin field in sync for existing clients
+    ctx.accounts.fifo_state.admin = new_super_admin;
+
+    emit_cpi!(SuperAdminTransferred {
+        version: EVENT_VERSION,
+        old_super_admin,
+        new_super_admin,
+    });
+
+    msg!("Super admin transferred to {}", new_super_admin);
+
+    Ok(())
+}
@@ -0,0 +1,224 @@
+use crate::errors::*;
+use crate::state::*;
+use crate::utils::{debug_msg, transfer_lamports};
+use anchor_lang::prelude::*;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct AmendOrder<'info> {
+    #[account(
+        mut,
+        seeds = [b"fifo_state"],
+        bump,
+        constraint = !fifo_state.emergency_pause @ ContinuumError::EmergencyPause,
+    )]
+    pub fifo_state: Account<'info, FifoState>,
+
+    #[account(
+        mut,
+        constraint = order_state.status == OrderStatus::Pending @ ContinuumError::InvalidOrderStatus,
+        constraint = order_state.user == user.key() @ ContinuumError::Unauthorized,
+    )]
+    pub order_state: Account<'info, OrderState>,
+
+    /// New terms are a new submission, which draining and inactive pools refuse
+    #[account(
+        mut,
+        seeds = [b"pool_registry", order_state.pool_id.as_ref()],
+        bump,
+        constraint = pool_registry.accepts_orders() @ ContinuumError::PoolNotAcceptingOrders,
+    )]
+    pub pool_registry: Account<'info, CpSwapPoolRegistry>,
+
+    /// Orders before and after this one in the pool queue, and the queue tail,
+    /// required when the amendment loses queue priority and they exist. The tail
+    /// is not passed again when it is one of the neighbors.
+    #[account(mut)]
+    pub queue_prev: Option<Account<'info, OrderState>>,
+    #[account(mut)]
+    pub queue_next: Option<Account<'info, OrderState>>,
+    #[account(mut)]
+    pub queue_tail: Option<Account<'info, OrderState>>,
+
+    /// Order account at the tail of the queue, required only when the
+    /// amendment loses queue priority
+    #[account(
+        init,
+        payer = user,
+        space = OrderState::LEN,
+        seeds = [b"order", user.key().as_ref(), &fifo_state.current_sequence.to_le_bytes()],
+        bump
+    )]
+    pub new_order_state: Option<Account<'info, OrderState>>,
+
+    /// Index of the order, closed when the order moves to the tail. Orders
+    /// submitted before indexing have none.
+    #[account(
+        mut,
+        seeds = [b"order_index", order_state.pool_id.as_ref(), &order_state.sequence.to_le_bytes()],
+        bump
+    )]
+    pub order_index: Option<Account<'info, OrderIndex>>,
+
+    /// Index of the re-queued order, required along with `new_order_state`
+    #[account(
+        init,
+        payer = user,
+        space = OrderIndex::LEN,
+        seeds = [
+            b"order_index",
+            order_state.pool_id.as_ref(),
+            &(fifo_state.current_sequence + 1).to_le_bytes(),
+        ],
+        bump
+    )]
+    pub new_order_index: Option<Account<'info, OrderIndex>>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+    pub clock: Sysvar<'info, Clock>,
+}
+
+pub fn amend_order(
+    ctx: Context<AmendOrder>,
+    new_amount_in: u64,
+    new_min_amount_out: u64,
+) -> Result<()> {
+    require!(new_amount_in > 0, ContinuumError::InvalidAmount);
+
+    let order = &ctx.accounts.order_state;
+    // Fills were priced against the current terms
+    require!(order.filled_size() == 0, ContinuumError::InvalidAmendment);
+    let old_sequence = order.sequence;
+    let old_amount_in = order.amount_in;
+    let old_min_amount_out = order.min_amount_out;
+
+    let keeps_priority = keeps_queue_priority(order, new_amount_in, new_min_amount_out);
+
+    let new_sequence = if keeps_priority {
+        require!(
+            ctx.accounts.new_order_state.is_none() && ctx.accounts.new_order_index.is_none(),
+            ContinuumError::InvalidAmendment
+        );
+
+        let order_state = &mut ctx.accounts.order_state;
+        order_state.amount_in = new_amount_in;
+        order_state.min_amount_out = new_min_amount_out;
+        // The new terms wait out the inclusion delay like a new submission
+        order_state.submitted_slot = ctx.accounts.clock.slot;
+
+        old_sequence
+    } else {
+        // Any other change re-queues the order at the tail, like a new submission
+        let fifo_state = &mut ctx.accounts.fifo_state;
+        let new_sequence = fifo_state.current_sequence + 1;
+        fifo_state.current_sequence = new_sequence;
+
+        let old_order = &ctx.accounts.order_state;
+        let new_order_state = ctx
+            .accounts
+            .new_order_state
+            .as_mut()
+            .ok_or(ContinuumError::InvalidAmendment)?;
+
+        new_order_state.sequence = new_sequence;
+        new_order_state.user = old_order.user;
+        new_order_state.pool_id = old_order.pool_id;
+        new_order_state.amount_in = new_amount_in;
+        new_order_state.min_amount_out = new_min_amount_out;
+        new_order_state.is_base_input = old_order.is_base_input;
+        new_order_state.status = OrderStatus::Pending;
+        new_order_state.submitted_at = ctx.accounts.clock.unix_timestamp;
+        new_order_state.submitted_slot = ctx.accounts.clock.slot;
+        new_order_state.executed_at = None;
+        new_order_state.route_len = old_order.route_len;
+        new_order_state.route_pools = old_order.route_pools;
+        new_order_state.bond_lamports = old_order.bond_lamports;
+        new_order_state.fill_policy = old_order.fill_policy;
+        new_order_state.source = old_order.source;
+        new_order_state.bond_payer = old_order.bond_payer;
+        new_order_state.version = StateVersion::CURRENT;
+
+        // Move the order from its place in the pool queue to the tail
+        let pool_registry = &mut ctx.accounts.pool_registry;
+        pool_registry.dequeue(
+            &mut ctx.accounts.order_state,
+            ctx.accounts.queue_prev.as_deref_mut(),
+            ctx.accounts.queue_next.as_deref_mut(),
+            ctx.accounts.clock.unix_timestamp,
+        )?;
+        let tail_sequence = pool_registry.queue.tail_sequence;
+        let tail = [
+            ctx.accounts.queue_prev.as_deref_mut(),
+            ctx.accounts.queue_next.as_deref_mut(),
+            ctx.accounts.queue_tail.as_deref_mut(),
+        ]
+        .into_iter()
+        .flatten()
+        .find(|order| order.sequence == tail_sequence);
+        pool_registry.enqueue(new_order_state, tail)?;
+        let old_order = &ctx.accounts.order_state;
+
+        ctx.accounts
+            .new_order_index
+            .as_mut()
+            .ok_or(ContinuumError::InvalidAmendment)?
+            .order = new_order_state.key();
+        if let Some(order_index) = &ctx.accounts.order_index {
+            order_index.close(ctx.accounts.user.to_account_info())?;
+        }
+
+        // Carry the bond over before the old account is closed to the user
+        transfer_lamports(
+            &old_order.to_account_info(),
+            &new_order_state.to_account_info(),
+            old_order.bond_lamports,
+        )?;
+
+        ctx.accounts
+            .order_state
+            .close(ctx.accounts.user.to_account_info())?;
+
+        new_sequence
+    };
+
+    emit_cpi!(OrderAmended {
+        version: EVENT_VERSION,
+        old_sequence,
+        new_sequence,
+        user: ctx.accounts.user.key(),
+        old_amount_in,
+        new_amount_in,
+        old_min_amount_out,
+        new_min_amount_out,
+        kept_queue_position: keeps_priority,
+        amended_at: ctx.accounts.clock.unix_timestamp,
+    });
+
+    debug_msg!(
+        "Order {} amended, queue sequence now {}",
+        old_sequence,
+        new_sequence
+    );
+
+    Ok(())
+}
+
+/// An amendment keeps its place in the queue only if it shrinks the order
+/// and/or tightens its price limit. For exact-output orders `amount_in` holds
+/// the output amount and `min_amount_out` the maximum input, so tightening
+/// means lowering the maximum input at least in proportion to the output,
+/// leaving the input allowed per unit of output no higher than before.
+fn keeps_queue_priority(order: &OrderState, new_amount_in: u64, new_min_amount_out: u64) -> bool {
+    let reduces_size = new_amount_in <= order.amount_in;
+    let tightens_limit = if order.is_base_input {
+        new_min_amount_out >= order.min_amount_out
+    } else {
+        new_min_amount_out as u128 * order.amount_in as u128
+            <= order.min_amount_out as u128 * new_amount_in as u128
+    };
+
+    reduces_size && tightens_limit
+}
@@ -0,0 +1,43 @@
+use crate::errors::ContinuumError;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CancelConfigChange<'info> {
+    #[account(
+        seeds = [b"admin_config"],
+        bump,
+        constraint = pending_change.change.is_authorized(&admin_config, &admin.key()) @ ContinuumError::Unauthorized,
+    )]
+    pub admin_config: Account<'info, AdminConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"pending_change".as_ref(), &pending_change.id.to_le_bytes()],
+        bump,
+        close = payer,
+    )]
+    pub pending_change: Account<'info, PendingChange>,
+
+    /// Holder of the role the change requires, or the super admin
+    pub admin: Signer<'info>,
+
+    /// Receives the pending change rent
+    #[account(mut, address = pending_change.payer @ ContinuumError::Unauthorized)]
+    pub payer: SystemAccount<'info>,
+}
+
+pub fn cancel_config_change(ctx: Context<CancelConfigChange>) -> Result<()> {
+    let id = ctx.accounts.pending_change.id;
+
+    emit_cpi!(ConfigChangeCancelled {
+        version: EVENT_VERSION,
+        id,
+        cancelled_by: ctx.accounts.admin.key(),
+    });
+
+    msg!("Config change {} cancelled", id);
+
+    Ok(())
+}
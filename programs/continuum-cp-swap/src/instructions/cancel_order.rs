@@ -1,36 +1,140 @@
 use crate::errors::*;
 use crate::state::*;
+use crate::utils::{close_order_index, debug_msg, transfer_lamports};
 use anchor_lang::prelude::*;
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct CancelOrder<'info> {
     #[account(
         mut,
-        seeds = [b"order", user.key().as_ref(), &order_state.sequence.to_le_bytes()],
+        seeds = [b"order", user.key().as_ref(), &order_state.seed_sequence().to_le_bytes()],
         bump,
         constraint = order_state.status == OrderStatus::Pending @ ContinuumError::InvalidOrderStatus,
         constraint = order_state.user == user.key() @ ContinuumError::Unauthorized,
     )]
     pub order_state: Account<'info, OrderState>,
 
+    #[account(
+        mut,
+        seeds = [b"user_state", order_state.user.as_ref()],
+        bump,
+    )]
+    pub user_state: Account<'info, UserState>,
+
+    #[account(
+        mut,
+        seeds = [b"user_pool_state", order_state.user.as_ref(), order_state.pool_id.as_ref()],
+        bump,
+    )]
+    pub user_pool_state: Account<'info, UserPoolState>,
+
+    /// Index of the order, closed to the user when the order settles
+    /// CHECK: Seeds are checked, orders submitted before indexing have none
+    #[account(
+        mut,
+        seeds = [b"order_index", order_state.pool_id.as_ref(), &order_state.sequence.to_le_bytes()],
+        bump
+    )]
+    pub order_index: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"pool_registry", order_state.pool_id.as_ref()],
+        bump,
+    )]
+    pub pool_registry: Account<'info, CpSwapPoolRegistry>,
+
+    /// Orders before and after this one in the pool queue, required when linked
+    #[account(mut)]
+    pub queue_prev: Option<Account<'info, OrderState>>,
+    #[account(mut)]
+    pub queue_next: Option<Account<'info, OrderState>>,
+
+    /// Receives the forfeited part of the bond, required only on early cancellation
+    /// CHECK: Checked against the pool's configured bond recipient
+    #[account(
+        mut,
+        address = pool_registry.bond_recipient @ ContinuumError::InvalidPoolConfig,
+    )]
+    pub bond_recipient: Option<UncheckedAccount<'info>>,
+
     #[account(mut)]
     pub user: Signer<'info>,
 
+    /// Receives the bond, the user unless a relayer posted it
+    /// CHECK: Checked against the order's bond payer
+    #[account(
+        mut,
+        address = order_state.bond_refund_recipient() @ ContinuumError::InvalidBondPayer,
+    )]
+    pub bond_payer: UncheckedAccount<'info>,
+
     pub clock: Sysvar<'info, Clock>,
 }
 
 pub fn cancel_order(ctx: Context<CancelOrder>) -> Result<()> {
+    let now = ctx.accounts.clock.unix_timestamp;
     let order_state = &mut ctx.accounts.order_state;
 
     order_state.status = OrderStatus::Cancelled;
-    order_state.executed_at = Some(ctx.accounts.clock.unix_timestamp);
+    order_state.executed_at = Some(now);
+    ctx.accounts.pool_registry.dequeue(
+        order_state,
+        ctx.accounts.queue_prev.as_deref_mut(),
+        ctx.accounts.queue_next.as_deref_mut(),
+        now,
+    )?;
+    ctx.accounts.user_state.release_pending();
+    ctx.accounts.user_pool_state.release_pending();
+
+    // Cancelling before the minimum resting period forfeits part of the bond
+    let bond = order_state.bond_lamports;
+    let slashed = ctx
+        .accounts
+        .pool_registry
+        .bond_slash(bond, order_state.submitted_at, now);
+    if slashed > 0 {
+        let bond_recipient = ctx
+            .accounts
+            .bond_recipient
+            .as_ref()
+            .ok_or(ContinuumError::InvalidPoolConfig)?;
+        transfer_lamports(
+            &order_state.to_account_info(),
+            &bond_recipient.to_account_info(),
+            slashed,
+        )?;
+
+        emit_cpi!(BondSlashed {
+            version: EVENT_VERSION,
+            sequence: order_state.sequence,
+            user: ctx.accounts.user.key(),
+            slashed,
+            recipient: bond_recipient.key(),
+        });
+    }
+
+    // The rest of the bond goes back to whoever posted it
+    transfer_lamports(
+        &order_state.to_account_info(),
+        &ctx.accounts.bond_payer.to_account_info(),
+        bond - slashed,
+    )?;
+    close_order_index(
+        &ctx.accounts.order_index,
+        &ctx.accounts.user.to_account_info(),
+    )?;
 
-    emit!(OrderCancelled {
+    emit_cpi!(OrderCancelled {
+        version: EVENT_VERSION,
         sequence: order_state.sequence,
         user: ctx.accounts.user.key(),
+        bond_refunded: bond - slashed,
+        cancelled_at: now,
     });
 
-    msg!("Order {} cancelled by user", order_state.sequence);
+    debug_msg!("Order {} cancelled by user", order_state.sequence);
 
     Ok(())
 }
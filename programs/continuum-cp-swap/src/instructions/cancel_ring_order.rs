@@ -0,0 +1,113 @@
+use crate::errors::*;
+use crate::state::*;
+use crate::utils::{debug_msg, transfer_lamports};
+use anchor_lang::prelude::*;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CancelRingOrder<'info> {
+    #[account(
+        mut,
+        seeds = [b"order_ring", pool_id.key().as_ref()],
+        bump,
+    )]
+    pub order_ring: AccountLoader<'info, OrderRing>,
+
+    #[account(
+        seeds = [b"pool_registry", pool_id.key().as_ref()],
+        bump,
+    )]
+    pub pool_registry: Account<'info, CpSwapPoolRegistry>,
+
+    #[account(
+        mut,
+        seeds = [b"user_state", user.key().as_ref()],
+        bump,
+    )]
+    pub user_state: Account<'info, UserState>,
+
+    #[account(
+        mut,
+        seeds = [b"user_pool_state", user.key().as_ref(), pool_id.key().as_ref()],
+        bump,
+    )]
+    pub user_pool_state: Account<'info, UserPoolState>,
+
+    /// Receives the forfeited part of the bond, required only on early cancellation
+    /// CHECK: Checked against the pool's configured bond recipient
+    #[account(
+        mut,
+        address = pool_registry.bond_recipient @ ContinuumError::InvalidPoolConfig,
+    )]
+    pub bond_recipient: Option<UncheckedAccount<'info>>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    /// CHECK: The pool the ring belongs to
+    pub pool_id: UncheckedAccount<'info>,
+
+    pub clock: Sysvar<'info, Clock>,
+}
+
+pub fn cancel_ring_order(ctx: Context<CancelRingOrder>, sequence: u64) -> Result<()> {
+    let user = ctx.accounts.user.key();
+    let now = ctx.accounts.clock.unix_timestamp;
+    let mut order_ring = ctx.accounts.order_ring.load_mut()?;
+
+    let record = order_ring
+        .get_mut(sequence)
+        .ok_or(ContinuumError::InvalidSequence)?;
+    require!(record.cancelled == 0, ContinuumError::InvalidOrderStatus);
+    require_keys_eq!(record.user, user, ContinuumError::Unauthorized);
+
+    // The slot is freed once the record reaches the head
+    record.cancelled = 1;
+    let bond = record.bond_lamports;
+    let submitted_at = record.submitted_at;
+    order_ring.discard_cancelled();
+    drop(order_ring);
+    ctx.accounts.user_state.release_pending();
+    ctx.accounts.user_pool_state.release_pending();
+
+    // Cancelling before the minimum resting period forfeits part of the bond
+    let order_ring = ctx.accounts.order_ring.to_account_info();
+    let slashed = ctx
+        .accounts
+        .pool_registry
+        .bond_slash(bond, submitted_at, now);
+    if slashed > 0 {
+        let bond_recipient = ctx
+            .accounts
+            .bond_recipient
+            .as_ref()
+            .ok_or(ContinuumError::InvalidPoolConfig)?;
+        transfer_lamports(&order_ring, &bond_recipient.to_account_info(), slashed)?;
+
+        emit_cpi!(BondSlashed {
+            version: EVENT_VERSION,
+            sequence,
+            user,
+            slashed,
+            recipient: bond_recipient.key(),
+        });
+    }
+    transfer_lamports(
+        &order_ring,
+        &ctx.accounts.user.to_account_info(),
+        bond - slashed,
+    )?;
+
+    emit_cpi!(RingOrderCancelled {
+        version: EVENT_VERSION,
+        pool_id: ctx.accounts.pool_id.key(),
+        sequence,
+        user,
+        bond_refunded: bond - slashed,
+        cancelled_at: now,
+    });
+
+    debug_msg!("Ring order {} cancelled", sequence);
+
+    Ok(())
+}
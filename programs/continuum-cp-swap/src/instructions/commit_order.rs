@@ -0,0 +1,164 @@
+use crate::errors::*;
+use crate::state::*;
+use crate::utils::debug_msg;
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CommitOrder<'info> {
+    #[account(
+        mut,
+        seeds = [b"fifo_state"],
+        bump,
+        constraint = !fifo_state.emergency_pause @ ContinuumError::EmergencyPause,
+    )]
+    pub fifo_state: Account<'info, FifoState>,
+
+    #[account(
+        mut,
+        seeds = [b"pool_registry", pool_id.key().as_ref()],
+        bump,
+        constraint = pool_registry.accepts_orders() @ ContinuumError::PoolNotAcceptingOrders,
+        constraint = pool_registry.reveal_window > 0 @ ContinuumError::CommitRevealDisabled,
+    )]
+    pub pool_registry: Account<'info, CpSwapPoolRegistry>,
+
+    /// Current tail of the pool queue, required unless the queue is empty
+    #[account(mut)]
+    pub queue_tail: Option<Account<'info, OrderState>>,
+
+    #[account(
+        init,
+        payer = user,
+        space = OrderState::LEN,
+        seeds = [b"order", user.key().as_ref(), &fifo_state.current_sequence.to_le_bytes()],
+        bump
+    )]
+    pub order_state: Account<'info, OrderState>,
+
+    #[account(
+        init,
+        payer = user,
+        space = OrderCommitment::LEN,
+        seeds = [b"order_commitment", order_state.key().as_ref()],
+        bump
+    )]
+    pub order_commitment: Account<'info, OrderCommitment>,
+
+    #[account(
+        init,
+        payer = user,
+        space = OrderIndex::LEN,
+        seeds = [
+            b"order_index",
+            pool_id.key().as_ref(),
+            &(fifo_state.current_sequence + 1).to_le_bytes(),
+        ],
+        bump
+    )]
+    pub order_index: Account<'info, OrderIndex>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = UserState::LEN,
+        seeds = [b"user_state", user.key().as_ref()],
+        bump
+    )]
+    pub user_state: Account<'info, UserState>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = UserPoolState::LEN,
+        seeds = [b"user_pool_state", user.key().as_ref(), pool_id.key().as_ref()],
+        bump
+    )]
+    pub user_pool_state: Account<'info, UserPoolState>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    /// CHECK: The pool ID to validate against registry
+    pub pool_id: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+    pub clock: Sysvar<'info, Clock>,
+}
+
+/// Takes a queue position for an order whose parameters stay hidden behind
+/// `commitment` until `reveal_order`, see `OrderCommitment::hash`
+pub fn commit_order(ctx: Context<CommitOrder>, commitment: [u8; 32]) -> Result<()> {
+    let user_pool_state = &mut ctx.accounts.user_pool_state;
+    ctx.accounts
+        .pool_registry
+        .check_pending_cap(user_pool_state)?;
+    user_pool_state.record_submission(ctx.accounts.user.key(), ctx.accounts.pool_id.key());
+    ctx.accounts
+        .user_state
+        .record_submission(ctx.accounts.user.key());
+
+    // Bonded like any order, refunded when it settles or expires unrevealed
+    let order_bond = ctx.accounts.pool_registry.order_bond;
+    if order_bond > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.user.to_account_info(),
+                    to: ctx.accounts.order_state.to_account_info(),
+                },
+            ),
+            order_bond,
+        )?;
+    }
+
+    let fifo_state = &mut ctx.accounts.fifo_state;
+    let sequence = fifo_state.current_sequence + 1;
+    fifo_state.current_sequence = sequence;
+
+    let clock = &ctx.accounts.clock;
+    let order_state = &mut ctx.accounts.order_state;
+    order_state.sequence = sequence;
+    order_state.user = ctx.accounts.user.key();
+    order_state.pool_id = ctx.accounts.pool_id.key();
+    order_state.status = OrderStatus::Committed;
+    order_state.submitted_at = clock.unix_timestamp;
+    order_state.submitted_slot = clock.slot;
+    order_state.executed_at = None;
+    order_state.route_len = 1;
+    order_state.route_pools = [Pubkey::default(); MAX_ROUTE_LEN - 1];
+    order_state.bond_lamports = order_bond;
+    order_state.version = StateVersion::CURRENT;
+    ctx.accounts
+        .pool_registry
+        .enqueue(order_state, ctx.accounts.queue_tail.as_deref_mut())?;
+    ctx.accounts.order_index.order = order_state.key();
+
+    let reveal_deadline = clock
+        .unix_timestamp
+        .saturating_add(ctx.accounts.pool_registry.reveal_window);
+    let order_commitment = &mut ctx.accounts.order_commitment;
+    order_commitment.commitment = commitment;
+    order_commitment.reveal_deadline = reveal_deadline;
+
+    emit_cpi!(OrderCommitted {
+        version: EVENT_VERSION,
+        sequence,
+        user: ctx.accounts.user.key(),
+        pool_id: ctx.accounts.pool_id.key(),
+        commitment,
+        bond_lamports: order_bond,
+        reveal_deadline,
+        committed_at: clock.unix_timestamp,
+    });
+
+    debug_msg!(
+        "Order {} committed by user {}",
+        sequence,
+        ctx.accounts.user.key()
+    );
+
+    Ok(())
+}
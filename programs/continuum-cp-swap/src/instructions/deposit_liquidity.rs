@@ -1,3 +1,4 @@
+use crate::cp_swap;
 use crate::errors::ContinuumError;
 use crate::state::*;
 use anchor_lang::prelude::*;
@@ -6,6 +7,7 @@ use anchor_lang::solana_program::{
     program::invoke_signed,
 };
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct DepositLiquidity<'info> {
     #[account(
@@ -18,7 +20,7 @@ pub struct DepositLiquidity<'info> {
     #[account(
         seeds = [b"pool_registry", pool_id.key().as_ref()],
         bump,
-        constraint = pool_registry.is_active @ ContinuumError::PoolNotRegistered,
+        constraint = pool_registry.accepts_orders() @ ContinuumError::PoolNotAcceptingOrders,
     )]
     pub pool_registry: Account<'info, CpSwapPoolRegistry>,
 
@@ -34,18 +36,26 @@ pub struct DepositLiquidity<'info> {
     pub pool_id: UncheckedAccount<'info>,
 
     /// CHECK: Raydium CP-Swap program
+    #[account(address = cp_swap::ID @ ContinuumError::InvalidPoolConfig)]
     pub cp_swap_program: UncheckedAccount<'info>,
     // Remaining accounts are passed directly to CP-Swap deposit instruction
 }
 
 pub fn deposit_liquidity<'info>(
     ctx: Context<'_, '_, '_, 'info, DepositLiquidity<'info>>,
+    lp_token_amount: u64,
+    maximum_token_0_amount: u64,
+    maximum_token_1_amount: u64,
 ) -> Result<()> {
     let pool_authority_bump = ctx.bumps.pool_authority;
     let pool_id = ctx.accounts.pool_id.key();
 
     // Build CPI instruction data - deposit discriminator
-    let ix_data = vec![242, 35, 198, 137, 82, 225, 242, 182];
+    let mut ix_data = Vec::with_capacity(32);
+    ix_data.extend_from_slice(&[242, 35, 198, 137, 82, 225, 242, 182]);
+    ix_data.extend_from_slice(&lp_token_amount.to_le_bytes());
+    ix_data.extend_from_slice(&maximum_token_0_amount.to_le_bytes());
+    ix_data.extend_from_slice(&maximum_token_1_amount.to_le_bytes());
 
     // Build account metas - pool authority first signer
     let mut account_metas = vec![AccountMeta::new_readonly(
@@ -88,5 +98,14 @@ pub fn deposit_liquidity<'info>(
 
     invoke_signed(&ix, &all_accounts, &[seeds])?;
 
+    emit_cpi!(LiquidityDeposited {
+        version: EVENT_VERSION,
+        pool_id,
+        lp_token_amount,
+        maximum_token_0_amount,
+        maximum_token_1_amount,
+        deposited_at: Clock::get()?.unix_timestamp,
+    });
+
     Ok(())
 }
@@ -0,0 +1,141 @@
+use crate::errors::ContinuumError;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ExecuteConfigChange<'info> {
+    #[account(
+        mut,
+        seeds = [b"admin_config"],
+        bump,
+    )]
+    pub admin_config: Account<'info, AdminConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"pending_change".as_ref(), &pending_change.id.to_le_bytes()],
+        bump,
+        close = payer,
+    )]
+    pub pending_change: Account<'info, PendingChange>,
+
+    /// Registry of the pool the change targets, required for pool changes
+    #[account(mut)]
+    pub pool_registry: Option<Account<'info, CpSwapPoolRegistry>>,
+
+    /// Receives the pending change rent
+    #[account(mut, address = pending_change.payer @ ContinuumError::Unauthorized)]
+    pub payer: SystemAccount<'info>,
+
+    pub clock: Sysvar<'info, Clock>,
+}
+
+/// Permissionless once the timelock has elapsed, as long as the proposer still
+/// holds the role the change requires
+pub fn execute_config_change(ctx: Context<ExecuteConfigChange>) -> Result<()> {
+    let pending_change = &ctx.accounts.pending_change;
+    require!(
+        ctx.accounts.clock.unix_timestamp >= pending_change.effective_after,
+        ContinuumError::TimelockNotElapsed
+    );
+    // A proposer who has since lost the role no longer backs the change
+    require!(
+        pending_change
+            .change
+            .is_authorized(&ctx.accounts.admin_config, &pending_change.proposer),
+        ContinuumError::Unauthorized
+    );
+
+    let id = pending_change.id;
+    let change = pending_change.change.clone();
+
+    let registry = match change.pool_id() {
+        Some(pool_id) => {
+            let registry = ctx
+                .accounts
+                .pool_registry
+                .as_mut()
+                .ok_or(ContinuumError::PoolNotRegistered)?;
+            require_keys_eq!(registry.pool_id, pool_id, ContinuumError::PoolNotRegistered);
+            Some(registry)
+        }
+        None => None,
+    };
+
+    match (&change, registry) {
+        (ConfigChange::UpdatePoolConfig { update, .. }, Some(registry)) => {
+            registry.apply_config(update)?;
+
+            emit_cpi!(PoolConfigUpdated {
+                version: EVENT_VERSION,
+                pool_id: registry.pool_id,
+                max_pending_per_user: registry.max_pending_per_user,
+                order_ttl: registry.order_ttl,
+                order_bond: registry.order_bond,
+                min_resting_period: registry.min_resting_period,
+                bond_slash_bps: registry.bond_slash_bps,
+                bond_recipient: registry.bond_recipient,
+                min_inclusion_delay: registry.min_inclusion_delay,
+                reveal_window: registry.reveal_window,
+                min_relayer_stake: registry.min_relayer_stake,
+                relayer_slash_bps: registry.relayer_slash_bps,
+                self_execution_timeout: registry.self_execution_timeout,
+            });
+        }
+        (ConfigChange::SetPoolStatus { status, .. }, Some(registry)) => {
+            let old_status = registry.status();
+            registry.set_status(*status);
+
+            emit_cpi!(PoolStatusChanged {
+                version: EVENT_VERSION,
+                pool_id: registry.pool_id,
+                old_status,
+                new_status: *status,
+            });
+        }
+        (ConfigChange::SetRole { role, new_holder }, None) => {
+            let holder = ctx.accounts.admin_config.role_holder_mut(*role);
+            let old_holder = *holder;
+            *holder = *new_holder;
+
+            emit_cpi!(RoleUpdated {
+                version: EVENT_VERSION,
+                role: *role,
+                old_holder,
+                new_holder: *new_holder,
+            });
+        }
+        (ConfigChange::SetTimelockDelay { delay }, None) => {
+            let old_delay = ctx.accounts.admin_config.timelock_delay;
+            ctx.accounts.admin_config.timelock_delay = *delay;
+
+            emit_cpi!(TimelockDelayUpdated {
+                version: EVENT_VERSION,
+                old_delay,
+                new_delay: *delay,
+            });
+        }
+        (ConfigChange::ProposeSuperAdmin { proposed }, None) => {
+            let admin_config = &mut ctx.accounts.admin_config;
+            admin_config.pending_super_admin = *proposed;
+
+            emit_cpi!(SuperAdminProposed {
+                version: EVENT_VERSION,
+                super_admin: admin_config.super_admin,
+                proposed: *proposed,
+            });
+        }
+        _ => return err!(ContinuumError::InvalidConfigChange),
+    }
+
+    emit_cpi!(ConfigChangeExecuted {
+        version: EVENT_VERSION,
+        id,
+        change,
+    });
+
+    msg!("Config change {} executed", id);
+
+    Ok(())
+}
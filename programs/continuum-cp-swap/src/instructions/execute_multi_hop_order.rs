@@ -0,0 +1,274 @@
+use crate::cp_swap::{self, invoke_swap, swap_instruction_data};
+use crate::errors::*;
+use crate::state::*;
+use crate::utils::{close_order_index, debug_msg, transfer_lamports};
+use anchor_lang::prelude::*;
+use anchor_spl::token::{accessor, Token, TokenAccount};
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(expected_sequence: u64)]
+pub struct ExecuteMultiHopOrder<'info> {
+    #[account(
+        mut,
+        seeds = [b"order", order_state.user.as_ref(), &order_state.seed_sequence().to_le_bytes()],
+        bump,
+        constraint = order_state.sequence == expected_sequence @ ContinuumError::InvalidSequence,
+        constraint = order_state.status == OrderStatus::Pending @ ContinuumError::InvalidOrderStatus,
+        constraint = order_state.is_multi_hop() @ ContinuumError::InvalidOrderType,
+    )]
+    pub order_state: Box<Account<'info, OrderState>>,
+
+    #[account(
+        mut,
+        seeds = [b"user_state", order_state.user.as_ref()],
+        bump,
+    )]
+    pub user_state: Box<Account<'info, UserState>>,
+
+    #[account(
+        mut,
+        seeds = [b"user_pool_state", order_state.user.as_ref(), order_state.pool_id.as_ref()],
+        bump,
+    )]
+    pub user_pool_state: Box<Account<'info, UserPoolState>>,
+
+    /// Index of the order, closed to the user when the order settles
+    /// CHECK: Seeds are checked, orders submitted before indexing have none
+    #[account(
+        mut,
+        seeds = [b"order_index", order_state.pool_id.as_ref(), &order_state.sequence.to_le_bytes()],
+        bump
+    )]
+    pub order_index: UncheckedAccount<'info>,
+
+    /// The order's user, receives the order index rent
+    #[account(mut, address = order_state.user @ ContinuumError::Unauthorized)]
+    pub user: SystemAccount<'info>,
+
+    /// Receives the bond, the user unless a relayer posted it
+    /// CHECK: Checked against the order's bond payer
+    #[account(
+        mut,
+        address = order_state.bond_refund_recipient() @ ContinuumError::InvalidBondPayer,
+    )]
+    pub bond_payer: UncheckedAccount<'info>,
+
+    /// The relayer executing the order
+    #[account(mut)]
+    pub executor: Signer<'info>,
+
+    /// Stake of the executor, required when the pool requires one
+    #[account(
+        seeds = [b"relayer_stake", executor.key().as_ref()],
+        bump
+    )]
+    pub relayer_stake: Option<Account<'info, RelayerStake>>,
+
+    /// User's source token account (input of the first hop)
+    #[account(
+        mut,
+        constraint = user_source.owner == order_state.user,
+    )]
+    pub user_source: Box<Account<'info, TokenAccount>>,
+
+    /// User's destination token account (output of the last hop)
+    #[account(
+        mut,
+        constraint = user_destination.owner == order_state.user,
+    )]
+    pub user_destination: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: The CP-Swap program
+    #[account(address = cp_swap::ID @ ContinuumError::InvalidPoolConfig)]
+    pub cp_swap_program: UncheckedAccount<'info>,
+
+    /// Orders before and after this one in the pool queue, required when linked
+    #[account(mut)]
+    pub queue_prev: Option<Account<'info, OrderState>>,
+    #[account(mut)]
+    pub queue_next: Option<Account<'info, OrderState>>,
+
+    pub token_program: Program<'info, Token>,
+    pub clock: Sysvar<'info, Clock>,
+    // Remaining accounts:
+    //   [0..route_len - 1]  user's intermediate token accounts, one per inner hop
+    //   then for each hop:  pool registry, pool authority PDA, then `accounts_per_hop`
+    //                       accounts passed through to the CP-Swap swap instruction
+    // The order queues in the first pool, so the first registry must be writable.
+}
+
+pub fn execute_multi_hop_order<'info>(
+    ctx: Context<'_, '_, 'info, 'info, ExecuteMultiHopOrder<'info>>,
+    expected_sequence: u64,
+    accounts_per_hop: u8,
+) -> Result<()> {
+    let route = ctx.accounts.order_state.route();
+    let user = ctx.accounts.order_state.user;
+    let amount_in = ctx.accounts.order_state.amount_in;
+    let min_amount_out = ctx.accounts.order_state.min_amount_out;
+
+    let hops = route.len();
+    let hop_len = 2 + accounts_per_hop as usize;
+    require!(
+        ctx.remaining_accounts.len() == (hops - 1) + hops * hop_len,
+        ContinuumError::InvalidRoute
+    );
+
+    let (intermediates, hop_accounts) = ctx.remaining_accounts.split_at(hops - 1);
+
+    // Registries first, so every token account can be checked against the mint
+    // the route passes through it
+    let mut registries = Vec::with_capacity(hops);
+    for (pool_id, accounts) in route.iter().zip(hop_accounts.chunks(hop_len)) {
+        let (expected_registry, _) =
+            Pubkey::find_program_address(&[b"pool_registry", pool_id.as_ref()], ctx.program_id);
+        require_keys_eq!(
+            accounts[0].key(),
+            expected_registry,
+            ContinuumError::PoolNotRegistered
+        );
+        let registry = Account::<CpSwapPoolRegistry>::try_from(&accounts[0])?;
+        // Draining pools still settle pending orders, inactive ones do not
+        require!(registry.is_active, ContinuumError::PoolNotRegistered);
+        registries.push(registry);
+    }
+    let pairs: Vec<_> = registries
+        .iter()
+        .map(|registry| (registry.token_0, registry.token_1))
+        .collect();
+    let mints = route_mints(&pairs)?;
+    require_keys_eq!(
+        ctx.accounts.user_source.mint,
+        mints[0],
+        ContinuumError::InvalidRoute
+    );
+    require_keys_eq!(
+        ctx.accounts.user_destination.mint,
+        mints[hops],
+        ContinuumError::InvalidRoute
+    );
+    for (intermediate, mint) in intermediates.iter().zip(&mints[1..hops]) {
+        let token_account = Account::<TokenAccount>::try_from(intermediate)?;
+        require_keys_eq!(token_account.owner, user, ContinuumError::Unauthorized);
+        require_keys_eq!(token_account.mint, *mint, ContinuumError::InvalidRoute);
+    }
+
+    let mut queue_registry = registries.swap_remove(0);
+    require!(
+        ctx.accounts
+            .order_state
+            .inclusion_delay_elapsed(queue_registry.min_inclusion_delay, ctx.accounts.clock.slot),
+        ContinuumError::InclusionDelayNotElapsed
+    );
+    let executed_by = queue_registry.check_executor(ctx.accounts.relayer_stake.as_deref())?;
+
+    debug_msg!(
+        "Execute multi-hop order {} across {} pools",
+        expected_sequence,
+        hops
+    );
+
+    let source_start_balance = ctx.accounts.user_source.amount;
+    let start_balance = ctx.accounts.user_destination.amount;
+    let user_source_info = ctx.accounts.user_source.to_account_info();
+    let user_destination_info = ctx.accounts.user_destination.to_account_info();
+
+    let mut hop_amount_in = amount_in;
+    for (i, (pool_id, accounts)) in route.iter().zip(hop_accounts.chunks(hop_len)).enumerate() {
+        let pool_authority = &accounts[1];
+        let cp_swap_accounts = &accounts[2..];
+        let (expected_authority, pool_authority_bump) =
+            Pubkey::find_program_address(&[b"cp_pool_authority", pool_id.as_ref()], ctx.program_id);
+        require_keys_eq!(
+            pool_authority.key(),
+            expected_authority,
+            ContinuumError::InvalidPoolConfig
+        );
+
+        let is_last = i == hops - 1;
+        let hop_source = if i == 0 {
+            &user_source_info
+        } else {
+            &intermediates[i - 1]
+        };
+        let hop_destination = if is_last {
+            &user_destination_info
+        } else {
+            &intermediates[i]
+        };
+        // Slippage is enforced end to end, inner hops accept any output
+        let hop_min_amount_out = if is_last { min_amount_out } else { 0 };
+
+        let destination_before = accessor::amount(hop_destination)?;
+        invoke_swap(
+            &ctx.accounts.cp_swap_program,
+            pool_authority,
+            &[
+                b"cp_pool_authority",
+                pool_id.as_ref(),
+                &[pool_authority_bump],
+            ],
+            &[hop_source.clone(), hop_destination.clone()],
+            cp_swap_accounts,
+            swap_instruction_data(true, hop_amount_in, hop_min_amount_out),
+        )?;
+        let destination_after = accessor::amount(hop_destination)?;
+
+        hop_amount_in = destination_after
+            .checked_sub(destination_before)
+            .ok_or(ContinuumError::SlippageExceeded)?;
+    }
+
+    let amount_spent = source_start_balance
+        .checked_sub(accessor::amount(&user_source_info)?)
+        .ok_or(ContinuumError::MathOverflow)?;
+    let amount_out = accessor::amount(&user_destination_info)?
+        .checked_sub(start_balance)
+        .ok_or(ContinuumError::SlippageExceeded)?;
+    require!(
+        amount_out >= min_amount_out,
+        ContinuumError::SlippageExceeded
+    );
+
+    let order_state = &mut ctx.accounts.order_state;
+    order_state.status = OrderStatus::Executed;
+    order_state.executed_at = Some(ctx.accounts.clock.unix_timestamp);
+    order_state.executed_by = executed_by;
+    queue_registry.dequeue(
+        order_state,
+        ctx.accounts.queue_prev.as_deref_mut(),
+        ctx.accounts.queue_next.as_deref_mut(),
+        ctx.accounts.clock.unix_timestamp,
+    )?;
+    queue_registry.exit(ctx.program_id)?;
+    ctx.accounts.user_state.release_pending();
+    ctx.accounts.user_pool_state.release_pending();
+    transfer_lamports(
+        &order_state.to_account_info(),
+        &ctx.accounts.bond_payer.to_account_info(),
+        order_state.bond_lamports,
+    )?;
+    close_order_index(
+        &ctx.accounts.order_index,
+        &ctx.accounts.user.to_account_info(),
+    )?;
+
+    emit_cpi!(OrderExecuted {
+        version: EVENT_VERSION,
+        sequence: order_state.sequence,
+        user,
+        pool_id: order_state.pool_id,
+        executor: ctx.accounts.executor.key(),
+        amount_in: amount_spent,
+        amount_out,
+        executed_at: ctx.accounts.clock.unix_timestamp,
+    });
+
+    debug_msg!(
+        "Multi-hop order {} executed successfully",
+        order_state.sequence
+    );
+
+    Ok(())
+}
@@ -1,35 +1,72 @@
+use crate::cp_swap::{self, invoke_swap, swap_instruction_data};
 use crate::errors::*;
 use crate::state::*;
+use crate::utils::{close_order_index, debug_msg, transfer_lamports};
 use anchor_lang::prelude::*;
-use anchor_lang::solana_program::{
-    instruction::{AccountMeta, Instruction},
-    program::invoke_signed,
-};
-use anchor_spl::token::{Token, TokenAccount};
+use anchor_spl::token::{accessor, Token, TokenAccount};
 
+#[event_cpi]
 #[derive(Accounts)]
 #[instruction(expected_sequence: u64)]
 pub struct ExecuteOrder<'info> {
     #[account(
-        seeds = [b"fifo_state"],
+        mut,
+        seeds = [b"order", order_state.user.as_ref(), &order_state.seed_sequence().to_le_bytes()],
         bump,
+        constraint = order_state.sequence == expected_sequence @ ContinuumError::InvalidSequence,
+        constraint = order_state.status == OrderStatus::Pending @ ContinuumError::InvalidOrderStatus,
+        constraint = !order_state.is_multi_hop() @ ContinuumError::InvalidOrderType,
     )]
-    pub fifo_state: Account<'info, FifoState>,
+    pub order_state: Box<Account<'info, OrderState>>,
 
     #[account(
         mut,
-        seeds = [b"order", order_state.user.as_ref(), &expected_sequence.to_le_bytes()],
+        seeds = [b"user_state", order_state.user.as_ref()],
         bump,
-        constraint = order_state.sequence == expected_sequence @ ContinuumError::InvalidSequence,
-        constraint = order_state.status == OrderStatus::Pending @ ContinuumError::InvalidOrderStatus,
     )]
-    pub order_state: Account<'info, OrderState>,
+    pub user_state: Box<Account<'info, UserState>>,
+
+    #[account(
+        mut,
+        seeds = [b"user_pool_state", order_state.user.as_ref(), order_state.pool_id.as_ref()],
+        bump,
+    )]
+    pub user_pool_state: Box<Account<'info, UserPoolState>>,
+
+    /// Index of the order, closed to the user when the order settles
+    /// CHECK: Seeds are checked, orders submitted before indexing have none
+    #[account(
+        mut,
+        seeds = [b"order_index", order_state.pool_id.as_ref(), &order_state.sequence.to_le_bytes()],
+        bump
+    )]
+    pub order_index: UncheckedAccount<'info>,
+
+    /// The order's user, receives the order index rent
+    #[account(mut, address = order_state.user @ ContinuumError::Unauthorized)]
+    pub user: SystemAccount<'info>,
+
+    /// Receives the bond, the user unless a relayer posted it
+    /// CHECK: Checked against the order's bond payer
+    #[account(
+        mut,
+        address = order_state.bond_refund_recipient() @ ContinuumError::InvalidBondPayer,
+    )]
+    pub bond_payer: UncheckedAccount<'info>,
 
     #[account(
+        mut,
         seeds = [b"pool_registry", order_state.pool_id.as_ref()],
         bump,
+        constraint = pool_registry.is_active @ ContinuumError::PoolNotRegistered,
     )]
-    pub pool_registry: Account<'info, CpSwapPoolRegistry>,
+    pub pool_registry: Box<Account<'info, CpSwapPoolRegistry>>,
+
+    /// Orders before and after this one in the pool queue, required when linked
+    #[account(mut)]
+    pub queue_prev: Option<Account<'info, OrderState>>,
+    #[account(mut)]
+    pub queue_next: Option<Account<'info, OrderState>>,
 
     /// The pool authority PDA that signs for the swap
     /// CHECK: This is a PDA that will be used to sign the CPI
@@ -39,14 +76,25 @@ pub struct ExecuteOrder<'info> {
     )]
     pub pool_authority: UncheckedAccount<'info>,
 
-    /// The relayer executing the order
+    /// The relayer executing the order, or the order's user once the order has
+    /// been at the head of the queue past the pool's self-execution timeout
     #[account(mut)]
     pub executor: Signer<'info>,
 
-    /// User's source token account (for input tokens)
+    /// Stake of the executor, required when the pool requires one
+    #[account(
+        seeds = [b"relayer_stake", executor.key().as_ref()],
+        bump
+    )]
+    pub relayer_stake: Option<Account<'info, RelayerStake>>,
+
+    /// User's source token account (for input tokens), the order's funding
+    /// source if it has one
     #[account(
         mut,
         constraint = user_source.owner == order_state.user,
+        constraint = order_state.funds_from(&user_source.key())
+            @ ContinuumError::InvalidSourceAccount,
     )]
     pub user_source: Box<Account<'info, TokenAccount>>,
 
@@ -58,6 +106,7 @@ pub struct ExecuteOrder<'info> {
     pub user_destination: Box<Account<'info, TokenAccount>>,
 
     /// CHECK: The CP-Swap program
+    #[account(address = cp_swap::ID @ ContinuumError::InvalidPoolConfig)]
     pub cp_swap_program: UncheckedAccount<'info>,
 
     pub token_program: Program<'info, Token>,
@@ -65,104 +114,126 @@ pub struct ExecuteOrder<'info> {
     // Remaining accounts are passed through to CP-Swap swap instruction
 }
 
-pub fn execute_order(ctx: Context<ExecuteOrder>, expected_sequence: u64) -> Result<()> {
-    let pool_authority_bump = ctx.bumps.pool_authority;
-    let pool_id = ctx.accounts.order_state.pool_id;
-    let sequence = ctx.accounts.order_state.sequence;
-    let user = ctx.accounts.order_state.user;
-    let is_base_input = ctx.accounts.order_state.is_base_input;
-    let amount_in = ctx.accounts.order_state.amount_in;
-    let min_amount_out = ctx.accounts.order_state.min_amount_out;
-
-    // Log sequence information for debugging
-    msg!("Execute order - Expected sequence param: {}, Order sequence: {}, Current FIFO sequence: {}", 
-        expected_sequence, 
-        sequence,
-        ctx.accounts.fifo_state.current_sequence
+pub fn execute_order<'info>(
+    ctx: Context<'_, '_, '_, 'info, ExecuteOrder<'info>>,
+    expected_sequence: u64,
+    fill_amount: Option<u64>,
+) -> Result<()> {
+    let order_state = &ctx.accounts.order_state;
+    require!(
+        order_state.inclusion_delay_elapsed(
+            ctx.accounts.pool_registry.min_inclusion_delay,
+            ctx.accounts.clock.slot
+        ),
+        ContinuumError::InclusionDelayNotElapsed
     );
-    msg!("Order user: {}, Order pool: {}", user, pool_id);
-
-    // Build the swap instruction data
-    let mut ix_data = Vec::new();
-
-    if is_base_input {
-        // swap_base_input discriminator
-        ix_data.extend_from_slice(&[143, 190, 90, 218, 196, 30, 51, 222]);
-        ix_data.extend_from_slice(&amount_in.to_le_bytes());
-        ix_data.extend_from_slice(&min_amount_out.to_le_bytes());
+    let pool_registry = &ctx.accounts.pool_registry;
+    let executed_by = if ctx.accounts.executor.key() == order_state.user
+        && pool_registry.allows_self_execution(order_state, ctx.accounts.clock.unix_timestamp)
+    {
+        // A stalled order no longer needs a relayer, and no relayer is accountable
+        0
     } else {
-        // swap_base_output discriminator
-        ix_data.extend_from_slice(&[55, 217, 98, 86, 163, 74, 180, 173]);
-        ix_data.extend_from_slice(&min_amount_out.to_le_bytes()); // max_amount_in
-        ix_data.extend_from_slice(&amount_in.to_le_bytes()); // amount_out
-    }
-
-    // Build account metas
-    let mut account_metas = vec![];
-
-    // Add the pool authority as the first account (signer)
-    account_metas.push(AccountMeta::new_readonly(
-        ctx.accounts.pool_authority.key(),
-        true,
-    ));
-
-    // Add user token accounts
-    account_metas.push(AccountMeta::new(ctx.accounts.user_source.key(), false));
-    account_metas.push(AccountMeta::new(ctx.accounts.user_destination.key(), false));
-
-    // Add remaining accounts (pool state, vaults, etc.)
-    for account in ctx.remaining_accounts.iter() {
-        account_metas.push(if account.is_writable {
-            AccountMeta::new(account.key(), false)
-        } else {
-            AccountMeta::new_readonly(account.key(), false)
-        });
-    }
-
-    // Create the instruction
-    let ix = Instruction {
-        program_id: ctx.accounts.cp_swap_program.key(),
-        accounts: account_metas,
-        data: ix_data,
+        pool_registry.check_executor(ctx.accounts.relayer_stake.as_deref())?
     };
+    let pool_id = order_state.pool_id;
+    let sequence = order_state.sequence;
+    let user = order_state.user;
+    // Whatever is left of the order unless the executor picks a partial fill
+    let fill = fill_amount.unwrap_or_else(|| order_state.remaining_size());
+    let limit = order_state.fill_limit(fill)?;
+
+    debug_msg!(
+        "Execute order {} (expected {}) for user {} on pool {}",
+        sequence,
+        expected_sequence,
+        user,
+        pool_id
+    );
 
-    // Execute swap with pool authority signer
-    let pool_authority_seeds = &[
-        b"cp_pool_authority",
-        pool_id.as_ref(),
-        &[pool_authority_bump],
-    ];
-
-    // Get the starting balance for calculating amount_out
+    // Starting balances, for the amounts actually filled
+    let user_source = ctx.accounts.user_source.to_account_info();
+    let user_destination = ctx.accounts.user_destination.to_account_info();
+    let source_start_balance = ctx.accounts.user_source.amount;
     let start_balance = ctx.accounts.user_destination.amount;
 
-    invoke_signed(
-        &ix,
+    invoke_swap(
+        &ctx.accounts.cp_swap_program,
+        &ctx.accounts.pool_authority,
         &[
-            ctx.accounts.pool_authority.to_account_info(),
-            ctx.accounts.user_source.to_account_info(),
-            ctx.accounts.user_destination.to_account_info(),
+            b"cp_pool_authority",
+            pool_id.as_ref(),
+            &[ctx.bumps.pool_authority],
         ],
-        &[pool_authority_seeds],
+        &[user_source.clone(), user_destination.clone()],
+        ctx.remaining_accounts,
+        swap_instruction_data(order_state.is_base_input, fill, limit),
     )?;
 
-    // Update order status
+    // Final balances, read without deserializing the token accounts again
+    let amount_spent = source_start_balance
+        .checked_sub(accessor::amount(&user_source)?)
+        .ok_or(ContinuumError::MathOverflow)?;
+    let amount_out = accessor::amount(&user_destination)?
+        .checked_sub(start_balance)
+        .ok_or(ContinuumError::SlippageExceeded)?;
+
+    // Every fill is evidence against its relayer if it skipped an older order
     let order_state = &mut ctx.accounts.order_state;
-    order_state.status = OrderStatus::Executed;
+    order_state.record_fill(amount_spent, amount_out)?;
     order_state.executed_at = Some(ctx.accounts.clock.unix_timestamp);
+    order_state.executed_by = executed_by;
+    if !order_state.fill_settles() {
+        // The remainder keeps its place at the head of the queue
+        emit_cpi!(OrderPartiallyFilled {
+            version: EVENT_VERSION,
+            sequence,
+            user,
+            pool_id,
+            executor: ctx.accounts.executor.key(),
+            amount_in: amount_spent,
+            amount_out,
+            remaining: order_state.remaining_size(),
+            filled_at: ctx.accounts.clock.unix_timestamp,
+        });
+
+        debug_msg!("Order {} partially filled", sequence);
 
-    // Reload destination account to get final balance
-    ctx.accounts.user_destination.reload()?;
-    let amount_out = ctx.accounts.user_destination.amount - start_balance;
+        return Ok(());
+    }
+
+    // Update order status
+    order_state.status = OrderStatus::Executed;
+    ctx.accounts.pool_registry.dequeue(
+        order_state,
+        ctx.accounts.queue_prev.as_deref_mut(),
+        ctx.accounts.queue_next.as_deref_mut(),
+        ctx.accounts.clock.unix_timestamp,
+    )?;
+    ctx.accounts.user_state.release_pending();
+    ctx.accounts.user_pool_state.release_pending();
+    transfer_lamports(
+        &order_state.to_account_info(),
+        &ctx.accounts.bond_payer.to_account_info(),
+        order_state.bond_lamports,
+    )?;
+    close_order_index(
+        &ctx.accounts.order_index,
+        &ctx.accounts.user.to_account_info(),
+    )?;
 
-    emit!(OrderExecuted {
+    emit_cpi!(OrderExecuted {
+        version: EVENT_VERSION,
         sequence,
         user,
-        amount_out,
+        pool_id,
         executor: ctx.accounts.executor.key(),
+        amount_in: order_state.filled_amount_in,
+        amount_out: order_state.filled_amount_out,
+        executed_at: ctx.accounts.clock.unix_timestamp,
     });
 
-    msg!("Order {} executed successfully", sequence);
+    debug_msg!("Order {} executed successfully", sequence);
 
     Ok(())
 }
@@ -0,0 +1,172 @@
+use crate::cp_swap::{self, invoke_swap, swap_instruction_data};
+use crate::errors::*;
+use crate::state::*;
+use crate::utils::{debug_msg, transfer_lamports};
+use anchor_lang::prelude::*;
+use anchor_spl::token::{accessor, Token, TokenAccount};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ExecuteRingOrder<'info> {
+    #[account(
+        mut,
+        seeds = [b"order_ring", pool_id.key().as_ref()],
+        bump,
+    )]
+    pub order_ring: AccountLoader<'info, OrderRing>,
+
+    #[account(
+        seeds = [b"pool_registry", pool_id.key().as_ref()],
+        bump,
+        constraint = pool_registry.is_active @ ContinuumError::PoolNotRegistered,
+    )]
+    pub pool_registry: Account<'info, CpSwapPoolRegistry>,
+
+    /// The pool authority PDA that signs for the swap
+    /// CHECK: This is a PDA that will be used to sign the CPI
+    #[account(
+        seeds = [b"cp_pool_authority", pool_id.key().as_ref()],
+        bump
+    )]
+    pub pool_authority: UncheckedAccount<'info>,
+
+    /// CHECK: The pool the ring belongs to
+    pub pool_id: UncheckedAccount<'info>,
+
+    /// The relayer executing the order
+    pub executor: Signer<'info>,
+
+    /// Stake of the executor, required when the pool requires one
+    #[account(
+        seeds = [b"relayer_stake", executor.key().as_ref()],
+        bump
+    )]
+    pub relayer_stake: Option<Account<'info, RelayerStake>>,
+
+    /// Head order user's source token account, checked against the record
+    #[account(mut)]
+    pub user_source: Box<Account<'info, TokenAccount>>,
+
+    /// Head order user's destination token account, checked against the record
+    #[account(mut)]
+    pub user_destination: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [b"user_state", user_source.owner.as_ref()],
+        bump,
+    )]
+    pub user_state: Box<Account<'info, UserState>>,
+
+    #[account(
+        mut,
+        seeds = [b"user_pool_state", user_source.owner.as_ref(), pool_id.key().as_ref()],
+        bump,
+    )]
+    pub user_pool_state: Box<Account<'info, UserPoolState>>,
+
+    /// The head order's user, receives the bond refund
+    #[account(mut, address = user_source.owner @ ContinuumError::Unauthorized)]
+    pub user: SystemAccount<'info>,
+
+    /// CHECK: The CP-Swap program
+    #[account(address = cp_swap::ID @ ContinuumError::InvalidPoolConfig)]
+    pub cp_swap_program: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub clock: Sysvar<'info, Clock>,
+    // Remaining accounts are passed through to CP-Swap swap instruction
+}
+
+/// Executes the order at the head of the ring, the only one that can be
+pub fn execute_ring_order<'info>(
+    ctx: Context<'_, '_, '_, 'info, ExecuteRingOrder<'info>>,
+    expected_sequence: u64,
+) -> Result<()> {
+    // Ring orders run strictly from the head, only eligibility and the
+    // inclusion delay are checked
+    ctx.accounts
+        .pool_registry
+        .check_executor(ctx.accounts.relayer_stake.as_deref())?;
+    let pool_id = ctx.accounts.pool_id.key();
+    let record = {
+        let order_ring = ctx.accounts.order_ring.load()?;
+        require!(
+            expected_sequence != 0 && order_ring.head_sequence() == expected_sequence,
+            ContinuumError::InvalidSequence
+        );
+        *order_ring
+            .get(expected_sequence)
+            .ok_or(ContinuumError::InvalidSequence)?
+    };
+    require!(
+        record.inclusion_delay_elapsed(
+            ctx.accounts.pool_registry.min_inclusion_delay,
+            ctx.accounts.clock.slot
+        ),
+        ContinuumError::InclusionDelayNotElapsed
+    );
+    require_keys_eq!(
+        ctx.accounts.user_source.owner,
+        record.user,
+        ContinuumError::Unauthorized
+    );
+    require_keys_eq!(
+        ctx.accounts.user_destination.owner,
+        record.user,
+        ContinuumError::Unauthorized
+    );
+
+    let user_source = ctx.accounts.user_source.to_account_info();
+    let user_destination = ctx.accounts.user_destination.to_account_info();
+    let source_start_balance = ctx.accounts.user_source.amount;
+    let start_balance = ctx.accounts.user_destination.amount;
+
+    invoke_swap(
+        &ctx.accounts.cp_swap_program,
+        &ctx.accounts.pool_authority,
+        &[
+            b"cp_pool_authority",
+            pool_id.as_ref(),
+            &[ctx.bumps.pool_authority],
+        ],
+        &[user_source.clone(), user_destination.clone()],
+        ctx.remaining_accounts,
+        swap_instruction_data(
+            record.is_base_input != 0,
+            record.amount_in,
+            record.min_amount_out,
+        ),
+    )?;
+
+    ctx.accounts.order_ring.load_mut()?.pop();
+    ctx.accounts.user_state.release_pending();
+    ctx.accounts.user_pool_state.release_pending();
+    transfer_lamports(
+        &ctx.accounts.order_ring.to_account_info(),
+        &ctx.accounts.user.to_account_info(),
+        record.bond_lamports,
+    )?;
+
+    let amount_spent = source_start_balance
+        .checked_sub(accessor::amount(&user_source)?)
+        .ok_or(ContinuumError::MathOverflow)?;
+    let amount_out = accessor::amount(&user_destination)?
+        .checked_sub(start_balance)
+        .ok_or(ContinuumError::SlippageExceeded)?;
+
+    emit_cpi!(RingOrderExecuted {
+        version: EVENT_VERSION,
+        pool_id,
+        sequence: expected_sequence,
+        user: record.user,
+        executor: ctx.accounts.executor.key(),
+        amount_in: amount_spent,
+        amount_out,
+        executed_at: ctx.accounts.clock.unix_timestamp,
+    });
+
+    debug_msg!("Ring order {} executed successfully", expected_sequence);
+
+    Ok(())
+}
@@ -0,0 +1,132 @@
+use crate::errors::*;
+use crate::state::*;
+use crate::utils::{close_order_index, debug_msg, transfer_lamports};
+use anchor_lang::prelude::*;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ExpireOrder<'info> {
+    #[account(
+        mut,
+        constraint = matches!(order_state.status, OrderStatus::Pending | OrderStatus::Committed)
+            @ ContinuumError::InvalidOrderStatus,
+    )]
+    pub order_state: Account<'info, OrderState>,
+
+    /// Commitment of an unrevealed order, required for those and closed to the user
+    #[account(
+        mut,
+        close = user,
+        seeds = [b"order_commitment", order_state.key().as_ref()],
+        bump
+    )]
+    pub order_commitment: Option<Account<'info, OrderCommitment>>,
+
+    #[account(
+        mut,
+        seeds = [b"user_state", order_state.user.as_ref()],
+        bump,
+    )]
+    pub user_state: Account<'info, UserState>,
+
+    #[account(
+        mut,
+        seeds = [b"user_pool_state", order_state.user.as_ref(), order_state.pool_id.as_ref()],
+        bump,
+    )]
+    pub user_pool_state: Account<'info, UserPoolState>,
+
+    /// Index of the order, closed to the user when the order settles
+    /// CHECK: Seeds are checked, orders submitted before indexing have none
+    #[account(
+        mut,
+        seeds = [b"order_index", order_state.pool_id.as_ref(), &order_state.sequence.to_le_bytes()],
+        bump
+    )]
+    pub order_index: UncheckedAccount<'info>,
+
+    /// The order's user, receives the rent of the order and its index
+    #[account(mut, address = order_state.user @ ContinuumError::Unauthorized)]
+    pub user: SystemAccount<'info>,
+
+    /// Receives the bond, the user unless a relayer posted it
+    /// CHECK: Checked against the order's bond payer
+    #[account(
+        mut,
+        address = order_state.bond_refund_recipient() @ ContinuumError::InvalidBondPayer,
+    )]
+    pub bond_payer: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"pool_registry", order_state.pool_id.as_ref()],
+        bump,
+    )]
+    pub pool_registry: Account<'info, CpSwapPoolRegistry>,
+
+    /// Orders before and after this one in the pool queue, required when linked
+    #[account(mut)]
+    pub queue_prev: Option<Account<'info, OrderState>>,
+    #[account(mut)]
+    pub queue_next: Option<Account<'info, OrderState>>,
+
+    /// Anyone can expire an order once its pool TTL has passed, or an
+    /// unrevealed one once its reveal window has
+    pub caller: Signer<'info>,
+
+    pub clock: Sysvar<'info, Clock>,
+}
+
+pub fn expire_order(ctx: Context<ExpireOrder>) -> Result<()> {
+    let order_ttl = ctx.accounts.pool_registry.order_ttl;
+    let now = ctx.accounts.clock.unix_timestamp;
+    let order_state = &mut ctx.accounts.order_state;
+
+    let reveal_window_closed = if order_state.status == OrderStatus::Committed {
+        let order_commitment = ctx
+            .accounts
+            .order_commitment
+            .as_ref()
+            .ok_or(ContinuumError::InvalidCommitment)?;
+        now > order_commitment.reveal_deadline
+    } else {
+        false
+    };
+    require!(
+        reveal_window_closed
+            || (order_ttl > 0 && now >= order_state.submitted_at.saturating_add(order_ttl)),
+        ContinuumError::OrderNotExpired
+    );
+
+    order_state.status = OrderStatus::Expired;
+    order_state.executed_at = Some(now);
+    ctx.accounts.pool_registry.dequeue(
+        order_state,
+        ctx.accounts.queue_prev.as_deref_mut(),
+        ctx.accounts.queue_next.as_deref_mut(),
+        now,
+    )?;
+    ctx.accounts.user_state.release_pending();
+    ctx.accounts.user_pool_state.release_pending();
+    transfer_lamports(
+        &order_state.to_account_info(),
+        &ctx.accounts.bond_payer.to_account_info(),
+        order_state.bond_lamports,
+    )?;
+    close_order_index(
+        &ctx.accounts.order_index,
+        &ctx.accounts.user.to_account_info(),
+    )?;
+
+    emit_cpi!(OrderExpired {
+        version: EVENT_VERSION,
+        sequence: order_state.sequence,
+        user: order_state.user,
+        bond_refunded: order_state.bond_lamports,
+        expired_at: now,
+    });
+
+    debug_msg!("Order {} expired", order_state.sequence);
+
+    Ok(())
+}
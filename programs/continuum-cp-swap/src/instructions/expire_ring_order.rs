@@ -0,0 +1,90 @@
+use crate::errors::*;
+use crate::state::*;
+use crate::utils::{debug_msg, transfer_lamports};
+use anchor_lang::prelude::*;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ExpireRingOrder<'info> {
+    #[account(
+        mut,
+        seeds = [b"order_ring", pool_id.key().as_ref()],
+        bump,
+    )]
+    pub order_ring: AccountLoader<'info, OrderRing>,
+
+    #[account(
+        seeds = [b"pool_registry", pool_id.key().as_ref()],
+        bump,
+    )]
+    pub pool_registry: Account<'info, CpSwapPoolRegistry>,
+
+    #[account(
+        mut,
+        seeds = [b"user_state", user.key().as_ref()],
+        bump,
+    )]
+    pub user_state: Account<'info, UserState>,
+
+    #[account(
+        mut,
+        seeds = [b"user_pool_state", user.key().as_ref(), pool_id.key().as_ref()],
+        bump,
+    )]
+    pub user_pool_state: Account<'info, UserPoolState>,
+
+    /// The order's user, checked against the record, receives the bond refund
+    #[account(mut)]
+    pub user: SystemAccount<'info>,
+
+    /// CHECK: The pool the ring belongs to
+    pub pool_id: UncheckedAccount<'info>,
+
+    /// Anyone can expire a record once its pool TTL has passed, which is how an
+    /// unexecutable head stops blocking the ring
+    pub caller: Signer<'info>,
+
+    pub clock: Sysvar<'info, Clock>,
+}
+
+pub fn expire_ring_order(ctx: Context<ExpireRingOrder>, sequence: u64) -> Result<()> {
+    let order_ttl = ctx.accounts.pool_registry.order_ttl;
+    let now = ctx.accounts.clock.unix_timestamp;
+    let user = ctx.accounts.user.key();
+    let mut order_ring = ctx.accounts.order_ring.load_mut()?;
+
+    let record = order_ring
+        .get_mut(sequence)
+        .ok_or(ContinuumError::InvalidSequence)?;
+    require!(record.cancelled == 0, ContinuumError::InvalidOrderStatus);
+    require_keys_eq!(record.user, user, ContinuumError::Unauthorized);
+    require!(
+        order_ttl > 0 && now >= record.submitted_at.saturating_add(order_ttl),
+        ContinuumError::OrderNotExpired
+    );
+
+    record.cancelled = 1;
+    let bond = record.bond_lamports;
+    order_ring.discard_cancelled();
+    drop(order_ring);
+    ctx.accounts.user_state.release_pending();
+    ctx.accounts.user_pool_state.release_pending();
+    transfer_lamports(
+        &ctx.accounts.order_ring.to_account_info(),
+        &ctx.accounts.user.to_account_info(),
+        bond,
+    )?;
+
+    emit_cpi!(RingOrderExpired {
+        version: EVENT_VERSION,
+        pool_id: ctx.accounts.pool_id.key(),
+        sequence,
+        user,
+        bond_refunded: bond,
+        expired_at: now,
+    });
+
+    debug_msg!("Ring order {} expired", sequence);
+
+    Ok(())
+}
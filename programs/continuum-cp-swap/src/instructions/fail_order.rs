@@ -0,0 +1,265 @@
+use crate::cp_swap::{self, AmmConfigView, PoolStateView};
+use crate::errors::*;
+use crate::state::*;
+use crate::utils::{close_order_index, debug_msg, transfer_lamports};
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program_option::COption;
+use anchor_spl::associated_token::get_associated_token_address;
+use anchor_spl::token::{self, TokenAccount};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct FailOrder<'info> {
+    #[account(
+        mut,
+        seeds = [b"order", order_state.user.as_ref(), &order_state.seed_sequence().to_le_bytes()],
+        bump,
+        constraint = order_state.status == OrderStatus::Pending @ ContinuumError::InvalidOrderStatus,
+        constraint = !order_state.is_multi_hop() @ ContinuumError::InvalidOrderType,
+    )]
+    pub order_state: Box<Account<'info, OrderState>>,
+
+    #[account(
+        mut,
+        seeds = [b"user_state", order_state.user.as_ref()],
+        bump,
+    )]
+    pub user_state: Box<Account<'info, UserState>>,
+
+    #[account(
+        mut,
+        seeds = [b"user_pool_state", order_state.user.as_ref(), order_state.pool_id.as_ref()],
+        bump,
+    )]
+    pub user_pool_state: Box<Account<'info, UserPoolState>>,
+
+    /// Index of the order, closed to the user when the order settles
+    /// CHECK: Seeds are checked, orders submitted before indexing have none
+    #[account(
+        mut,
+        seeds = [b"order_index", order_state.pool_id.as_ref(), &order_state.sequence.to_le_bytes()],
+        bump
+    )]
+    pub order_index: UncheckedAccount<'info>,
+
+    /// The order's user, receives the order index rent
+    #[account(mut, address = order_state.user @ ContinuumError::Unauthorized)]
+    pub user: SystemAccount<'info>,
+
+    /// Receives the bond, the user unless a relayer posted it
+    /// CHECK: Checked against the order's bond payer
+    #[account(
+        mut,
+        address = order_state.bond_refund_recipient() @ ContinuumError::InvalidBondPayer,
+    )]
+    pub bond_payer: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"pool_registry", order_state.pool_id.as_ref()],
+        bump,
+        constraint = pool_registry.queue.head_sequence == order_state.sequence
+            @ ContinuumError::InvalidSequence,
+    )]
+    pub pool_registry: Box<Account<'info, CpSwapPoolRegistry>>,
+
+    /// Order after this one in the pool queue, required when linked
+    #[account(mut)]
+    pub queue_next: Option<Account<'info, OrderState>>,
+
+    /// CHECK: The pool authority the user's token accounts must be delegated to
+    #[account(
+        seeds = [b"cp_pool_authority", order_state.pool_id.as_ref()],
+        bump
+    )]
+    pub pool_authority: UncheckedAccount<'info>,
+
+    /// The relayer that would otherwise have to execute the order
+    pub executor: Signer<'info>,
+
+    /// Stake of the executor, required when the pool requires one
+    #[account(
+        seeds = [b"relayer_stake", executor.key().as_ref()],
+        bump
+    )]
+    pub relayer_stake: Option<Account<'info, RelayerStake>>,
+
+    /// CHECK: The order's CP-Swap pool, decoded in the handler
+    #[account(address = order_state.pool_id @ ContinuumError::InvalidPoolConfig)]
+    pub pool_state: UncheckedAccount<'info>,
+
+    /// CHECK: The pool's fee config, checked against the pool and decoded in the handler
+    pub amm_config: UncheckedAccount<'info>,
+
+    #[account(address = pool_registry.token_0_vault @ ContinuumError::InvalidPoolConfig)]
+    pub token_0_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(address = pool_registry.token_1_vault @ ContinuumError::InvalidPoolConfig)]
+    pub token_1_vault: Box<Account<'info, TokenAccount>>,
+
+    /// The accounts the order is judged on for token 0 and token 1: the user's
+    /// associated token accounts, or for an order with a funding source that
+    /// source in either place. They are decoded in the handler, where a
+    /// missing, closed or frozen account funds nothing.
+    /// CHECK: Addresses are checked in the handler
+    pub user_token_0: UncheckedAccount<'info>,
+
+    /// CHECK: Addresses are checked in the handler
+    pub user_token_1: UncheckedAccount<'info>,
+
+    pub clock: Sysvar<'info, Clock>,
+}
+
+/// Settles the head order as `Failed` when it cannot be executed in either
+/// direction: the account it is judged on for the input cannot fund its
+/// remainder through the pool authority, or the current reserves cannot meet
+/// its limit. The relayer then moves on to the next order without skipping it.
+/// An order with a funding source is judged on that account alone, one without
+/// on the user's associated token accounts.
+pub fn fail_order(ctx: Context<FailOrder>) -> Result<()> {
+    let pool_registry = &ctx.accounts.pool_registry;
+    pool_registry.check_executor(ctx.accounts.relayer_stake.as_deref())?;
+
+    let pool = PoolStateView::load(&ctx.accounts.pool_state)?;
+    require_keys_eq!(
+        ctx.accounts.amm_config.key(),
+        pool.amm_config,
+        ContinuumError::InvalidPoolConfig
+    );
+    let config = AmmConfigView::load(&ctx.accounts.amm_config)?;
+    let (reserve_0, reserve_1) = pool.reserves(
+        ctx.accounts.token_0_vault.amount,
+        ctx.accounts.token_1_vault.amount,
+    )?;
+
+    let order_state = &ctx.accounts.order_state;
+    let accounts = [
+        ctx.accounts.user_token_0.to_account_info(),
+        ctx.accounts.user_token_1.to_account_info(),
+    ];
+    let mints = [pool_registry.token_0, pool_registry.token_1];
+    if order_state.source == Pubkey::default() {
+        for (account, mint) in accounts.iter().zip(&mints) {
+            require_keys_eq!(
+                account.key(),
+                get_associated_token_address(&order_state.user, mint),
+                ContinuumError::InvalidSourceAccount
+            );
+        }
+    } else {
+        require!(
+            accounts
+                .iter()
+                .any(|account| account.key() == order_state.source),
+            ContinuumError::InvalidSourceAccount
+        );
+    }
+
+    let pool_authority = ctx.accounts.pool_authority.key();
+    let executable = [
+        (&accounts[0], &mints[0], reserve_0, reserve_1),
+        (&accounts[1], &mints[1], reserve_1, reserve_0),
+    ]
+    .into_iter()
+    .any(|(account, mint, input_reserve, output_reserve)| {
+        // Only the funding source counts when the order has one
+        let available = if order_state.funds_from(&account.key()) {
+            delegated_balance(account, &order_state.user, mint, &pool_authority)
+        } else {
+            0
+        };
+        executable(
+            order_state,
+            &config,
+            available,
+            input_reserve,
+            output_reserve,
+        )
+    });
+    require!(!executable, ContinuumError::OrderStillExecutable);
+
+    let now = ctx.accounts.clock.unix_timestamp;
+    let order_state = &mut ctx.accounts.order_state;
+    order_state.status = OrderStatus::Failed;
+    order_state.executed_at = Some(now);
+    ctx.accounts.pool_registry.dequeue(
+        order_state,
+        None,
+        ctx.accounts.queue_next.as_deref_mut(),
+        now,
+    )?;
+    ctx.accounts.user_state.release_pending();
+    ctx.accounts.user_pool_state.release_pending();
+    transfer_lamports(
+        &order_state.to_account_info(),
+        &ctx.accounts.bond_payer.to_account_info(),
+        order_state.bond_lamports,
+    )?;
+    close_order_index(
+        &ctx.accounts.order_index,
+        &ctx.accounts.user.to_account_info(),
+    )?;
+
+    emit_cpi!(OrderFailed {
+        version: EVENT_VERSION,
+        sequence: order_state.sequence,
+        user: order_state.user,
+        bond_refunded: order_state.bond_lamports,
+        failed_at: now,
+    });
+
+    debug_msg!("Order {} failed", order_state.sequence);
+
+    Ok(())
+}
+
+/// Tokens of `mint` the pool authority can take from `account` for `user`,
+/// none unless it is a live token account of theirs delegated to the authority
+fn delegated_balance(
+    account: &AccountInfo,
+    user: &Pubkey,
+    mint: &Pubkey,
+    pool_authority: &Pubkey,
+) -> u64 {
+    if account.owner != &token::ID {
+        return 0;
+    }
+    let Ok(data) = account.try_borrow_data() else {
+        return 0;
+    };
+    let Ok(source) = TokenAccount::try_deserialize(&mut &data[..]) else {
+        return 0;
+    };
+    if source.owner != *user
+        || source.mint != *mint
+        || source.is_frozen()
+        || source.delegate != COption::Some(*pool_authority)
+    {
+        return 0;
+    }
+    source.amount.min(source.delegated_amount)
+}
+
+/// Whether the rest of `order` can be filled with `available` input tokens at
+/// the current reserves
+fn executable(
+    order: &OrderState,
+    config: &AmmConfigView,
+    available: u64,
+    input_reserve: u64,
+    output_reserve: u64,
+) -> bool {
+    let fill = order.remaining_size();
+    let Ok(limit) = order.fill_limit(fill) else {
+        return false;
+    };
+    let (input_needed, meets_limit) = if order.is_base_input {
+        let amount_out = cp_swap::swap_base_input(config, fill, input_reserve, output_reserve);
+        (fill, amount_out.is_ok_and(|amount_out| amount_out >= limit))
+    } else {
+        let amount_in = cp_swap::swap_base_output(config, fill, input_reserve, output_reserve);
+        (limit, amount_in.is_ok_and(|amount_in| amount_in <= limit))
+    };
+
+    meets_limit && available >= input_needed
+}
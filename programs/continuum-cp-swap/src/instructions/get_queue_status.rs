@@ -0,0 +1,18 @@
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct GetQueueStatus<'info> {
+    #[account(
+        seeds = [b"pool_registry", pool_id.key().as_ref()],
+        bump,
+    )]
+    pub pool_registry: Account<'info, CpSwapPoolRegistry>,
+
+    /// CHECK: The pool whose queue is read
+    pub pool_id: UncheckedAccount<'info>,
+}
+
+pub fn get_queue_status(ctx: Context<GetQueueStatus>) -> Result<QueueStatus> {
+    Ok(ctx.accounts.pool_registry.queue)
+}
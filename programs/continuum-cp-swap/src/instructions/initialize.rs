@@ -1,6 +1,7 @@
 use crate::state::*;
 use anchor_lang::prelude::*;
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct Initialize<'info> {
     #[account(
@@ -24,6 +25,12 @@ pub fn initialize(ctx: Context<Initialize>) -> Result<()> {
     fifo_state.current_sequence = 0;
     fifo_state.admin = ctx.accounts.admin.key();
     fifo_state.emergency_pause = false;
+    fifo_state.version = StateVersion::CURRENT;
+
+    emit_cpi!(FifoInitialized {
+        version: EVENT_VERSION,
+        admin: ctx.accounts.admin.key(),
+    });
 
     msg!(
         "Continuum FIFO initialized with admin: {}",
@@ -0,0 +1,52 @@
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct InitializeAdminConfig<'info> {
+    #[account(
+        seeds = [b"fifo_state"],
+        bump,
+        has_one = admin,
+    )]
+    pub fifo_state: Account<'info, FifoState>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = AdminConfig::LEN,
+        seeds = [b"admin_config"],
+        bump
+    )]
+    pub admin_config: Account<'info, AdminConfig>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn initialize_admin_config(ctx: Context<InitializeAdminConfig>) -> Result<()> {
+    let admin = ctx.accounts.admin.key();
+    let admin_config = &mut ctx.accounts.admin_config;
+
+    // The FIFO admin starts out holding every role
+    admin_config.super_admin = admin;
+    admin_config.pending_super_admin = Pubkey::default();
+    admin_config.pauser = admin;
+    admin_config.pool_manager = admin;
+    admin_config.fee_manager = admin;
+    admin_config.relayer_manager = admin;
+    admin_config.timelock_delay = DEFAULT_TIMELOCK_DELAY;
+    admin_config.change_count = 0;
+
+    emit_cpi!(AdminConfigInitialized {
+        version: EVENT_VERSION,
+        super_admin: admin,
+        timelock_delay: DEFAULT_TIMELOCK_DELAY,
+    });
+
+    msg!("Admin config initialized with super admin: {}", admin);
+
+    Ok(())
+}
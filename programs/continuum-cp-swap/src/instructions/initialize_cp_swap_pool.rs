@@ -1,3 +1,5 @@
+use crate::cp_swap::{self, PoolStateView};
+use crate::errors::ContinuumError;
 use crate::state::*;
 use anchor_lang::prelude::*;
 use anchor_lang::solana_program::{
@@ -5,18 +7,19 @@ use anchor_lang::solana_program::{
     program::invoke_signed,
 };
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct InitializeCpSwapPool<'info> {
     #[account(
-        seeds = [b"fifo_state"],
+        seeds = [b"admin_config"],
         bump,
-        has_one = admin,
+        constraint = admin_config.has_role(AdminRole::PoolManager, &admin.key()) @ ContinuumError::Unauthorized,
     )]
-    pub fifo_state: Account<'info, FifoState>,
+    pub admin_config: Account<'info, AdminConfig>,
 
     #[account(
         init,
-        payer = admin,
+        payer = payer,
         space = CpSwapPoolRegistry::LEN,
         seeds = [b"pool_registry", pool_state.key().as_ref()],
         bump
@@ -32,13 +35,17 @@ pub struct InitializeCpSwapPool<'info> {
     )]
     pub pool_authority: UncheckedAccount<'info>,
 
-    #[account(mut)]
     pub admin: Signer<'info>,
 
+    /// Pays for the registry, separate so the admin can be a multisig PDA
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
     /// CHECK: The pool state account that will be created by CP-Swap
     pub pool_state: UncheckedAccount<'info>,
 
     /// CHECK: The CP-Swap program
+    #[account(address = cp_swap::ID @ ContinuumError::InvalidPoolConfig)]
     pub cp_swap_program: UncheckedAccount<'info>,
 
     pub system_program: Program<'info, System>,
@@ -107,21 +114,27 @@ pub fn initialize_cp_swap_pool(
 
     invoke_signed(&ix, ctx.remaining_accounts, &[pool_authority_seeds])?;
 
-    // Register the pool
-    let registry = &mut ctx.accounts.pool_registry;
-    registry.pool_id = pool_state_key;
-    registry.continuum_authority = ctx.accounts.pool_authority.key();
-    registry.created_at = Clock::get()?.unix_timestamp;
-    registry.is_active = true;
-
-    // TODO: Extract token mints from remaining accounts
-    // For now, we'll need to pass them as additional parameters or extract from pool state
-    registry.token_0 = Pubkey::default(); // To be filled
-    registry.token_1 = Pubkey::default(); // To be filled
+    // Register the pool with the mints and vaults CP-Swap just created
+    let pool = PoolStateView::load(&ctx.accounts.pool_state)?;
+    require!(
+        pool.is_controlled_by(&ctx.accounts.pool_authority.key()),
+        ContinuumError::InvalidPoolConfig
+    );
 
-    emit!(PoolRegistered {
+    let registry = &mut ctx.accounts.pool_registry;
+    registry.register(
+        pool_state_key,
+        ctx.accounts.pool_authority.key(),
+        &pool,
+        Clock::get()?.unix_timestamp,
+    );
+
+    emit_cpi!(PoolRegistered {
+        version: EVENT_VERSION,
         pool_id: pool_state_key,
         continuum_authority: ctx.accounts.pool_authority.key(),
+        token_0: pool.token_0_mint,
+        token_1: pool.token_1_mint,
     });
 
     msg!("CP-Swap pool initialized with Continuum authority");
@@ -0,0 +1,60 @@
+use crate::errors::ContinuumError;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct InitializeOrderRing<'info> {
+    #[account(
+        seeds = [b"admin_config"],
+        bump,
+        constraint = admin_config.has_role(AdminRole::PoolManager, &admin.key()) @ ContinuumError::Unauthorized,
+    )]
+    pub admin_config: Account<'info, AdminConfig>,
+
+    /// Needs an order TTL, expiry is how an unexecutable head leaves the ring
+    #[account(
+        mut,
+        seeds = [b"pool_registry", pool_id.key().as_ref()],
+        bump,
+        constraint = pool_registry.order_ttl > 0 @ ContinuumError::InvalidPoolConfig,
+    )]
+    pub pool_registry: Account<'info, CpSwapPoolRegistry>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = OrderRing::LEN,
+        seeds = [b"order_ring", pool_id.key().as_ref()],
+        bump
+    )]
+    pub order_ring: AccountLoader<'info, OrderRing>,
+
+    pub admin: Signer<'info>,
+
+    /// Pays for the ring, separate so the admin can be a multisig PDA
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: The registered pool the ring queues orders for
+    pub pool_id: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn initialize_order_ring(ctx: Context<InitializeOrderRing>) -> Result<()> {
+    let pool_id = ctx.accounts.pool_id.key();
+    let mut order_ring = ctx.accounts.order_ring.load_init()?;
+    order_ring.pool_id = pool_id;
+    ctx.accounts.pool_registry.has_order_ring = true;
+
+    emit_cpi!(OrderRingInitialized {
+        version: EVENT_VERSION,
+        pool_id,
+        capacity: ORDER_RING_CAPACITY as u16,
+    });
+
+    msg!("Order ring initialized for pool {}", pool_id);
+
+    Ok(())
+}
@@ -0,0 +1,55 @@
+use crate::errors::ContinuumError;
+use crate::state::*;
+use crate::utils::{load_legacy, realloc_account, store_account};
+use anchor_lang::prelude::*;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct MigrateFifoState<'info> {
+    #[account(
+        seeds = [b"admin_config"],
+        bump,
+        has_one = super_admin @ ContinuumError::Unauthorized,
+    )]
+    pub admin_config: Account<'info, AdminConfig>,
+
+    /// CHECK: Deserialized by hand, the old layout does not fit `FifoState`
+    #[account(
+        mut,
+        seeds = [b"fifo_state"],
+        bump,
+        owner = crate::ID,
+    )]
+    pub fifo_state: UncheckedAccount<'info>,
+
+    pub super_admin: Signer<'info>,
+
+    /// Pays the extra rent for the larger account
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn migrate_fifo_state(ctx: Context<MigrateFifoState>) -> Result<()> {
+    let fifo_state = ctx.accounts.fifo_state.to_account_info();
+    let legacy = load_legacy::<FifoState, FifoStateV0>(&fifo_state, FifoStateV0::LEN)?;
+
+    realloc_account(
+        &fifo_state,
+        &ctx.accounts.payer.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        FifoState::LEN,
+    )?;
+    store_account(&fifo_state, &legacy.migrate())?;
+
+    emit_cpi!(AccountMigrated {
+        version: EVENT_VERSION,
+        account: fifo_state.key(),
+        state_version: STATE_VERSION,
+    });
+
+    msg!("FIFO state migrated to version {}", STATE_VERSION);
+
+    Ok(())
+}
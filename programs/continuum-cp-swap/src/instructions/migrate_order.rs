@@ -0,0 +1,106 @@
+use crate::errors::ContinuumError;
+use crate::state::*;
+use crate::utils::{load_legacy, realloc_account, store_account};
+use anchor_lang::prelude::*;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct MigrateOrder<'info> {
+    #[account(
+        seeds = [b"admin_config"],
+        bump,
+        has_one = super_admin @ ContinuumError::Unauthorized,
+    )]
+    pub admin_config: Account<'info, AdminConfig>,
+
+    /// CHECK: Deserialized by hand, the old layout does not fit `OrderState`
+    #[account(mut, owner = crate::ID)]
+    pub order_state: UncheckedAccount<'info>,
+
+    /// CHECK: Checked against the migrated order
+    pub user: UncheckedAccount<'info>,
+
+    /// CHECK: Checked against the migrated order
+    pub pool_id: UncheckedAccount<'info>,
+
+    /// Pending counters settling the order releases, created for orders that
+    /// predate them
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = UserState::LEN,
+        seeds = [b"user_state", user.key().as_ref()],
+        bump
+    )]
+    pub user_state: Account<'info, UserState>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = UserPoolState::LEN,
+        seeds = [b"user_pool_state", user.key().as_ref(), pool_id.key().as_ref()],
+        bump
+    )]
+    pub user_pool_state: Account<'info, UserPoolState>,
+
+    pub super_admin: Signer<'info>,
+
+    /// Pays the extra rent for the larger account
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn migrate_order(ctx: Context<MigrateOrder>) -> Result<()> {
+    let order_state = ctx.accounts.order_state.to_account_info();
+    // Every older layout upgrades straight to the current one
+    let uncounted = order_state.data_len() == OrderStateV0::LEN;
+    let migrated = match order_state.data_len() {
+        OrderStateV0::LEN => {
+            load_legacy::<OrderState, OrderStateV0>(&order_state, OrderStateV0::LEN)?.migrate()
+        }
+        OrderStateV1::LEN => {
+            load_legacy::<OrderState, OrderStateV1>(&order_state, OrderStateV1::LEN)?.migrate()
+        }
+        _ => load_legacy::<OrderState, OrderStateV2>(&order_state, OrderStateV2::LEN)?.migrate(),
+    };
+    let sequence = migrated.sequence;
+    require_keys_eq!(
+        migrated.user,
+        ctx.accounts.user.key(),
+        ContinuumError::Unauthorized
+    );
+    require_keys_eq!(
+        migrated.pool_id,
+        ctx.accounts.pool_id.key(),
+        ContinuumError::PoolNotRegistered
+    );
+
+    // Orders from before the pending counters were never counted, those
+    // written since already are
+    if uncounted && migrated.status == OrderStatus::Pending {
+        ctx.accounts.user_state.record_submission(migrated.user);
+        ctx.accounts
+            .user_pool_state
+            .record_submission(migrated.user, migrated.pool_id);
+    }
+
+    realloc_account(
+        &order_state,
+        &ctx.accounts.payer.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        OrderState::LEN,
+    )?;
+    store_account(&order_state, &migrated)?;
+
+    emit_cpi!(AccountMigrated {
+        version: EVENT_VERSION,
+        account: order_state.key(),
+        state_version: STATE_VERSION,
+    });
+
+    msg!("Order {} migrated to version {}", sequence, STATE_VERSION);
+
+    Ok(())
+}
@@ -0,0 +1,68 @@
+use crate::cp_swap::{self, PoolStateView};
+use crate::errors::ContinuumError;
+use crate::state::*;
+use crate::utils::{load_legacy, realloc_account, store_account};
+use anchor_lang::prelude::*;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct MigratePoolRegistry<'info> {
+    #[account(
+        seeds = [b"admin_config"],
+        bump,
+        has_one = super_admin @ ContinuumError::Unauthorized,
+    )]
+    pub admin_config: Account<'info, AdminConfig>,
+
+    /// CHECK: Deserialized by hand, the old layout does not fit `CpSwapPoolRegistry`
+    #[account(
+        mut,
+        seeds = [b"pool_registry", pool_state.key().as_ref()],
+        bump,
+        owner = crate::ID,
+    )]
+    pub pool_registry: UncheckedAccount<'info>,
+
+    /// CHECK: The registered CP-Swap pool, read for its vaults
+    #[account(owner = cp_swap::ID @ ContinuumError::InvalidPoolConfig)]
+    pub pool_state: UncheckedAccount<'info>,
+
+    pub super_admin: Signer<'info>,
+
+    /// Pays the extra rent for the larger account
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn migrate_pool_registry(ctx: Context<MigratePoolRegistry>) -> Result<()> {
+    let pool_registry = ctx.accounts.pool_registry.to_account_info();
+    let legacy = load_legacy::<CpSwapPoolRegistry, CpSwapPoolRegistryV0>(
+        &pool_registry,
+        CpSwapPoolRegistryV0::LEN,
+    )?;
+    let pool = PoolStateView::load(&ctx.accounts.pool_state)?;
+
+    realloc_account(
+        &pool_registry,
+        &ctx.accounts.payer.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        CpSwapPoolRegistry::LEN,
+    )?;
+    store_account(&pool_registry, &legacy.migrate(&pool))?;
+
+    emit_cpi!(AccountMigrated {
+        version: EVENT_VERSION,
+        account: pool_registry.key(),
+        state_version: STATE_VERSION,
+    });
+
+    msg!(
+        "Pool registry {} migrated to version {}",
+        ctx.accounts.pool_state.key(),
+        STATE_VERSION
+    );
+
+    Ok(())
+}
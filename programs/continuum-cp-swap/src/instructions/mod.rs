@@ -1,19 +1,77 @@
+pub mod accept_super_admin;
+pub mod amend_order;
+pub mod cancel_config_change;
 pub mod cancel_order;
+pub mod cancel_ring_order;
+pub mod commit_order;
 pub mod deposit_liquidity;
+pub mod execute_config_change;
+pub mod execute_multi_hop_order;
 pub mod execute_order;
+pub mod execute_ring_order;
+pub mod expire_order;
+pub mod expire_ring_order;
+pub mod fail_order;
+pub mod get_queue_status;
 pub mod initialize;
+pub mod initialize_admin_config;
 pub mod initialize_cp_swap_pool;
+pub mod initialize_order_ring;
+pub mod migrate_fifo_state;
+pub mod migrate_order;
+pub mod migrate_pool_registry;
+pub mod queue_config_change;
+pub mod quote_swap;
+pub mod register_existing_pool;
+pub mod report_misbehavior;
+pub mod request_unstake;
+pub mod reveal_order;
+pub mod set_emergency_pause;
+pub mod stake_relayer;
+pub mod submit_multi_hop_order;
 pub mod submit_order;
 pub mod submit_order_simple;
+pub mod submit_order_with_intent;
+pub mod submit_ring_order;
 pub mod swap_immediate;
 pub mod withdraw_liquidity;
+pub mod withdraw_stake;
 
+pub use accept_super_admin::*;
+pub use amend_order::*;
+pub use cancel_config_change::*;
 pub use cancel_order::*;
+pub use cancel_ring_order::*;
+pub use commit_order::*;
 pub use deposit_liquidity::*;
+pub use execute_config_change::*;
+pub use execute_multi_hop_order::*;
 pub use execute_order::*;
+pub use execute_ring_order::*;
+pub use expire_order::*;
+pub use expire_ring_order::*;
+pub use fail_order::*;
+pub use get_queue_status::*;
 pub use initialize::*;
+pub use initialize_admin_config::*;
 pub use initialize_cp_swap_pool::*;
+pub use initialize_order_ring::*;
+pub use migrate_fifo_state::*;
+pub use migrate_order::*;
+pub use migrate_pool_registry::*;
+pub use queue_config_change::*;
+pub use quote_swap::*;
+pub use register_existing_pool::*;
+pub use report_misbehavior::*;
+pub use request_unstake::*;
+pub use reveal_order::*;
+pub use set_emergency_pause::*;
+pub use stake_relayer::*;
+pub use submit_multi_hop_order::*;
 pub use submit_order::*;
 pub use submit_order_simple::*;
+pub use submit_order_with_intent::*;
+pub use submit_ring_order::*;
 pub use swap_immediate::*;
 pub use withdraw_liquidity::*;
+pub use withdraw_stake::*;
@@ -0,0 +1,74 @@
+use crate::errors::ContinuumError;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct QueueConfigChange<'info> {
+    #[account(
+        mut,
+        seeds = [b"admin_config"],
+        bump,
+    )]
+    pub admin_config: Account<'info, AdminConfig>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = PendingChange::LEN,
+        seeds = [b"pending_change".as_ref(), &admin_config.change_count.to_le_bytes()],
+        bump
+    )]
+    pub pending_change: Account<'info, PendingChange>,
+
+    /// Holder of the role the change requires, may be a multisig PDA
+    pub admin: Signer<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+    pub clock: Sysvar<'info, Clock>,
+}
+
+pub fn queue_config_change(ctx: Context<QueueConfigChange>, change: ConfigChange) -> Result<()> {
+    let admin = ctx.accounts.admin.key();
+    let admin_config = &mut ctx.accounts.admin_config;
+    require!(
+        change.is_authorized(admin_config, &admin),
+        ContinuumError::Unauthorized
+    );
+    change.validate()?;
+
+    let id = admin_config.change_count;
+    admin_config.change_count += 1;
+
+    let now = ctx.accounts.clock.unix_timestamp;
+    let effective_after = now
+        .checked_add(admin_config.timelock_delay)
+        .ok_or(ContinuumError::InvalidConfigChange)?;
+
+    let pending_change = &mut ctx.accounts.pending_change;
+    pending_change.id = id;
+    pending_change.proposer = admin;
+    pending_change.payer = ctx.accounts.payer.key();
+    pending_change.change = change.clone();
+    pending_change.queued_at = now;
+    pending_change.effective_after = effective_after;
+
+    emit_cpi!(ConfigChangeQueued {
+        version: EVENT_VERSION,
+        id,
+        proposer: admin,
+        change,
+        effective_after,
+    });
+
+    msg!(
+        "Config change {} queued, executable after {}",
+        id,
+        effective_after
+    );
+
+    Ok(())
+}
@@ -0,0 +1,89 @@
+use crate::cp_swap::{self, AmmConfigView, PoolStateView};
+use crate::errors::ContinuumError;
+use crate::state::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token::TokenAccount;
+
+#[derive(Accounts)]
+pub struct QuoteSwap<'info> {
+    #[account(
+        seeds = [b"pool_registry", pool_state.key().as_ref()],
+        bump,
+        constraint = pool_registry.is_active @ ContinuumError::PoolNotRegistered,
+    )]
+    pub pool_registry: Account<'info, CpSwapPoolRegistry>,
+
+    /// CHECK: The registered CP-Swap pool, decoded in the handler
+    #[account(owner = cp_swap::ID @ ContinuumError::InvalidPoolConfig)]
+    pub pool_state: UncheckedAccount<'info>,
+
+    /// CHECK: The pool's fee config, checked against the pool and decoded in the handler
+    pub amm_config: UncheckedAccount<'info>,
+
+    #[account(address = pool_registry.token_0_vault @ ContinuumError::InvalidPoolConfig)]
+    pub token_0_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(address = pool_registry.token_1_vault @ ContinuumError::InvalidPoolConfig)]
+    pub token_1_vault: Box<Account<'info, TokenAccount>>,
+}
+
+/// Quotes a swap of `amount` against the pool's current reserves. `amount` is
+/// the input for base input swaps and the desired output otherwise. Continuum
+/// takes no cut of the swap itself, relayer fees on intents are paid apart from
+/// `amount_in`, so CP-Swap's fees are the only ones applied.
+pub fn quote_swap(
+    ctx: Context<QuoteSwap>,
+    amount: u64,
+    zero_for_one: bool,
+    is_base_input: bool,
+) -> Result<SwapQuote> {
+    require!(amount > 0, ContinuumError::InvalidAmount);
+
+    let pool = PoolStateView::load(&ctx.accounts.pool_state)?;
+    require_keys_eq!(
+        ctx.accounts.amm_config.key(),
+        pool.amm_config,
+        ContinuumError::InvalidPoolConfig
+    );
+    let config = AmmConfigView::load(&ctx.accounts.amm_config)?;
+
+    let (reserve_0, reserve_1) = pool.reserves(
+        ctx.accounts.token_0_vault.amount,
+        ctx.accounts.token_1_vault.amount,
+    )?;
+    let (input_reserve, output_reserve) = if zero_for_one {
+        (reserve_0, reserve_1)
+    } else {
+        (reserve_1, reserve_0)
+    };
+
+    let (amount_in, amount_out) = if is_base_input {
+        let amount_out = cp_swap::swap_base_input(&config, amount, input_reserve, output_reserve)?;
+        (amount, amount_out)
+    } else {
+        let amount_in = cp_swap::swap_base_output(&config, amount, input_reserve, output_reserve)?;
+        (amount_in, amount)
+    };
+
+    let trade_fee = config.trade_fee(amount_in)?;
+    let (protocol_fee, fund_fee) = config.split_trade_fee(trade_fee);
+
+    // 1 - (amount_out / amount_in_less_fee) / (output_reserve / input_reserve)
+    let amount_in_less_fee = (amount_in - trade_fee) as u128;
+    let price_impact_bps = if amount_in_less_fee == 0 || output_reserve == 0 {
+        0
+    } else {
+        let execution = amount_out as u128 * input_reserve as u128 * 10_000
+            / (amount_in_less_fee * output_reserve as u128);
+        10_000u128.saturating_sub(execution) as u16
+    };
+
+    Ok(SwapQuote {
+        amount_in,
+        amount_out,
+        trade_fee,
+        protocol_fee,
+        fund_fee,
+        price_impact_bps,
+    })
+}
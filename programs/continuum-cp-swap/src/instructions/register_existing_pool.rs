@@ -0,0 +1,75 @@
+use crate::cp_swap::{self, PoolStateView};
+use crate::errors::ContinuumError;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct RegisterExistingPool<'info> {
+    #[account(
+        seeds = [b"admin_config"],
+        bump,
+        constraint = admin_config.has_role(AdminRole::PoolManager, &admin.key()) @ ContinuumError::Unauthorized,
+    )]
+    pub admin_config: Account<'info, AdminConfig>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = CpSwapPoolRegistry::LEN,
+        seeds = [b"pool_registry", pool_state.key().as_ref()],
+        bump
+    )]
+    pub pool_registry: Account<'info, CpSwapPoolRegistry>,
+
+    /// The pool authority PDA the pool must already use as custom authority
+    /// CHECK: This is a PDA checked against the pool's custom authority
+    #[account(
+        seeds = [b"cp_pool_authority", pool_state.key().as_ref()],
+        bump
+    )]
+    pub pool_authority: UncheckedAccount<'info>,
+
+    pub admin: Signer<'info>,
+
+    /// Pays for the registry, separate so the admin can be a multisig PDA
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: An existing CP-Swap pool state, decoded and verified in the handler
+    #[account(owner = cp_swap::ID @ ContinuumError::InvalidPoolConfig)]
+    pub pool_state: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn register_existing_pool(ctx: Context<RegisterExistingPool>) -> Result<()> {
+    let pool_state_key = ctx.accounts.pool_state.key();
+    let pool_authority = ctx.accounts.pool_authority.key();
+
+    let pool = PoolStateView::load(&ctx.accounts.pool_state)?;
+    require!(
+        pool.is_controlled_by(&pool_authority),
+        ContinuumError::InvalidPoolConfig
+    );
+
+    let registry = &mut ctx.accounts.pool_registry;
+    registry.register(
+        pool_state_key,
+        pool_authority,
+        &pool,
+        Clock::get()?.unix_timestamp,
+    );
+
+    emit_cpi!(PoolRegistered {
+        version: EVENT_VERSION,
+        pool_id: pool_state_key,
+        continuum_authority: pool_authority,
+        token_0: pool.token_0_mint,
+        token_1: pool.token_1_mint,
+    });
+
+    msg!("Existing CP-Swap pool {} registered", pool_state_key);
+
+    Ok(())
+}
@@ -0,0 +1,100 @@
+use crate::errors::*;
+use crate::state::*;
+use crate::utils::{debug_msg, transfer_lamports};
+use anchor_lang::prelude::*;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ReportMisbehavior<'info> {
+    /// Order the relayer executed out of turn, each execution is reported once
+    #[account(
+        mut,
+        constraint = executed_order.executed_by != 0 @ ContinuumError::InvalidMisbehaviorEvidence,
+    )]
+    pub executed_order: Account<'info, OrderState>,
+
+    /// Older order of the same pool that was pending and eligible at the time
+    pub skipped_order: Account<'info, OrderState>,
+
+    #[account(
+        seeds = [b"pool_registry", executed_order.pool_id.as_ref()],
+        bump,
+    )]
+    pub pool_registry: Account<'info, CpSwapPoolRegistry>,
+
+    #[account(
+        mut,
+        seeds = [b"relayer_stake", relayer_stake.relayer.as_ref()],
+        bump,
+        constraint = relayer_stake.id == executed_order.executed_by
+            @ ContinuumError::InvalidMisbehaviorEvidence,
+    )]
+    pub relayer_stake: Account<'info, RelayerStake>,
+
+    /// User of the skipped order, compensated with part of the slash
+    #[account(mut, address = skipped_order.user @ ContinuumError::Unauthorized)]
+    pub skipped_user: SystemAccount<'info>,
+
+    /// Anyone but the skipped order's user can report, and is rewarded with
+    /// the other part
+    #[account(
+        mut,
+        constraint = reporter.key() != skipped_order.user @ ContinuumError::Unauthorized,
+    )]
+    pub reporter: Signer<'info>,
+}
+
+/// Slashes `relayer_slash_bps` of the executing relayer's stake when the
+/// orders show it skipped an older eligible order, split evenly between the
+/// reporter and the skipped order's user. Pools without a relayer slash only
+/// record the report.
+pub fn report_misbehavior(ctx: Context<ReportMisbehavior>) -> Result<()> {
+    let executed_order = &ctx.accounts.executed_order;
+    let skipped_order = &ctx.accounts.skipped_order;
+    require!(
+        skipped_order.was_skipped_for(executed_order),
+        ContinuumError::InvalidMisbehaviorEvidence
+    );
+
+    let relayer_stake = &mut ctx.accounts.relayer_stake;
+    let slashed = (relayer_stake.amount as u128
+        * ctx.accounts.pool_registry.relayer_slash_bps as u128
+        / 10_000) as u64;
+    let reporter_reward = slashed / 2;
+    let user_compensation = slashed - reporter_reward;
+    relayer_stake.amount -= slashed;
+    transfer_lamports(
+        &relayer_stake.to_account_info(),
+        &ctx.accounts.reporter.to_account_info(),
+        reporter_reward,
+    )?;
+    transfer_lamports(
+        &relayer_stake.to_account_info(),
+        &ctx.accounts.skipped_user.to_account_info(),
+        user_compensation,
+    )?;
+
+    emit_cpi!(RelayerSlashed {
+        version: EVENT_VERSION,
+        relayer: relayer_stake.relayer,
+        pool_id: executed_order.pool_id,
+        executed_sequence: executed_order.sequence,
+        skipped_sequence: skipped_order.sequence,
+        reporter: ctx.accounts.reporter.key(),
+        reporter_reward,
+        user: skipped_order.user,
+        user_compensation,
+    });
+
+    debug_msg!(
+        "Relayer {} slashed {} for executing order {} before {}",
+        relayer_stake.relayer,
+        slashed,
+        executed_order.sequence,
+        skipped_order.sequence
+    );
+
+    ctx.accounts.executed_order.executed_by = 0;
+
+    Ok(())
+}
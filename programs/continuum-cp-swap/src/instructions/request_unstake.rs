@@ -0,0 +1,39 @@
+use crate::errors::*;
+use crate::state::*;
+use crate::utils::debug_msg;
+use anchor_lang::prelude::*;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct RequestUnstake<'info> {
+    #[account(
+        mut,
+        seeds = [b"relayer_stake", relayer.key().as_ref()],
+        bump,
+        constraint = relayer_stake.unstake_requested_at == 0 @ ContinuumError::RelayerUnstaking,
+    )]
+    pub relayer_stake: Account<'info, RelayerStake>,
+
+    pub relayer: Signer<'info>,
+
+    pub clock: Sysvar<'info, Clock>,
+}
+
+/// Stops the relayer from executing orders. The stake stays slashable for
+/// `RELAYER_UNSTAKE_DELAY` before it can be withdrawn.
+pub fn request_unstake(ctx: Context<RequestUnstake>) -> Result<()> {
+    let now = ctx.accounts.clock.unix_timestamp;
+    let relayer_stake = &mut ctx.accounts.relayer_stake;
+    relayer_stake.unstake_requested_at = now;
+
+    emit_cpi!(RelayerUnstakeRequested {
+        version: EVENT_VERSION,
+        relayer: relayer_stake.relayer,
+        amount: relayer_stake.amount,
+        withdrawable_at: now.saturating_add(RELAYER_UNSTAKE_DELAY),
+    });
+
+    debug_msg!("Relayer {} requested to unstake", relayer_stake.relayer);
+
+    Ok(())
+}
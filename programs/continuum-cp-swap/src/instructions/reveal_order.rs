@@ -0,0 +1,83 @@
+use crate::errors::*;
+use crate::state::*;
+use crate::utils::debug_msg;
+use anchor_lang::prelude::*;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct RevealOrder<'info> {
+    #[account(
+        mut,
+        seeds = [b"order", user.key().as_ref(), &order_state.seed_sequence().to_le_bytes()],
+        bump,
+        constraint = order_state.status == OrderStatus::Committed @ ContinuumError::InvalidOrderStatus,
+    )]
+    pub order_state: Account<'info, OrderState>,
+
+    /// Closed to the user once the order is revealed
+    #[account(
+        mut,
+        close = user,
+        seeds = [b"order_commitment", order_state.key().as_ref()],
+        bump
+    )]
+    pub order_commitment: Account<'info, OrderCommitment>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    pub clock: Sysvar<'info, Clock>,
+}
+
+/// Reveals a committed order's parameters, which must hash to its commitment.
+/// The order keeps its queue position and becomes executable like a pending
+/// order submitted now.
+pub fn reveal_order(
+    ctx: Context<RevealOrder>,
+    amount_in: u64,
+    min_amount_out: u64,
+    is_base_input: bool,
+    salt: [u8; 32],
+) -> Result<()> {
+    let clock = &ctx.accounts.clock;
+    require!(
+        clock.unix_timestamp <= ctx.accounts.order_commitment.reveal_deadline,
+        ContinuumError::RevealWindowClosed
+    );
+
+    let order_state = &mut ctx.accounts.order_state;
+    let commitment = OrderCommitment::hash(
+        &order_state.user,
+        &order_state.pool_id,
+        amount_in,
+        min_amount_out,
+        is_base_input,
+        &salt,
+    );
+    require!(
+        commitment == ctx.accounts.order_commitment.commitment,
+        ContinuumError::InvalidCommitment
+    );
+    require!(amount_in > 0, ContinuumError::InvalidAmount);
+
+    order_state.amount_in = amount_in;
+    order_state.min_amount_out = min_amount_out;
+    order_state.is_base_input = is_base_input;
+    order_state.status = OrderStatus::Pending;
+    // The parameters are public from now on, the inclusion delay runs from here
+    order_state.submitted_slot = clock.slot;
+
+    emit_cpi!(OrderRevealed {
+        version: EVENT_VERSION,
+        sequence: order_state.sequence,
+        user: order_state.user,
+        amount_in,
+        min_amount_out,
+        is_base_input,
+        revealed_at: clock.unix_timestamp,
+    });
+
+    debug_msg!("Order {} revealed", order_state.sequence);
+
+    Ok(())
+}
@@ -0,0 +1,37 @@
+use crate::errors::ContinuumError;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SetEmergencyPause<'info> {
+    #[account(
+        seeds = [b"admin_config"],
+        bump,
+        constraint = admin_config.has_role(AdminRole::Pauser, &pauser.key()) @ ContinuumError::Unauthorized,
+    )]
+    pub admin_config: Account<'info, AdminConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"fifo_state"],
+        bump,
+    )]
+    pub fifo_state: Account<'info, FifoState>,
+
+    pub pauser: Signer<'info>,
+}
+
+pub fn set_emergency_pause(ctx: Context<SetEmergencyPause>, paused: bool) -> Result<()> {
+    ctx.accounts.fifo_state.emergency_pause = paused;
+
+    emit_cpi!(EmergencyPauseSet {
+        version: EVENT_VERSION,
+        paused,
+        authority: ctx.accounts.pauser.key(),
+    });
+
+    msg!("Emergency pause set to {}", paused);
+
+    Ok(())
+}
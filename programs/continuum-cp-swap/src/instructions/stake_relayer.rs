@@ -0,0 +1,79 @@
+use crate::errors::*;
+use crate::state::*;
+use crate::utils::debug_msg;
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct StakeRelayer<'info> {
+    #[account(
+        mut,
+        seeds = [b"fifo_state"],
+        bump,
+    )]
+    pub fifo_state: Account<'info, FifoState>,
+
+    #[account(
+        init_if_needed,
+        payer = relayer,
+        space = RelayerStake::LEN,
+        seeds = [b"relayer_stake", relayer.key().as_ref()],
+        bump
+    )]
+    pub relayer_stake: Account<'info, RelayerStake>,
+
+    #[account(mut)]
+    pub relayer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Adds `amount` lamports to the relayer's stake, opening it on first use
+pub fn stake_relayer(ctx: Context<StakeRelayer>, amount: u64) -> Result<()> {
+    require!(amount > 0, ContinuumError::InvalidAmount);
+
+    let relayer_stake = &mut ctx.accounts.relayer_stake;
+    require!(
+        relayer_stake.unstake_requested_at == 0,
+        ContinuumError::RelayerUnstaking
+    );
+    if relayer_stake.id == 0 {
+        let fifo_state = &mut ctx.accounts.fifo_state;
+        fifo_state.relayer_count += 1;
+        relayer_stake.relayer = ctx.accounts.relayer.key();
+        relayer_stake.id = fifo_state.relayer_count;
+    }
+
+    system_program::transfer(
+        CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.relayer.to_account_info(),
+                to: relayer_stake.to_account_info(),
+            },
+        ),
+        amount,
+    )?;
+    relayer_stake.amount = relayer_stake
+        .amount
+        .checked_add(amount)
+        .ok_or(ContinuumError::MathOverflow)?;
+
+    emit_cpi!(RelayerStaked {
+        version: EVENT_VERSION,
+        relayer: relayer_stake.relayer,
+        stake_id: relayer_stake.id,
+        amount,
+        total: relayer_stake.amount,
+    });
+
+    debug_msg!(
+        "Relayer {} staked {}, {} in total",
+        relayer_stake.relayer,
+        amount,
+        relayer_stake.amount
+    );
+
+    Ok(())
+}
@@ -0,0 +1,193 @@
+use crate::errors::*;
+use crate::state::*;
+use crate::utils::debug_msg;
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(route: Vec<Pubkey>)]
+pub struct SubmitMultiHopOrder<'info> {
+    #[account(
+        mut,
+        seeds = [b"fifo_state"],
+        bump,
+        constraint = !fifo_state.emergency_pause @ ContinuumError::EmergencyPause,
+    )]
+    pub fifo_state: Account<'info, FifoState>,
+
+    #[account(
+        init,
+        payer = user,
+        space = OrderState::LEN,
+        seeds = [b"order", user.key().as_ref(), &fifo_state.current_sequence.to_le_bytes()],
+        bump
+    )]
+    pub order_state: Account<'info, OrderState>,
+
+    /// Indexed under the first pool of the route, where the order queues
+    #[account(
+        init,
+        payer = user,
+        space = OrderIndex::LEN,
+        seeds = [
+            b"order_index",
+            route.first().ok_or(ContinuumError::InvalidRoute)?.as_ref(),
+            &(fifo_state.current_sequence + 1).to_le_bytes(),
+        ],
+        bump
+    )]
+    pub order_index: Account<'info, OrderIndex>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = UserState::LEN,
+        seeds = [b"user_state", user.key().as_ref()],
+        bump
+    )]
+    pub user_state: Account<'info, UserState>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = UserPoolState::LEN,
+        seeds = [b"user_pool_state", user.key().as_ref(), route.first().ok_or(ContinuumError::InvalidRoute)?.as_ref()],
+        bump
+    )]
+    pub user_pool_state: Account<'info, UserPoolState>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    /// Current tail of the first pool's queue, required unless it is empty
+    #[account(mut)]
+    pub queue_tail: Option<Account<'info, OrderState>>,
+
+    pub system_program: Program<'info, System>,
+    pub clock: Sysvar<'info, Clock>,
+    // Remaining accounts are the pool registries of the route, in route order.
+    // The order queues in the first pool, so its registry must be writable.
+}
+
+pub fn submit_multi_hop_order<'info>(
+    ctx: Context<'_, '_, 'info, 'info, SubmitMultiHopOrder<'info>>,
+    route: Vec<Pubkey>,
+    amount_in: u64,
+    min_amount_out: u64,
+) -> Result<()> {
+    require!(
+        route.len() >= 2 && route.len() <= MAX_ROUTE_LEN,
+        ContinuumError::InvalidRoute
+    );
+    require!(
+        ctx.remaining_accounts.len() == route.len(),
+        ContinuumError::InvalidRoute
+    );
+
+    // Every pool in the route must be registered, active and distinct
+    let mut order_bond = 0;
+    let mut queue_registry = None;
+    let mut pairs = Vec::with_capacity(route.len());
+    for (i, (pool_id, registry_info)) in route.iter().zip(ctx.remaining_accounts).enumerate() {
+        require!(!route[..i].contains(pool_id), ContinuumError::InvalidRoute);
+
+        let (expected_registry, _) =
+            Pubkey::find_program_address(&[b"pool_registry", pool_id.as_ref()], ctx.program_id);
+        require_keys_eq!(
+            registry_info.key(),
+            expected_registry,
+            ContinuumError::PoolNotRegistered
+        );
+
+        let registry = Account::<CpSwapPoolRegistry>::try_from(registry_info)?;
+        require!(
+            registry.accepts_orders(),
+            ContinuumError::PoolNotAcceptingOrders
+        );
+        pairs.push((registry.token_0, registry.token_1));
+
+        // The first pool's bond, cap and queue apply to the whole route
+        if i == 0 {
+            registry.check_pending_cap(&ctx.accounts.user_pool_state)?;
+            order_bond = registry.order_bond;
+            queue_registry = Some(registry);
+        }
+    }
+    // Each pool must trade what the one before it outputs
+    route_mints(&pairs)?;
+
+    ctx.accounts
+        .user_state
+        .record_submission(ctx.accounts.user.key());
+    ctx.accounts
+        .user_pool_state
+        .record_submission(ctx.accounts.user.key(), route[0]);
+
+    // Bond lamports into the order account, refunded when the order settles
+    if order_bond > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.user.to_account_info(),
+                    to: ctx.accounts.order_state.to_account_info(),
+                },
+            ),
+            order_bond,
+        )?;
+    }
+
+    let fifo_state = &mut ctx.accounts.fifo_state;
+    let order_state = &mut ctx.accounts.order_state;
+
+    // The order queues in the first pool only. The later pools are traded
+    // through at execution without the order taking a place in their queues.
+    let new_sequence = fifo_state.current_sequence + 1;
+    fifo_state.current_sequence = new_sequence;
+
+    let route_len = route.len();
+    let mut route_pools = [Pubkey::default(); MAX_ROUTE_LEN - 1];
+    route_pools[..route_len - 1].copy_from_slice(&route[1..]);
+
+    order_state.sequence = new_sequence;
+    order_state.user = ctx.accounts.user.key();
+    order_state.pool_id = route[0];
+    order_state.amount_in = amount_in;
+    order_state.min_amount_out = min_amount_out;
+    // Multi-hop orders are always exact input, min_amount_out applies end to end
+    order_state.is_base_input = true;
+    order_state.status = OrderStatus::Pending;
+    order_state.submitted_at = ctx.accounts.clock.unix_timestamp;
+    order_state.submitted_slot = ctx.accounts.clock.slot;
+    order_state.executed_at = None;
+    order_state.route_len = route_len as u8;
+    order_state.route_pools = route_pools;
+    order_state.bond_lamports = order_bond;
+    order_state.version = StateVersion::CURRENT;
+
+    let mut queue_registry = queue_registry.ok_or(ContinuumError::InvalidRoute)?;
+    queue_registry.enqueue(order_state, ctx.accounts.queue_tail.as_deref_mut())?;
+    queue_registry.exit(ctx.program_id)?;
+    ctx.accounts.order_index.order = order_state.key();
+
+    emit_cpi!(MultiHopOrderSubmitted {
+        version: EVENT_VERSION,
+        sequence: new_sequence,
+        user: ctx.accounts.user.key(),
+        route,
+        amount_in,
+        min_amount_out,
+        bond_lamports: order_bond,
+        submitted_at: ctx.accounts.clock.unix_timestamp,
+    });
+
+    debug_msg!(
+        "Multi-hop order {} submitted by user {} across {} pools",
+        new_sequence,
+        ctx.accounts.user.key(),
+        route_len
+    );
+
+    Ok(())
+}
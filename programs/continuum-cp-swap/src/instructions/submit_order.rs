@@ -1,7 +1,11 @@
 use crate::errors::*;
 use crate::state::*;
+use crate::utils::debug_msg;
 use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_spl::token::TokenAccount;
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct SubmitOrder<'info> {
     #[account(
@@ -13,12 +17,17 @@ pub struct SubmitOrder<'info> {
     pub fifo_state: Account<'info, FifoState>,
 
     #[account(
+        mut,
         seeds = [b"pool_registry", pool_id.key().as_ref()],
         bump,
-        constraint = pool_registry.is_active @ ContinuumError::PoolNotRegistered,
+        constraint = pool_registry.accepts_orders() @ ContinuumError::PoolNotAcceptingOrders,
     )]
     pub pool_registry: Account<'info, CpSwapPoolRegistry>,
 
+    /// Current tail of the pool queue, required unless the queue is empty
+    #[account(mut)]
+    pub queue_tail: Option<Account<'info, OrderState>>,
+
     #[account(
         init,
         payer = user,
@@ -28,9 +37,50 @@ pub struct SubmitOrder<'info> {
     )]
     pub order_state: Account<'info, OrderState>,
 
+    #[account(
+        init,
+        payer = user,
+        space = OrderIndex::LEN,
+        seeds = [
+            b"order_index",
+            pool_id.key().as_ref(),
+            &(fifo_state.current_sequence + 1).to_le_bytes(),
+        ],
+        bump
+    )]
+    pub order_index: Account<'info, OrderIndex>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = UserState::LEN,
+        seeds = [b"user_state", user.key().as_ref()],
+        bump
+    )]
+    pub user_state: Account<'info, UserState>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = UserPoolState::LEN,
+        seeds = [b"user_pool_state", user.key().as_ref(), pool_id.key().as_ref()],
+        bump
+    )]
+    pub user_pool_state: Account<'info, UserPoolState>,
+
     #[account(mut)]
     pub user: Signer<'info>,
 
+    /// Token account of the user to fund the order from, which also fixes its
+    /// direction. Without one the order executes from any of the user's
+    /// accounts and is judged on the associated ones.
+    #[account(
+        constraint = user_source.owner == user.key() @ ContinuumError::InvalidSourceAccount,
+        constraint = user_source.mint == pool_registry.token_0
+            || user_source.mint == pool_registry.token_1 @ ContinuumError::InvalidSourceAccount,
+    )]
+    pub user_source: Option<Account<'info, TokenAccount>>,
+
     /// CHECK: The pool ID to validate against registry
     pub pool_id: UncheckedAccount<'info>,
 
@@ -43,23 +93,48 @@ pub fn submit_order(
     amount_in: u64,
     min_amount_out: u64,
     is_base_input: bool,
+    fill_policy: FillPolicy,
 ) -> Result<()> {
+    let user_pool_state = &mut ctx.accounts.user_pool_state;
+    ctx.accounts
+        .pool_registry
+        .check_pending_cap(user_pool_state)?;
+    user_pool_state.record_submission(ctx.accounts.user.key(), ctx.accounts.pool_id.key());
+    ctx.accounts
+        .user_state
+        .record_submission(ctx.accounts.user.key());
+
+    // Bond lamports into the order account, refunded when the order settles
+    let order_bond = ctx.accounts.pool_registry.order_bond;
+    if order_bond > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.user.to_account_info(),
+                    to: ctx.accounts.order_state.to_account_info(),
+                },
+            ),
+            order_bond,
+        )?;
+    }
+
     let fifo_state = &mut ctx.accounts.fifo_state;
     let order_state = &mut ctx.accounts.order_state;
     let clock = &ctx.accounts.clock;
 
     // Get current sequence for PDA (before increment)
     let pda_sequence = fifo_state.current_sequence;
-    msg!("Submit order - Current FIFO sequence: {}", pda_sequence);
+    debug_msg!("Submit order - Current FIFO sequence: {}", pda_sequence);
 
     // Increment sequence for next order
     let new_sequence = fifo_state.current_sequence + 1;
     fifo_state.current_sequence = new_sequence;
-    msg!("Submit order - New FIFO sequence: {}", new_sequence);
+    debug_msg!("Submit order - New FIFO sequence: {}", new_sequence);
 
     // Store order details with the incremented sequence
     order_state.sequence = new_sequence;
-    msg!(
+    debug_msg!(
         "Submit order - Order stored with sequence: {}",
         new_sequence
     );
@@ -68,19 +143,38 @@ pub fn submit_order(
     order_state.amount_in = amount_in;
     order_state.min_amount_out = min_amount_out;
     order_state.is_base_input = is_base_input;
+    order_state.fill_policy = fill_policy;
     order_state.status = OrderStatus::Pending;
     order_state.submitted_at = clock.unix_timestamp;
+    order_state.submitted_slot = clock.slot;
     order_state.executed_at = None;
+    order_state.route_len = 1;
+    order_state.route_pools = [Pubkey::default(); MAX_ROUTE_LEN - 1];
+    order_state.bond_lamports = order_bond;
+    order_state.source = ctx
+        .accounts
+        .user_source
+        .as_ref()
+        .map_or_else(Pubkey::default, |source| source.key());
+    order_state.version = StateVersion::CURRENT;
+    ctx.accounts
+        .pool_registry
+        .enqueue(order_state, ctx.accounts.queue_tail.as_deref_mut())?;
+    ctx.accounts.order_index.order = order_state.key();
 
-    emit!(OrderSubmitted {
+    emit_cpi!(OrderSubmitted {
+        version: EVENT_VERSION,
         sequence: new_sequence,
         user: ctx.accounts.user.key(),
         pool_id: ctx.accounts.pool_id.key(),
         amount_in,
+        min_amount_out,
         is_base_input,
+        bond_lamports: order_bond,
+        submitted_at: clock.unix_timestamp,
     });
 
-    msg!(
+    debug_msg!(
         "Order {} submitted by user {} (PDA uses sequence {})",
         new_sequence,
         ctx.accounts.user.key(),
@@ -1,8 +1,10 @@
 use crate::errors::*;
 use crate::state::*;
+use crate::utils::debug_msg;
 use anchor_lang::prelude::*;
 use anchor_spl::token::{Token, TokenAccount};
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct SubmitOrderSimple<'info> {
     #[account(
@@ -16,7 +18,7 @@ pub struct SubmitOrderSimple<'info> {
     #[account(
         seeds = [b"pool_registry", pool_id.key().as_ref()],
         bump,
-        constraint = pool_registry.is_active @ ContinuumError::PoolNotRegistered,
+        constraint = pool_registry.accepts_orders() @ ContinuumError::PoolNotAcceptingOrders,
     )]
     pub pool_registry: Account<'info, CpSwapPoolRegistry>,
 
@@ -47,7 +49,7 @@ pub struct SubmitOrderSimple<'info> {
 pub fn submit_order_simple(
     ctx: Context<SubmitOrderSimple>,
     amount_in: u64,
-    _min_amount_out: u64,
+    min_amount_out: u64,
     is_base_input: bool,
 ) -> Result<()> {
     let fifo_state = &mut ctx.accounts.fifo_state;
@@ -56,19 +58,23 @@ pub fn submit_order_simple(
     let sequence = fifo_state.current_sequence + 1;
     fifo_state.current_sequence = sequence;
 
-    msg!(
+    debug_msg!(
         "Order {} submitted by user {} for pool {}",
         sequence,
         ctx.accounts.user.key(),
         ctx.accounts.pool_id.key()
     );
 
-    emit!(OrderSubmitted {
+    emit_cpi!(OrderSubmitted {
+        version: EVENT_VERSION,
         sequence,
         user: ctx.accounts.user.key(),
         pool_id: ctx.accounts.pool_id.key(),
         amount_in,
+        min_amount_out,
         is_base_input,
+        bond_lamports: 0,
+        submitted_at: ctx.accounts.clock.unix_timestamp,
     });
 
     Ok(())
@@ -0,0 +1,295 @@
+use crate::errors::*;
+use crate::state::*;
+use crate::utils::debug_msg;
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{
+    ed25519_program,
+    sysvar::instructions::{
+        self as instructions_sysvar, load_current_index_checked, load_instruction_at_checked,
+    },
+};
+use anchor_lang::system_program;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(intent: OrderIntent)]
+pub struct SubmitOrderWithIntent<'info> {
+    #[account(
+        mut,
+        seeds = [b"fifo_state"],
+        bump,
+        constraint = !fifo_state.emergency_pause @ ContinuumError::EmergencyPause,
+    )]
+    pub fifo_state: Account<'info, FifoState>,
+
+    #[account(
+        mut,
+        seeds = [b"pool_registry", intent.pool_id.as_ref()],
+        bump,
+        constraint = pool_registry.accepts_orders() @ ContinuumError::PoolNotAcceptingOrders,
+    )]
+    pub pool_registry: Account<'info, CpSwapPoolRegistry>,
+
+    /// Current tail of the pool queue, required unless the queue is empty
+    #[account(mut)]
+    pub queue_tail: Option<Account<'info, OrderState>>,
+
+    #[account(
+        init,
+        payer = relayer,
+        space = OrderState::LEN,
+        seeds = [b"order", intent.user.as_ref(), &fifo_state.current_sequence.to_le_bytes()],
+        bump
+    )]
+    pub order_state: Account<'info, OrderState>,
+
+    #[account(
+        init,
+        payer = relayer,
+        space = OrderIndex::LEN,
+        seeds = [
+            b"order_index",
+            intent.pool_id.as_ref(),
+            &(fifo_state.current_sequence + 1).to_le_bytes(),
+        ],
+        bump
+    )]
+    pub order_index: Account<'info, OrderIndex>,
+
+    #[account(
+        init_if_needed,
+        payer = relayer,
+        space = UserState::LEN,
+        seeds = [b"user_state", intent.user.as_ref()],
+        bump
+    )]
+    pub user_state: Account<'info, UserState>,
+
+    #[account(
+        init_if_needed,
+        payer = relayer,
+        space = UserPoolState::LEN,
+        seeds = [b"user_pool_state", intent.user.as_ref(), intent.pool_id.as_ref()],
+        bump
+    )]
+    pub user_pool_state: Account<'info, UserPoolState>,
+
+    /// The pool authority PDA, delegate on the user's source account
+    /// CHECK: This is a PDA that signs the relayer fee transfer
+    #[account(
+        seeds = [b"cp_pool_authority", intent.pool_id.as_ref()],
+        bump
+    )]
+    pub pool_authority: UncheckedAccount<'info>,
+
+    /// The relayer submitting the order and paying for its accounts
+    #[account(mut)]
+    pub relayer: Signer<'info>,
+
+    /// User's source token account the order is funded from and the relayer
+    /// fee is paid from, as signed in the intent
+    #[account(
+        mut,
+        address = intent.source @ ContinuumError::InvalidSourceAccount,
+        constraint = user_source.owner == intent.user @ ContinuumError::Unauthorized,
+        constraint = user_source.mint == pool_registry.token_0
+            || user_source.mint == pool_registry.token_1 @ ContinuumError::InvalidSourceAccount,
+    )]
+    pub user_source: Box<Account<'info, TokenAccount>>,
+
+    /// Relayer's token account receiving the fee
+    #[account(
+        mut,
+        constraint = relayer_fee_account.mint == user_source.mint @ ContinuumError::InvalidPoolConfig,
+    )]
+    pub relayer_fee_account: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: The instructions sysvar, used to read the ed25519 signature check
+    #[account(address = instructions_sysvar::ID)]
+    pub instructions: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub clock: Sysvar<'info, Clock>,
+}
+
+pub fn submit_order_with_intent(
+    ctx: Context<SubmitOrderWithIntent>,
+    intent: OrderIntent,
+) -> Result<()> {
+    require_keys_eq!(
+        intent.program_id,
+        crate::ID,
+        ContinuumError::InvalidIntentSignature
+    );
+    require!(
+        ctx.accounts.clock.unix_timestamp <= intent.deadline,
+        ContinuumError::IntentExpired
+    );
+
+    let message = intent.try_to_vec()?;
+    verify_intent_signature(&ctx.accounts.instructions, &intent.user, &message)?;
+
+    let user_state = &mut ctx.accounts.user_state;
+    require!(
+        intent.nonce == user_state.nonce,
+        ContinuumError::InvalidIntentNonce
+    );
+    user_state.nonce += 1;
+    user_state.record_submission(intent.user);
+    let user_pool_state = &mut ctx.accounts.user_pool_state;
+    ctx.accounts
+        .pool_registry
+        .check_pending_cap(user_pool_state)?;
+    user_pool_state.record_submission(intent.user, intent.pool_id);
+
+    // The relayer posts the pool bond, refunded to the relayer when the order
+    // settles and slashed on early cancellation like any order's bond
+    let order_bond = ctx.accounts.pool_registry.order_bond;
+    if order_bond > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.relayer.to_account_info(),
+                    to: ctx.accounts.order_state.to_account_info(),
+                },
+            ),
+            order_bond,
+        )?;
+    }
+
+    // Reimburse the relayer in input tokens, signed by the pool authority delegate
+    if intent.relayer_fee > 0 {
+        let pool_authority_seeds = &[
+            b"cp_pool_authority",
+            intent.pool_id.as_ref(),
+            &[ctx.bumps.pool_authority],
+        ];
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.user_source.to_account_info(),
+                    to: ctx.accounts.relayer_fee_account.to_account_info(),
+                    authority: ctx.accounts.pool_authority.to_account_info(),
+                },
+                &[pool_authority_seeds],
+            ),
+            intent.relayer_fee,
+        )?;
+    }
+
+    let fifo_state = &mut ctx.accounts.fifo_state;
+    let order_state = &mut ctx.accounts.order_state;
+
+    let new_sequence = fifo_state.current_sequence + 1;
+    fifo_state.current_sequence = new_sequence;
+
+    order_state.sequence = new_sequence;
+    order_state.user = intent.user;
+    order_state.pool_id = intent.pool_id;
+    order_state.amount_in = intent.amount_in;
+    order_state.min_amount_out = intent.min_amount_out;
+    order_state.is_base_input = intent.is_base_input;
+    order_state.status = OrderStatus::Pending;
+    order_state.submitted_at = ctx.accounts.clock.unix_timestamp;
+    order_state.submitted_slot = ctx.accounts.clock.slot;
+    order_state.executed_at = None;
+    order_state.route_len = 1;
+    order_state.route_pools = [Pubkey::default(); MAX_ROUTE_LEN - 1];
+    order_state.bond_lamports = order_bond;
+    order_state.source = intent.source;
+    order_state.bond_payer = ctx.accounts.relayer.key();
+    order_state.version = StateVersion::CURRENT;
+    ctx.accounts
+        .pool_registry
+        .enqueue(order_state, ctx.accounts.queue_tail.as_deref_mut())?;
+    ctx.accounts.order_index.order = order_state.key();
+
+    emit_cpi!(OrderSubmitted {
+        version: EVENT_VERSION,
+        sequence: new_sequence,
+        user: intent.user,
+        pool_id: intent.pool_id,
+        amount_in: intent.amount_in,
+        min_amount_out: intent.min_amount_out,
+        is_base_input: intent.is_base_input,
+        bond_lamports: order_bond,
+        submitted_at: ctx.accounts.clock.unix_timestamp,
+    });
+
+    emit_cpi!(IntentRelayed {
+        version: EVENT_VERSION,
+        sequence: new_sequence,
+        user: intent.user,
+        relayer: ctx.accounts.relayer.key(),
+        relayer_fee: intent.relayer_fee,
+        nonce: intent.nonce,
+    });
+
+    debug_msg!(
+        "Order {} submitted for user {} by relayer {}",
+        new_sequence,
+        intent.user,
+        ctx.accounts.relayer.key()
+    );
+
+    Ok(())
+}
+
+/// Checks that the instruction right before this one is an ed25519 program
+/// verification of `message` signed by `signer`, with all data inline.
+fn verify_intent_signature(
+    instructions: &AccountInfo,
+    signer: &Pubkey,
+    message: &[u8],
+) -> Result<()> {
+    let current_index = load_current_index_checked(instructions)?;
+    require!(current_index > 0, ContinuumError::InvalidIntentSignature);
+
+    let ed25519_ix = load_instruction_at_checked((current_index - 1) as usize, instructions)?;
+    require_keys_eq!(
+        ed25519_ix.program_id,
+        ed25519_program::ID,
+        ContinuumError::InvalidIntentSignature
+    );
+
+    // Layout: num_signatures (u8), padding (u8), then one Ed25519SignatureOffsets
+    let data = &ed25519_ix.data;
+    require!(
+        data.len() >= 16 && data[0] == 1,
+        ContinuumError::InvalidIntentSignature
+    );
+
+    let read_u16 = |at: usize| u16::from_le_bytes([data[at], data[at + 1]]);
+    let signature_instruction_index = read_u16(4);
+    let public_key_offset = read_u16(6) as usize;
+    let public_key_instruction_index = read_u16(8);
+    let message_data_offset = read_u16(10) as usize;
+    let message_data_size = read_u16(12) as usize;
+    let message_instruction_index = read_u16(14);
+
+    // u16::MAX means the data lives in the ed25519 instruction itself
+    require!(
+        signature_instruction_index == u16::MAX
+            && public_key_instruction_index == u16::MAX
+            && message_instruction_index == u16::MAX,
+        ContinuumError::InvalidIntentSignature
+    );
+
+    let public_key = data
+        .get(public_key_offset..public_key_offset + 32)
+        .ok_or(ContinuumError::InvalidIntentSignature)?;
+    let signed_message = data
+        .get(message_data_offset..message_data_offset + message_data_size)
+        .ok_or(ContinuumError::InvalidIntentSignature)?;
+
+    require!(
+        public_key == signer.as_ref() && signed_message == message,
+        ContinuumError::InvalidIntentSignature
+    );
+
+    Ok(())
+}
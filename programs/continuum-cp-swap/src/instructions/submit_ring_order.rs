@@ -0,0 +1,123 @@
+use crate::errors::*;
+use crate::state::*;
+use crate::utils::debug_msg;
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SubmitRingOrder<'info> {
+    #[account(
+        seeds = [b"fifo_state"],
+        bump,
+        constraint = !fifo_state.emergency_pause @ ContinuumError::EmergencyPause,
+    )]
+    pub fifo_state: Account<'info, FifoState>,
+
+    #[account(
+        seeds = [b"pool_registry", pool_id.key().as_ref()],
+        bump,
+        constraint = pool_registry.accepts_orders() @ ContinuumError::PoolNotAcceptingOrders,
+    )]
+    pub pool_registry: Account<'info, CpSwapPoolRegistry>,
+
+    /// Holds the order record and its bond, no per-order account is created
+    #[account(
+        mut,
+        seeds = [b"order_ring", pool_id.key().as_ref()],
+        bump,
+    )]
+    pub order_ring: AccountLoader<'info, OrderRing>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = UserState::LEN,
+        seeds = [b"user_state", user.key().as_ref()],
+        bump
+    )]
+    pub user_state: Account<'info, UserState>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = UserPoolState::LEN,
+        seeds = [b"user_pool_state", user.key().as_ref(), pool_id.key().as_ref()],
+        bump
+    )]
+    pub user_pool_state: Account<'info, UserPoolState>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    /// CHECK: The pool ID to validate against registry
+    pub pool_id: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+    pub clock: Sysvar<'info, Clock>,
+}
+
+/// Ring orders are bonded and count against the per-user pending cap like
+/// queued orders, so the fixed number of records cannot be taken for free
+pub fn submit_ring_order(
+    ctx: Context<SubmitRingOrder>,
+    amount_in: u64,
+    min_amount_out: u64,
+    is_base_input: bool,
+) -> Result<()> {
+    require!(amount_in > 0, ContinuumError::InvalidAmount);
+
+    let user = ctx.accounts.user.key();
+    let pool_id = ctx.accounts.pool_id.key();
+    let user_pool_state = &mut ctx.accounts.user_pool_state;
+    ctx.accounts
+        .pool_registry
+        .check_pending_cap(user_pool_state)?;
+    user_pool_state.record_submission(user, pool_id);
+    ctx.accounts.user_state.record_submission(user);
+
+    // Bond lamports into the ring, refunded when the order settles
+    let order_bond = ctx.accounts.pool_registry.order_bond;
+    if order_bond > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.user.to_account_info(),
+                    to: ctx.accounts.order_ring.to_account_info(),
+                },
+            ),
+            order_bond,
+        )?;
+    }
+
+    let clock = &ctx.accounts.clock;
+    let submitted_at = clock.unix_timestamp;
+    let sequence = ctx.accounts.order_ring.load_mut()?.push(OrderRecord {
+        user,
+        amount_in,
+        min_amount_out,
+        submitted_at,
+        submitted_slot: clock.slot,
+        bond_lamports: order_bond,
+        is_base_input: is_base_input as u8,
+        cancelled: 0,
+        padding: [0; 6],
+    })?;
+
+    emit_cpi!(RingOrderSubmitted {
+        version: EVENT_VERSION,
+        pool_id,
+        sequence,
+        user,
+        amount_in,
+        min_amount_out,
+        is_base_input,
+        bond_lamports: order_bond,
+        submitted_at,
+    });
+
+    debug_msg!("Ring order {} submitted by user {}", sequence, user);
+
+    Ok(())
+}
@@ -1,3 +1,4 @@
+use crate::cp_swap;
 use crate::errors::ContinuumError;
 use crate::state::*;
 use anchor_lang::prelude::*;
@@ -6,6 +7,7 @@ use anchor_lang::solana_program::{
     program::invoke_signed,
 };
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct WithdrawLiquidity<'info> {
     #[account(
@@ -34,18 +36,26 @@ pub struct WithdrawLiquidity<'info> {
     pub pool_id: UncheckedAccount<'info>,
 
     /// CHECK: Raydium CP-Swap program
+    #[account(address = cp_swap::ID @ ContinuumError::InvalidPoolConfig)]
     pub cp_swap_program: UncheckedAccount<'info>,
     // Remaining accounts are passed directly to CP-Swap withdraw instruction
 }
 
 pub fn withdraw_liquidity<'info>(
     ctx: Context<'_, '_, '_, 'info, WithdrawLiquidity<'info>>,
+    lp_token_amount: u64,
+    minimum_token_0_amount: u64,
+    minimum_token_1_amount: u64,
 ) -> Result<()> {
     let pool_authority_bump = ctx.bumps.pool_authority;
     let pool_id = ctx.accounts.pool_id.key();
 
     // Build CPI instruction data - withdraw discriminator
-    let ix_data = vec![183, 18, 70, 156, 148, 109, 161, 34];
+    let mut ix_data = Vec::with_capacity(32);
+    ix_data.extend_from_slice(&[183, 18, 70, 156, 148, 109, 161, 34]);
+    ix_data.extend_from_slice(&lp_token_amount.to_le_bytes());
+    ix_data.extend_from_slice(&minimum_token_0_amount.to_le_bytes());
+    ix_data.extend_from_slice(&minimum_token_1_amount.to_le_bytes());
 
     // Build account metas - pool authority first signer
     let mut account_metas = vec![AccountMeta::new_readonly(
@@ -88,5 +98,14 @@ pub fn withdraw_liquidity<'info>(
 
     invoke_signed(&ix, &all_accounts, &[seeds])?;
 
+    emit_cpi!(LiquidityWithdrawn {
+        version: EVENT_VERSION,
+        pool_id,
+        lp_token_amount,
+        minimum_token_0_amount,
+        minimum_token_1_amount,
+        withdrawn_at: Clock::get()?.unix_timestamp,
+    });
+
     Ok(())
 }
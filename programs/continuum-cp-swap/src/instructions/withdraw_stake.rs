@@ -0,0 +1,48 @@
+use crate::errors::*;
+use crate::state::*;
+use crate::utils::debug_msg;
+use anchor_lang::prelude::*;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct WithdrawStake<'info> {
+    /// Closed to the relayer with the remaining stake and rent
+    #[account(
+        mut,
+        close = relayer,
+        seeds = [b"relayer_stake", relayer.key().as_ref()],
+        bump,
+    )]
+    pub relayer_stake: Account<'info, RelayerStake>,
+
+    #[account(mut)]
+    pub relayer: Signer<'info>,
+
+    pub clock: Sysvar<'info, Clock>,
+}
+
+pub fn withdraw_stake(ctx: Context<WithdrawStake>) -> Result<()> {
+    let relayer_stake = &ctx.accounts.relayer_stake;
+    require!(
+        relayer_stake.unstake_requested_at != 0
+            && ctx.accounts.clock.unix_timestamp
+                >= relayer_stake
+                    .unstake_requested_at
+                    .saturating_add(RELAYER_UNSTAKE_DELAY),
+        ContinuumError::UnstakeDelayNotElapsed
+    );
+
+    emit_cpi!(RelayerStakeWithdrawn {
+        version: EVENT_VERSION,
+        relayer: relayer_stake.relayer,
+        amount: relayer_stake.amount,
+    });
+
+    debug_msg!(
+        "Relayer {} withdrew {}",
+        relayer_stake.relayer,
+        relayer_stake.amount
+    );
+
+    Ok(())
+}
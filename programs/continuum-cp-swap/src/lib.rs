@@ -2,11 +2,14 @@ use anchor_lang::prelude::*;
 
 declare_id!("A548C9LR926hnAWvYDjsXJddidhfzLf3bRb8dmYPgRKn");
 
+pub mod cp_swap;
 pub mod errors;
 pub mod instructions;
 pub mod state;
+pub mod utils;
 
 use instructions::*;
+use state::{ConfigChange, FillPolicy, OrderIntent, QueueStatus, SwapQuote};
 
 #[program]
 pub mod continuum_cp_swap {
@@ -27,19 +30,30 @@ pub mod continuum_cp_swap {
         instructions::initialize_cp_swap_pool(ctx, init_amount_0, init_amount_1, open_time)
     }
 
+    /// Register an existing CP-Swap pool whose custom authority is already Continuum's
+    pub fn register_existing_pool(ctx: Context<RegisterExistingPool>) -> Result<()> {
+        instructions::register_existing_pool(ctx)
+    }
+
     /// Submit a swap order to the FIFO queue
     pub fn submit_order(
         ctx: Context<SubmitOrder>,
         amount_in: u64,
         min_amount_out: u64,
         is_base_input: bool,
+        fill_policy: FillPolicy,
     ) -> Result<()> {
-        instructions::submit_order(ctx, amount_in, min_amount_out, is_base_input)
+        instructions::submit_order(ctx, amount_in, min_amount_out, is_base_input, fill_policy)
     }
 
-    /// Execute the next order in the FIFO queue
-    pub fn execute_order(ctx: Context<ExecuteOrder>, expected_sequence: u64) -> Result<()> {
-        instructions::execute_order(ctx, expected_sequence)
+    /// Execute the next order in the FIFO queue, or `fill_amount` of it when
+    /// its fill policy allows partial fills
+    pub fn execute_order<'info>(
+        ctx: Context<'_, '_, '_, 'info, ExecuteOrder<'info>>,
+        expected_sequence: u64,
+        fill_amount: Option<u64>,
+    ) -> Result<()> {
+        instructions::execute_order(ctx, expected_sequence, fill_amount)
     }
 
     /// Cancel an order (only by original submitter)
@@ -47,6 +61,16 @@ pub mod continuum_cp_swap {
         instructions::cancel_order(ctx)
     }
 
+    /// Amend a pending order, keeping its queue position only when its size
+    /// does not grow and its price limit per unit does not loosen
+    pub fn amend_order(
+        ctx: Context<AmendOrder>,
+        new_amount_in: u64,
+        new_min_amount_out: u64,
+    ) -> Result<()> {
+        instructions::amend_order(ctx, new_amount_in, new_min_amount_out)
+    }
+
     /// Simplified submit order without PDA
     pub fn submit_order_simple(
         ctx: Context<SubmitOrderSimple>,
@@ -70,14 +94,210 @@ pub mod continuum_cp_swap {
     /// Deposit liquidity into a CP-Swap pool using Continuum authority
     pub fn deposit_liquidity<'info>(
         ctx: Context<'_, '_, '_, 'info, DepositLiquidity<'info>>,
+        lp_token_amount: u64,
+        maximum_token_0_amount: u64,
+        maximum_token_1_amount: u64,
     ) -> Result<()> {
-        instructions::deposit_liquidity(ctx)
+        instructions::deposit_liquidity(
+            ctx,
+            lp_token_amount,
+            maximum_token_0_amount,
+            maximum_token_1_amount,
+        )
     }
 
     /// Withdraw liquidity from a CP-Swap pool using Continuum authority
     pub fn withdraw_liquidity<'info>(
         ctx: Context<'_, '_, '_, 'info, WithdrawLiquidity<'info>>,
+        lp_token_amount: u64,
+        minimum_token_0_amount: u64,
+        minimum_token_1_amount: u64,
+    ) -> Result<()> {
+        instructions::withdraw_liquidity(
+            ctx,
+            lp_token_amount,
+            minimum_token_0_amount,
+            minimum_token_1_amount,
+        )
+    }
+
+    /// Submit an exact-input order routed through up to three registered pools.
+    /// It queues in the first pool only and trades through the others when it
+    /// is executed, without a place in their queues.
+    pub fn submit_multi_hop_order<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SubmitMultiHopOrder<'info>>,
+        route: Vec<Pubkey>,
+        amount_in: u64,
+        min_amount_out: u64,
+    ) -> Result<()> {
+        instructions::submit_multi_hop_order(ctx, route, amount_in, min_amount_out)
+    }
+
+    /// Execute a multi-hop order atomically via chained CP-Swap swaps
+    pub fn execute_multi_hop_order<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ExecuteMultiHopOrder<'info>>,
+        expected_sequence: u64,
+        accounts_per_hop: u8,
+    ) -> Result<()> {
+        instructions::execute_multi_hop_order(ctx, expected_sequence, accounts_per_hop)
+    }
+
+    /// Submit an order on a user's behalf from an ed25519-signed intent
+    pub fn submit_order_with_intent(
+        ctx: Context<SubmitOrderWithIntent>,
+        intent: OrderIntent,
+    ) -> Result<()> {
+        instructions::submit_order_with_intent(ctx, intent)
+    }
+
+    /// Expire a pending order whose pool TTL has elapsed, or a committed order
+    /// left unrevealed past its reveal window (permissionless)
+    pub fn expire_order(ctx: Context<ExpireOrder>) -> Result<()> {
+        instructions::expire_order(ctx)
+    }
+
+    /// Settle the head order of a pool as failed when it can no longer be
+    /// executed, so relayers can move past it
+    pub fn fail_order(ctx: Context<FailOrder>) -> Result<()> {
+        instructions::fail_order(ctx)
+    }
+
+    /// Take a queue position for an order hidden behind a hash commitment
+    pub fn commit_order(ctx: Context<CommitOrder>, commitment: [u8; 32]) -> Result<()> {
+        instructions::commit_order(ctx, commitment)
+    }
+
+    /// Reveal a committed order's parameters within the pool's reveal window
+    pub fn reveal_order(
+        ctx: Context<RevealOrder>,
+        amount_in: u64,
+        min_amount_out: u64,
+        is_base_input: bool,
+        salt: [u8; 32],
     ) -> Result<()> {
-        instructions::withdraw_liquidity(ctx)
+        instructions::reveal_order(ctx, amount_in, min_amount_out, is_base_input, salt)
+    }
+
+    /// Stake lamports to become an eligible executor of pools requiring a stake
+    pub fn stake_relayer(ctx: Context<StakeRelayer>, amount: u64) -> Result<()> {
+        instructions::stake_relayer(ctx, amount)
+    }
+
+    /// Stop executing orders and start the unstake delay
+    pub fn request_unstake(ctx: Context<RequestUnstake>) -> Result<()> {
+        instructions::request_unstake(ctx)
+    }
+
+    /// Withdraw a relayer stake once the unstake delay has passed
+    pub fn withdraw_stake(ctx: Context<WithdrawStake>) -> Result<()> {
+        instructions::withdraw_stake(ctx)
+    }
+
+    /// Slash a relayer that executed an order ahead of an older eligible one (permissionless)
+    pub fn report_misbehavior(ctx: Context<ReportMisbehavior>) -> Result<()> {
+        instructions::report_misbehavior(ctx)
+    }
+
+    /// Create the optional fixed-capacity order ring of a registered pool. The
+    /// pool must have an order TTL, which cannot be cleared afterwards, since
+    /// an unexecutable head only leaves the ring by expiring.
+    pub fn initialize_order_ring(ctx: Context<InitializeOrderRing>) -> Result<()> {
+        instructions::initialize_order_ring(ctx)
+    }
+
+    /// Queue an order as a compact record in the pool's order ring
+    pub fn submit_ring_order(
+        ctx: Context<SubmitRingOrder>,
+        amount_in: u64,
+        min_amount_out: u64,
+        is_base_input: bool,
+    ) -> Result<()> {
+        instructions::submit_ring_order(ctx, amount_in, min_amount_out, is_base_input)
+    }
+
+    /// Execute the order at the head of a pool's order ring
+    pub fn execute_ring_order<'info>(
+        ctx: Context<'_, '_, '_, 'info, ExecuteRingOrder<'info>>,
+        expected_sequence: u64,
+    ) -> Result<()> {
+        instructions::execute_ring_order(ctx, expected_sequence)
+    }
+
+    /// Cancel one of the user's orders in a pool's order ring
+    pub fn cancel_ring_order(ctx: Context<CancelRingOrder>, sequence: u64) -> Result<()> {
+        instructions::cancel_ring_order(ctx, sequence)
+    }
+
+    /// Drop a record of a pool's order ring once the pool's order TTL has passed
+    pub fn expire_ring_order(ctx: Context<ExpireRingOrder>, sequence: u64) -> Result<()> {
+        instructions::expire_ring_order(ctx, sequence)
+    }
+
+    /// Read a pool's queue head, tail, depth and oldest pending order, through return data
+    pub fn get_queue_status(ctx: Context<GetQueueStatus>) -> Result<QueueStatus> {
+        instructions::get_queue_status(ctx)
+    }
+
+    /// Quote a swap against a registered pool's current reserves, through return data
+    pub fn quote_swap(
+        ctx: Context<QuoteSwap>,
+        amount: u64,
+        zero_for_one: bool,
+        is_base_input: bool,
+    ) -> Result<SwapQuote> {
+        instructions::quote_swap(ctx, amount, zero_for_one, is_base_input)
+    }
+
+    /// Create the role config, giving every role to the current FIFO admin
+    pub fn initialize_admin_config(ctx: Context<InitializeAdminConfig>) -> Result<()> {
+        instructions::initialize_admin_config(ctx)
+    }
+
+    /// Accept a super admin transfer proposed through a timelocked
+    /// `ConfigChange::ProposeSuperAdmin` (proposed super admin only)
+    pub fn accept_super_admin(ctx: Context<AcceptSuperAdmin>) -> Result<()> {
+        instructions::accept_super_admin(ctx)
+    }
+
+    /// Set or clear the emergency pause (pauser only). The only admin action
+    /// exempt from the timelock and so the only emergency lever: it stops new
+    /// orders, swaps and liquidity changes in every pool at once, while pending
+    /// orders can still settle. Winding down a single pool is a `SetPoolStatus`
+    /// change and waits out the timelock.
+    pub fn set_emergency_pause(ctx: Context<SetEmergencyPause>, paused: bool) -> Result<()> {
+        instructions::set_emergency_pause(ctx, paused)
+    }
+
+    /// Queue a pool, role, timelock or super admin change behind the timelock
+    /// (role holder only)
+    pub fn queue_config_change(ctx: Context<QueueConfigChange>, change: ConfigChange) -> Result<()> {
+        instructions::queue_config_change(ctx, change)
+    }
+
+    /// Apply a queued config change once its timelock has elapsed, if its
+    /// proposer still holds the role it requires (permissionless)
+    pub fn execute_config_change(ctx: Context<ExecuteConfigChange>) -> Result<()> {
+        instructions::execute_config_change(ctx)
+    }
+
+    /// Drop a queued config change before it is executed (role holder only)
+    pub fn cancel_config_change(ctx: Context<CancelConfigChange>) -> Result<()> {
+        instructions::cancel_config_change(ctx)
+    }
+
+    /// Upgrade a pre-versioning FIFO state account in place (super admin only)
+    pub fn migrate_fifo_state(ctx: Context<MigrateFifoState>) -> Result<()> {
+        instructions::migrate_fifo_state(ctx)
+    }
+
+    /// Upgrade a pre-versioning pool registry in place (super admin only)
+    pub fn migrate_pool_registry(ctx: Context<MigratePoolRegistry>) -> Result<()> {
+        instructions::migrate_pool_registry(ctx)
+    }
+
+    /// Upgrade an older order account in place, creating the pending counters
+    /// its settlement releases when they are missing (super admin only)
+    pub fn migrate_order(ctx: Context<MigrateOrder>) -> Result<()> {
+        instructions::migrate_order(ctx)
     }
 }
@@ -1,3 +1,5 @@
+use crate::cp_swap::PoolStateView;
+use crate::errors::ContinuumError;
 use anchor_lang::prelude::*;
 
 #[account]
@@ -5,10 +7,108 @@ pub struct FifoState {
     pub current_sequence: u64,
     pub admin: Pubkey,
     pub emergency_pause: bool,
+    /// Layout version, new fields are carved out of `reserved` after it
+    pub version: StateVersion,
+    /// Relayer stakes opened so far, the latest stake id
+    pub relayer_count: u64,
+    pub reserved: [u8; 56],
 }
 
 impl FifoState {
-    pub const LEN: usize = 8 + 8 + 32 + 1;
+    pub const LEN: usize = 8 + 8 + 32 + 1 + 1 + 8 + 56;
+}
+
+/// Layout version written by this program, bumped whenever a migration is added
+pub const STATE_VERSION: u8 = 3;
+
+/// Layout version stored in every versioned account, 0 while it is being
+/// initialized. Only versions up to `STATE_VERSION` deserialize, so an account
+/// written by a newer program is refused rather than misread. Older versions
+/// of the same size load as they are: fields carved out of `reserved` since
+/// then read as zero, which must be their default. Older layouts of another
+/// size fail on their length until migrated.
+#[derive(AnchorSerialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct StateVersion(u8);
+
+impl StateVersion {
+    pub const CURRENT: Self = Self(STATE_VERSION);
+
+    pub fn get(self) -> u8 {
+        self.0
+    }
+}
+
+impl AnchorDeserialize for StateVersion {
+    fn deserialize_reader<R: std::io::Read>(reader: &mut R) -> std::io::Result<Self> {
+        let version = u8::deserialize_reader(reader)?;
+        if version > STATE_VERSION {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "unsupported account version",
+            ));
+        }
+        Ok(Self(version))
+    }
+}
+
+#[account]
+pub struct AdminConfig {
+    /// Holds every role and is the only one who can assign them
+    pub super_admin: Pubkey,
+    /// Proposed super admin, default when no transfer is pending
+    pub pending_super_admin: Pubkey,
+    pub pauser: Pubkey,
+    pub pool_manager: Pubkey,
+    pub fee_manager: Pubkey,
+    pub relayer_manager: Pubkey,
+    /// Seconds a queued config change waits before it can be executed
+    pub timelock_delay: i64,
+    /// Number of config changes queued so far, seeds the next pending change
+    pub change_count: u64,
+}
+
+/// Default delay on config changes, one day
+pub const DEFAULT_TIMELOCK_DELAY: i64 = 24 * 60 * 60;
+
+/// Upper bound on the config change delay, so a bad value cannot lock config forever
+pub const MAX_TIMELOCK_DELAY: i64 = 30 * 24 * 60 * 60;
+
+impl AdminConfig {
+    pub const LEN: usize = 8 + 32 * 6 + 8 + 8;
+
+    pub fn has_role(&self, role: AdminRole, key: &Pubkey) -> bool {
+        if self.super_admin == *key {
+            return true;
+        }
+        let holder = match role {
+            AdminRole::Pauser => self.pauser,
+            AdminRole::PoolManager => self.pool_manager,
+            AdminRole::FeeManager => self.fee_manager,
+            AdminRole::RelayerManager => self.relayer_manager,
+        };
+        holder == *key
+    }
+
+    pub fn role_holder_mut(&mut self, role: AdminRole) -> &mut Pubkey {
+        match role {
+            AdminRole::Pauser => &mut self.pauser,
+            AdminRole::PoolManager => &mut self.pool_manager,
+            AdminRole::FeeManager => &mut self.fee_manager,
+            AdminRole::RelayerManager => &mut self.relayer_manager,
+        }
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum AdminRole {
+    /// Can set and clear the emergency pause
+    Pauser,
+    /// Can register pools, change their status and order limits
+    PoolManager,
+    /// Can change bond and fee parameters
+    FeeManager,
+    /// Can change relayer parameters
+    RelayerManager,
 }
 
 #[account]
@@ -19,10 +119,481 @@ pub struct CpSwapPoolRegistry {
     pub continuum_authority: Pubkey,
     pub created_at: i64,
     pub is_active: bool,
+    /// A draining pool rejects new orders but still settles pending ones
+    pub is_draining: bool,
+    /// Maximum concurrent pending orders per user in this pool, 0 for no cap
+    pub max_pending_per_user: u16,
+    /// Seconds after submission a pending order can be expired, 0 for never
+    pub order_ttl: i64,
+    /// Lamports bonded by each submitted order
+    pub order_bond: u64,
+    /// Seconds an order must rest before it can be cancelled without slashing
+    pub min_resting_period: i64,
+    /// Share of the bond forfeited on early cancellation, in basis points
+    pub bond_slash_bps: u16,
+    /// Treasury or relayer wallet receiving forfeited bonds
+    pub bond_recipient: Pubkey,
+    pub token_0_vault: Pubkey,
+    pub token_1_vault: Pubkey,
+    pub version: StateVersion,
+    /// Pending orders of this pool, oldest first
+    pub queue: QueueStatus,
+    /// Slots that must pass after submission before an order can be executed
+    pub min_inclusion_delay: u64,
+    /// Seconds a committed order has to be revealed, 0 if commitments are refused
+    pub reveal_window: i64,
+    /// Lamports a relayer must stake to execute this pool's orders, 0 for anyone
+    pub min_relayer_stake: u64,
+    /// Share of a relayer's stake slashed per reported misbehavior, in basis points
+    pub relayer_slash_bps: u16,
+    /// When the current head order reached the head of the queue
+    pub head_since: i64,
+    /// Seconds an order must be at the head before its own user can execute it
+    /// without a relayer stake, 0 to never allow it
+    pub self_execution_timeout: i64,
+    /// Set once the pool has an order ring, whose orders only leave the head by
+    /// expiring when they cannot be executed, so the TTL must stay set
+    pub has_order_ring: bool,
+    pub reserved: [u8; 53],
 }
 
 impl CpSwapPoolRegistry {
-    pub const LEN: usize = 8 + 32 + 32 + 32 + 32 + 8 + 1;
+    pub const LEN: usize = 8
+        + 32
+        + 32
+        + 32
+        + 32
+        + 8
+        + 1
+        + 1
+        + 2
+        + 8
+        + 8
+        + 8
+        + 2
+        + 32
+        + 32
+        + 32
+        + 1
+        + 32
+        + 8
+        + 8
+        + 8
+        + 2
+        + 8
+        + 8
+        + 1
+        + 53;
+
+    /// Fills a new registry entry for a pool verified to use `continuum_authority`
+    pub fn register(
+        &mut self,
+        pool_id: Pubkey,
+        continuum_authority: Pubkey,
+        pool: &PoolStateView,
+        now: i64,
+    ) {
+        self.pool_id = pool_id;
+        self.token_0 = pool.token_0_mint;
+        self.token_1 = pool.token_1_mint;
+        self.token_0_vault = pool.token_0_vault;
+        self.token_1_vault = pool.token_1_vault;
+        self.continuum_authority = continuum_authority;
+        self.created_at = now;
+        self.set_status(PoolStatus::Active);
+        self.max_pending_per_user = 0;
+        self.order_ttl = 0;
+        self.order_bond = 0;
+        self.min_resting_period = 0;
+        self.bond_slash_bps = 0;
+        self.bond_recipient = Pubkey::default();
+        self.version = StateVersion::CURRENT;
+        self.queue = QueueStatus::default();
+        self.min_inclusion_delay = 0;
+        self.reveal_window = 0;
+        self.min_relayer_stake = 0;
+        self.relayer_slash_bps = 0;
+        self.head_since = 0;
+        self.self_execution_timeout = 0;
+    }
+
+    /// Whether new orders, immediate swaps and deposits are allowed
+    pub fn accepts_orders(&self) -> bool {
+        self.is_active && !self.is_draining
+    }
+
+    pub fn status(&self) -> PoolStatus {
+        match (self.is_active, self.is_draining) {
+            (false, _) => PoolStatus::Inactive,
+            (true, false) => PoolStatus::Active,
+            (true, true) => PoolStatus::Draining,
+        }
+    }
+
+    pub fn set_status(&mut self, status: PoolStatus) {
+        self.is_active = status != PoolStatus::Inactive;
+        self.is_draining = status == PoolStatus::Draining;
+    }
+
+    pub fn check_pending_cap(&self, user_pool_state: &UserPoolState) -> Result<()> {
+        require!(
+            self.max_pending_per_user == 0
+                || user_pool_state.pending_count < self.max_pending_per_user as u32,
+            ContinuumError::TooManyPendingOrders
+        );
+        Ok(())
+    }
+
+    /// Part of `bond` forfeited when an order submitted at `submitted_at` is cancelled at `now`
+    pub fn bond_slash(&self, bond: u64, submitted_at: i64, now: i64) -> u64 {
+        if now >= submitted_at.saturating_add(self.min_resting_period) {
+            return 0;
+        }
+        (bond as u128 * self.bond_slash_bps as u128 / 10_000) as u64
+    }
+
+    /// Stake id to record on an order executed by the holder of `stake`, 0 for
+    /// none. Once the pool requires a stake, only relayers with at least
+    /// `min_relayer_stake` that are not unstaking can execute.
+    pub fn check_executor(&self, stake: Option<&RelayerStake>) -> Result<u64> {
+        if self.min_relayer_stake == 0 {
+            return Ok(stake.map_or(0, |stake| stake.id));
+        }
+        let stake = stake
+            .filter(|stake| stake.is_eligible(self.min_relayer_stake))
+            .ok_or(ContinuumError::RelayerNotEligible)?;
+        Ok(stake.id)
+    }
+
+    /// Whether `order` has been at the head of the queue for the self-execution
+    /// timeout, so its user no longer has to wait for an eligible relayer
+    pub fn allows_self_execution(&self, order: &OrderState, now: i64) -> bool {
+        self.self_execution_timeout > 0
+            && self.queue.head_sequence == order.sequence
+            && now >= self.head_since.saturating_add(self.self_execution_timeout)
+    }
+
+    /// Whether `order` is linked into the queue. Orders submitted before queue
+    /// tracking are not, and settle without touching it.
+    pub fn is_queued(&self, order: &OrderState) -> bool {
+        order.queue_prev != 0 || self.queue.head_sequence == order.sequence
+    }
+
+    /// Links a new `order` at the tail of the queue. `tail` must be the current
+    /// tail order unless the queue is empty.
+    pub fn enqueue(&mut self, order: &mut OrderState, tail: Option<&mut OrderState>) -> Result<()> {
+        if self.queue.tail_sequence == 0 {
+            self.queue.head_sequence = order.sequence;
+            self.queue.oldest_pending_at = order.submitted_at;
+            self.head_since = order.submitted_at;
+            order.queue_prev = 0;
+        } else {
+            let tail = queue_neighbor(tail, self.queue.tail_sequence)?;
+            tail.queue_next = order.sequence;
+            order.queue_prev = tail.sequence;
+        }
+        order.queue_next = 0;
+        self.queue.tail_sequence = order.sequence;
+        self.queue.pending_count += 1;
+        Ok(())
+    }
+
+    /// Unlinks `order` once it leaves the pending state at `now`. `prev` and
+    /// `next` must be the orders it links to, when it has them.
+    pub fn dequeue(
+        &mut self,
+        order: &mut OrderState,
+        prev: Option<&mut OrderState>,
+        next: Option<&mut OrderState>,
+        now: i64,
+    ) -> Result<()> {
+        if !self.is_queued(order) {
+            return Ok(());
+        }
+
+        if order.queue_prev == 0 {
+            self.queue.head_sequence = order.queue_next;
+            self.queue.oldest_pending_at = 0;
+            self.head_since = now;
+        } else {
+            queue_neighbor(prev, order.queue_prev)?.queue_next = order.queue_next;
+        }
+
+        if order.queue_next == 0 {
+            self.queue.tail_sequence = order.queue_prev;
+        } else {
+            let next = queue_neighbor(next, order.queue_next)?;
+            next.queue_prev = order.queue_prev;
+            if order.queue_prev == 0 {
+                self.queue.oldest_pending_at = next.submitted_at;
+            }
+        }
+
+        self.queue.pending_count = self.queue.pending_count.saturating_sub(1);
+        order.queue_prev = 0;
+        order.queue_next = 0;
+        Ok(())
+    }
+
+    pub fn apply_config(&mut self, update: &PoolConfigUpdate) -> Result<()> {
+        update.validate()?;
+
+        if let Some(max_pending_per_user) = update.max_pending_per_user {
+            self.max_pending_per_user = max_pending_per_user;
+        }
+        if let Some(order_ttl) = update.order_ttl {
+            self.order_ttl = order_ttl;
+        }
+        if let Some(order_bond) = update.order_bond {
+            self.order_bond = order_bond;
+        }
+        if let Some(min_resting_period) = update.min_resting_period {
+            self.min_resting_period = min_resting_period;
+        }
+        if let Some(bond_slash_bps) = update.bond_slash_bps {
+            self.bond_slash_bps = bond_slash_bps;
+        }
+        if let Some(bond_recipient) = update.bond_recipient {
+            self.bond_recipient = bond_recipient;
+        }
+        if let Some(min_inclusion_delay) = update.min_inclusion_delay {
+            self.min_inclusion_delay = min_inclusion_delay;
+        }
+        if let Some(reveal_window) = update.reveal_window {
+            self.reveal_window = reveal_window;
+        }
+        if let Some(min_relayer_stake) = update.min_relayer_stake {
+            self.min_relayer_stake = min_relayer_stake;
+        }
+        if let Some(relayer_slash_bps) = update.relayer_slash_bps {
+            self.relayer_slash_bps = relayer_slash_bps;
+        }
+        if let Some(self_execution_timeout) = update.self_execution_timeout {
+            self.self_execution_timeout = self_execution_timeout;
+        }
+
+        // Forfeited bonds need somewhere to go
+        require!(
+            self.bond_slash_bps == 0 || self.bond_recipient != Pubkey::default(),
+            ContinuumError::InvalidPoolConfig
+        );
+        require!(
+            !self.has_order_ring || self.order_ttl > 0,
+            ContinuumError::InvalidPoolConfig
+        );
+
+        Ok(())
+    }
+}
+
+/// The queue neighbor an instruction was given, checked against `sequence`
+fn queue_neighbor(order: Option<&mut OrderState>, sequence: u64) -> Result<&mut OrderState> {
+    order
+        .filter(|order| order.sequence == sequence)
+        .ok_or_else(|| error!(ContinuumError::InvalidQueueNeighbor))
+}
+
+/// Per-pool queue metadata, sequences are 0 while the queue is empty
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub struct QueueStatus {
+    /// Oldest pending order, next in line for execution
+    pub head_sequence: u64,
+    /// Newest pending order
+    pub tail_sequence: u64,
+    pub pending_count: u64,
+    /// When the head order was submitted
+    pub oldest_pending_at: i64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PoolStatus {
+    /// Nothing but cancellations and expiries is allowed
+    Inactive,
+    Active,
+    /// New submissions and amendments are rejected, pending orders can still
+    /// execute or be cancelled
+    Draining,
+}
+
+/// Admin-supplied pool settings, `None` leaves the current value unchanged
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct PoolConfigUpdate {
+    pub max_pending_per_user: Option<u16>,
+    pub order_ttl: Option<i64>,
+    pub order_bond: Option<u64>,
+    pub min_resting_period: Option<i64>,
+    pub bond_slash_bps: Option<u16>,
+    pub bond_recipient: Option<Pubkey>,
+    pub min_inclusion_delay: Option<u64>,
+    pub reveal_window: Option<i64>,
+    pub min_relayer_stake: Option<u64>,
+    pub relayer_slash_bps: Option<u16>,
+    pub self_execution_timeout: Option<i64>,
+}
+
+impl PoolConfigUpdate {
+    /// Order limits are managed by the pool manager
+    pub fn touches_limits(&self) -> bool {
+        self.max_pending_per_user.is_some()
+            || self.order_ttl.is_some()
+            || self.min_inclusion_delay.is_some()
+            || self.reveal_window.is_some()
+    }
+
+    /// Bond economics are managed by the fee manager
+    pub fn touches_fees(&self) -> bool {
+        self.order_bond.is_some()
+            || self.min_resting_period.is_some()
+            || self.bond_slash_bps.is_some()
+            || self.bond_recipient.is_some()
+    }
+
+    /// Relayer stake and slashing are managed by the relayer manager
+    pub fn touches_relayers(&self) -> bool {
+        self.min_relayer_stake.is_some()
+            || self.relayer_slash_bps.is_some()
+            || self.self_execution_timeout.is_some()
+    }
+
+    /// Range checks that do not depend on the current pool settings
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            self.order_ttl.unwrap_or(0) >= 0
+                && self.min_resting_period.unwrap_or(0) >= 0
+                && self.reveal_window.unwrap_or(0) >= 0
+                && self.self_execution_timeout.unwrap_or(0) >= 0
+                && self.bond_slash_bps.unwrap_or(0) <= 10_000
+                && self.relayer_slash_bps.unwrap_or(0) <= 10_000,
+            ContinuumError::InvalidPoolConfig
+        );
+        Ok(())
+    }
+}
+
+/// A sensitive admin action, queued behind the timelock before it takes effect
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub enum ConfigChange {
+    UpdatePoolConfig {
+        pool_id: Pubkey,
+        update: PoolConfigUpdate,
+    },
+    SetPoolStatus {
+        pool_id: Pubkey,
+        status: PoolStatus,
+    },
+    SetRole {
+        role: AdminRole,
+        new_holder: Pubkey,
+    },
+    SetTimelockDelay {
+        delay: i64,
+    },
+    /// First step of a super admin transfer, `Pubkey::default()` withdraws a
+    /// pending proposal. The proposed key accepts with `accept_super_admin`.
+    ProposeSuperAdmin {
+        proposed: Pubkey,
+    },
+}
+
+impl ConfigChange {
+    /// Largest serialized variant: tag, pool id and a fully set `PoolConfigUpdate`
+    pub const MAX_LEN: usize = 1 + 32 + (3 + 9 + 9 + 9 + 3 + 33 + 9 + 9 + 9 + 3 + 9);
+
+    /// Whether `key` may queue or cancel this change
+    pub fn is_authorized(&self, admin_config: &AdminConfig, key: &Pubkey) -> bool {
+        match self {
+            ConfigChange::UpdatePoolConfig { update, .. } => {
+                (!update.touches_limits() || admin_config.has_role(AdminRole::PoolManager, key))
+                    && (!update.touches_fees() || admin_config.has_role(AdminRole::FeeManager, key))
+                    && (!update.touches_relayers()
+                        || admin_config.has_role(AdminRole::RelayerManager, key))
+            }
+            ConfigChange::SetPoolStatus { .. } => {
+                admin_config.has_role(AdminRole::PoolManager, key)
+            }
+            ConfigChange::SetRole { .. }
+            | ConfigChange::SetTimelockDelay { .. }
+            | ConfigChange::ProposeSuperAdmin { .. } => admin_config.super_admin == *key,
+        }
+    }
+
+    /// Pool whose registry must be passed when the change is executed
+    pub fn pool_id(&self) -> Option<Pubkey> {
+        match self {
+            ConfigChange::UpdatePoolConfig { pool_id, .. }
+            | ConfigChange::SetPoolStatus { pool_id, .. } => Some(*pool_id),
+            ConfigChange::SetRole { .. }
+            | ConfigChange::SetTimelockDelay { .. }
+            | ConfigChange::ProposeSuperAdmin { .. } => None,
+        }
+    }
+
+    /// Checks that can be done when the change is queued
+    pub fn validate(&self) -> Result<()> {
+        match self {
+            ConfigChange::UpdatePoolConfig { update, .. } => update.validate(),
+            ConfigChange::SetTimelockDelay { delay } => {
+                require!(
+                    (0..=MAX_TIMELOCK_DELAY).contains(delay),
+                    ContinuumError::InvalidConfigChange
+                );
+                Ok(())
+            }
+            ConfigChange::SetPoolStatus { .. }
+            | ConfigChange::SetRole { .. }
+            | ConfigChange::ProposeSuperAdmin { .. } => Ok(()),
+        }
+    }
+}
+
+/// A queued config change, PDA `[b"pending_change", id]`
+#[account]
+pub struct PendingChange {
+    pub id: u64,
+    pub proposer: Pubkey,
+    /// Paid the account rent, refunded when the change is executed or cancelled
+    pub payer: Pubkey,
+    pub change: ConfigChange,
+    pub queued_at: i64,
+    pub effective_after: i64,
+}
+
+impl PendingChange {
+    pub const LEN: usize = 8 + 8 + 32 + 32 + ConfigChange::MAX_LEN + 8 + 8;
+}
+
+/// Maximum number of pools a multi-hop order can route through
+pub const MAX_ROUTE_LEN: usize = 3;
+
+/// Mints a route through pools with the given token pairs trades, from the
+/// input of its first pool to the output of its last. The first two pools must
+/// share exactly one mint, which makes the other one of the first pool the
+/// input, and every later pool must trade the output of the one before it.
+pub fn route_mints(pairs: &[(Pubkey, Pubkey)]) -> Result<Vec<Pubkey>> {
+    require!(pairs.len() >= 2, ContinuumError::InvalidRoute);
+    let trades = |mint: Pubkey, (token_0, token_1): (Pubkey, Pubkey)| {
+        mint == token_0 || mint == token_1
+    };
+    let (first_0, first_1) = pairs[0];
+    let input = match (trades(first_0, pairs[1]), trades(first_1, pairs[1])) {
+        (false, true) => first_0,
+        (true, false) => first_1,
+        _ => return err!(ContinuumError::InvalidRoute),
+    };
+
+    let mut mints = vec![input];
+    for &(token_0, token_1) in pairs {
+        let hop_input = mints[mints.len() - 1];
+        let hop_output = if hop_input == token_0 {
+            token_1
+        } else if hop_input == token_1 {
+            token_0
+        } else {
+            return err!(ContinuumError::InvalidRoute);
+        };
+        mints.push(hop_output);
+    }
+    Ok(mints)
 }
 
 #[account]
@@ -35,46 +606,1096 @@ pub struct OrderState {
     pub is_base_input: bool,
     pub status: OrderStatus,
     pub submitted_at: i64,
+    /// When the order settled, or was last partially filled while pending
     pub executed_at: Option<i64>,
+    /// Number of pools in the route (1 for single-pool orders)
+    pub route_len: u8,
+    /// Pools after `pool_id` for multi-hop orders, unused slots are default
+    pub route_pools: [Pubkey; MAX_ROUTE_LEN - 1],
+    /// Anti-griefing bond held in this account on top of its rent
+    pub bond_lamports: u64,
+    pub version: StateVersion,
+    /// Sequences of the previous and next pending orders of the same pool,
+    /// 0 at either end of the queue
+    pub queue_prev: u64,
+    pub queue_next: u64,
+    /// Slot the order, or its latest amendment, was submitted in
+    pub submitted_slot: u64,
+    /// Id of the relayer stake behind the latest fill, 0 if the executor had
+    /// none or the fill was already reported
+    pub executed_by: u64,
+    /// Tokens taken from and delivered to the user by the fills so far
+    pub filled_amount_in: u64,
+    pub filled_amount_out: u64,
+    pub fill_policy: FillPolicy,
+    /// Token account the order is funded from, the only one it executes from
+    /// and the one it is failed on. Default for orders submitted without one,
+    /// which are judged on the user's associated token accounts.
+    pub source: Pubkey,
+    /// Account that posted the bond and gets it back, default when the user did
+    pub bond_payer: Pubkey,
+    pub reserved: [u8; 64],
 }
 
 impl OrderState {
-    pub const LEN: usize = 8 + 8 + 32 + 32 + 8 + 8 + 1 + 1 + 8 + 9;
+    pub const LEN: usize = 8
+        + 8
+        + 32
+        + 32
+        + 8
+        + 8
+        + 1
+        + 1
+        + 8
+        + 9
+        + 1
+        + 32 * (MAX_ROUTE_LEN - 1)
+        + 8
+        + 1
+        + 8
+        + 8
+        + 8
+        + 8
+        + 8
+        + 8
+        + 1
+        + 32
+        + 32
+        + 64;
+
+    pub fn is_multi_hop(&self) -> bool {
+        self.route_len > 1
+    }
+
+    /// FIFO sequence the order account is seeded with, read before submission incremented it
+    pub fn seed_sequence(&self) -> u64 {
+        self.sequence.saturating_sub(1)
+    }
+
+    /// Whether `min_inclusion_delay` slots have passed since submission, so the
+    /// order was public before it could be executed
+    pub fn inclusion_delay_elapsed(&self, min_inclusion_delay: u64, slot: u64) -> bool {
+        slot >= self.submitted_slot.saturating_add(min_inclusion_delay)
+    }
+
+    /// Part of the order filled so far, in the unit of `amount_in`: the input
+    /// of exact-input orders and the output of exact-output orders
+    pub fn filled_size(&self) -> u64 {
+        if self.is_base_input {
+            self.filled_amount_in
+        } else {
+            self.filled_amount_out
+        }
+    }
+
+    pub fn remaining_size(&self) -> u64 {
+        self.amount_in.saturating_sub(self.filled_size())
+    }
+
+    /// Swap limit for filling `fill` more of the order, `min_amount_out` scaled
+    /// to the order filled so far so every fill keeps the order's price. A fill
+    /// of the whole order gets `min_amount_out` itself.
+    pub fn fill_limit(&self, fill: u64) -> Result<u64> {
+        let remaining = self.remaining_size();
+        require!(fill > 0 && fill <= remaining, ContinuumError::InvalidAmount);
+        require!(
+            fill == remaining || self.fill_policy != FillPolicy::FillOrKill,
+            ContinuumError::PartialFillNotAllowed
+        );
+
+        let filled_after = (self.filled_size() + fill) as u128;
+        let limit = if self.is_base_input {
+            // Output the fills must reach together, less what they delivered
+            (self.min_amount_out as u128 * filled_after)
+                .div_ceil(self.amount_in as u128)
+                .saturating_sub(self.filled_amount_out as u128)
+        } else {
+            // Input the fills may spend together, less what they spent
+            (self.min_amount_out as u128 * filled_after / self.amount_in as u128)
+                .checked_sub(self.filled_amount_in as u128)
+                .ok_or(ContinuumError::SlippageExceeded)?
+        };
+        u64::try_from(limit).map_err(|_| error!(ContinuumError::MathOverflow))
+    }
+
+    /// Adds a fill that took `amount_in` from the user and delivered `amount_out`
+    pub fn record_fill(&mut self, amount_in: u64, amount_out: u64) -> Result<()> {
+        self.filled_amount_in = self
+            .filled_amount_in
+            .checked_add(amount_in)
+            .ok_or(ContinuumError::MathOverflow)?;
+        self.filled_amount_out = self
+            .filled_amount_out
+            .checked_add(amount_out)
+            .ok_or(ContinuumError::MathOverflow)?;
+        Ok(())
+    }
+
+    /// Whether the order leaves the queue after its latest fill
+    pub fn fill_settles(&self) -> bool {
+        self.remaining_size() == 0 || self.fill_policy == FillPolicy::PartialReleaseRemainder
+    }
+
+    /// Whether the order may be funded from `account`
+    pub fn funds_from(&self, account: &Pubkey) -> bool {
+        self.source == Pubkey::default() || self.source == *account
+    }
+
+    /// Account the bond is refunded to when the order settles
+    pub fn bond_refund_recipient(&self) -> Pubkey {
+        if self.bond_payer == Pubkey::default() {
+            self.user
+        } else {
+            self.bond_payer
+        }
+    }
+
+    /// All pools the order swaps through, in execution order
+    pub fn route(&self) -> Vec<Pubkey> {
+        let mut route = vec![self.pool_id];
+        route.extend_from_slice(&self.route_pools[..(self.route_len.max(1) as usize - 1)]);
+        route
+    }
+
+    /// Whether this order was pending and past its inclusion delay when the
+    /// newer `executed` order of the same pool was last filled, in whole or in
+    /// part, so it should have gone first. Its delay ran out no later than that
+    /// of `executed`, which was executable. An order failed by then was not
+    /// executable, the relayer was expected to fail it first otherwise.
+    /// Unrevealed commitments have no amount and multi-hop orders cannot be
+    /// failed, neither ever counts.
+    pub fn was_skipped_for(&self, executed: &OrderState) -> bool {
+        let pending_at_execution = match self.status {
+            OrderStatus::Pending => true,
+            OrderStatus::Executed
+            | OrderStatus::Cancelled
+            | OrderStatus::Expired
+            | OrderStatus::Failed => self.executed_at > executed.executed_at,
+            OrderStatus::Committed => false,
+        };
+
+        (executed.status == OrderStatus::Executed || executed.filled_size() > 0)
+            && self.pool_id == executed.pool_id
+            && !self.is_multi_hop()
+            && self.sequence < executed.sequence
+            && self.submitted_slot < executed.submitted_slot
+            && self.amount_in > 0
+            && pending_at_execution
+    }
+}
+
+/// Hash an order was committed to, seeded by `[b"order_commitment", order]`.
+/// It lives until the order is revealed or expires unrevealed.
+#[account]
+pub struct OrderCommitment {
+    pub commitment: [u8; 32],
+    /// Last timestamp the order can be revealed at
+    pub reveal_deadline: i64,
 }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+impl OrderCommitment {
+    pub const LEN: usize = 8 + 32 + 8;
+
+    /// Commitment to an order's parameters. The salt keeps them from being
+    /// guessed by hashing likely values.
+    pub fn hash(
+        user: &Pubkey,
+        pool_id: &Pubkey,
+        amount_in: u64,
+        min_amount_out: u64,
+        is_base_input: bool,
+        salt: &[u8; 32],
+    ) -> [u8; 32] {
+        anchor_lang::solana_program::hash::hashv(&[
+            user.as_ref(),
+            pool_id.as_ref(),
+            &amount_in.to_le_bytes(),
+            &min_amount_out.to_le_bytes(),
+            &[is_base_input as u8],
+            salt,
+        ])
+        .to_bytes()
+    }
+}
+
+/// Seconds between a relayer asking to unstake and withdrawing, left to
+/// report misbehavior against the stake
+pub const RELAYER_UNSTAKE_DELAY: i64 = 7 * 24 * 60 * 60;
+
+/// Lamports a relayer staked to execute orders, seeded by
+/// `[b"relayer_stake", relayer]` and held in the account on top of its rent
+#[account]
+pub struct RelayerStake {
+    pub relayer: Pubkey,
+    /// Recorded on the orders the relayer executes
+    pub id: u64,
+    pub amount: u64,
+    /// When the relayer asked to unstake, 0 while staked
+    pub unstake_requested_at: i64,
+}
+
+impl RelayerStake {
+    pub const LEN: usize = 8 + 32 + 8 + 8 + 8;
+
+    /// Unstaking relayers can no longer execute orders
+    pub fn is_eligible(&self, min_relayer_stake: u64) -> bool {
+        self.amount >= min_relayer_stake && self.unstake_requested_at == 0
+    }
+}
+
+/// Pointer from a pool and order sequence to the order account, seeded by
+/// `[b"order_index", pool_id, sequence]` so the order can be found without
+/// knowing its user. Multi-hop orders are indexed under their first pool, the
+/// only one they queue in.
+#[account]
+pub struct OrderIndex {
+    pub order: Pubkey,
+}
+
+impl OrderIndex {
+    pub const LEN: usize = 8 + 32;
+}
+
+/// Records an order ring holds before submissions are refused, as many as fit
+/// in the 10 KiB an account created through CPI can have
+pub const ORDER_RING_CAPACITY: usize = 120;
+
+/// Optional per-pool queue of compact order records, an alternative to one
+/// `OrderState` account per order. Ring orders carry their own sequence, the
+/// position in the ring, and do not advance the FIFO sequence.
+#[account(zero_copy)]
+pub struct OrderRing {
+    pub pool_id: Pubkey,
+    /// Records removed from the head so far, the head order has sequence `head + 1`
+    pub head: u64,
+    /// Records in the ring, cancelled and expired ones included until they
+    /// reach the head
+    pub len: u64,
+    pub records: [OrderRecord; ORDER_RING_CAPACITY],
+}
+
+#[zero_copy]
+pub struct OrderRecord {
+    pub user: Pubkey,
+    pub amount_in: u64,
+    pub min_amount_out: u64,
+    pub submitted_at: i64,
+    pub submitted_slot: u64,
+    /// Lamports held in the ring for this order, refunded when it settles
+    pub bond_lamports: u64,
+    pub is_base_input: u8,
+    /// Set once the order is cancelled or expired, the record is dropped when
+    /// it reaches the head
+    pub cancelled: u8,
+    pub padding: [u8; 6],
+}
+
+impl OrderRing {
+    pub const LEN: usize = 8 + 32 + 8 + 8 + 80 * ORDER_RING_CAPACITY;
+
+    /// Sequence of the oldest record, 0 when the ring is empty
+    pub fn head_sequence(&self) -> u64 {
+        if self.len == 0 {
+            0
+        } else {
+            self.head + 1
+        }
+    }
+
+    /// Appends `record` at the tail and returns its sequence
+    pub fn push(&mut self, record: OrderRecord) -> Result<u64> {
+        require!(
+            self.len < ORDER_RING_CAPACITY as u64,
+            ContinuumError::OrderRingFull
+        );
+        let sequence = self.head + self.len + 1;
+        self.records[Self::slot(sequence)] = record;
+        self.len += 1;
+        Ok(sequence)
+    }
+
+    /// The record with `sequence`, if it is still in the ring
+    pub fn get(&self, sequence: u64) -> Option<&OrderRecord> {
+        self.contains(sequence)
+            .then(|| &self.records[Self::slot(sequence)])
+    }
+
+    pub fn get_mut(&mut self, sequence: u64) -> Option<&mut OrderRecord> {
+        self.contains(sequence)
+            .then(|| &mut self.records[Self::slot(sequence)])
+    }
+
+    /// Removes the head record, then any cancelled records behind it so the
+    /// head is always a pending order
+    pub fn pop(&mut self) {
+        if self.len > 0 {
+            self.head += 1;
+            self.len -= 1;
+        }
+        self.discard_cancelled();
+    }
+
+    pub fn discard_cancelled(&mut self) {
+        while self.len > 0 && self.records[Self::slot(self.head + 1)].cancelled != 0 {
+            self.head += 1;
+            self.len -= 1;
+        }
+    }
+
+    fn contains(&self, sequence: u64) -> bool {
+        sequence > self.head && sequence <= self.head + self.len
+    }
+
+    fn slot(sequence: u64) -> usize {
+        ((sequence - 1) % ORDER_RING_CAPACITY as u64) as usize
+    }
+}
+
+impl OrderRecord {
+    /// Whether `min_inclusion_delay` slots have passed since submission, as for
+    /// `OrderState::inclusion_delay_elapsed`
+    pub fn inclusion_delay_elapsed(&self, min_inclusion_delay: u64, slot: u64) -> bool {
+        slot >= self.submitted_slot.saturating_add(min_inclusion_delay)
+    }
+}
+
+#[account]
+pub struct UserState {
+    pub user: Pubkey,
+    /// Next nonce accepted for a signed order intent
+    pub nonce: u64,
+    /// Orders currently pending in the queue, across pools
+    pub pending_count: u32,
+    /// Orders ever submitted by the user
+    pub order_count: u64,
+}
+
+impl UserState {
+    pub const LEN: usize = 8 + 32 + 8 + 4 + 8;
+
+    pub fn record_submission(&mut self, user: Pubkey) {
+        self.user = user;
+        self.pending_count += 1;
+        self.order_count += 1;
+    }
+
+    /// Called when a pending order is executed, cancelled or expired
+    pub fn release_pending(&mut self) {
+        self.pending_count = self.pending_count.saturating_sub(1);
+    }
+}
+
+/// Pending orders of a user queued in one pool, held to the pool's
+/// `max_pending_per_user`
+#[account]
+pub struct UserPoolState {
+    pub user: Pubkey,
+    pub pool_id: Pubkey,
+    pub pending_count: u32,
+}
+
+impl UserPoolState {
+    pub const LEN: usize = 8 + 32 + 32 + 4;
+
+    pub fn record_submission(&mut self, user: Pubkey, pool_id: Pubkey) {
+        self.user = user;
+        self.pool_id = pool_id;
+        self.pending_count += 1;
+    }
+
+    /// Called when a pending order is executed, cancelled or expired
+    pub fn release_pending(&mut self) {
+        self.pending_count = self.pending_count.saturating_sub(1);
+    }
+}
+
+/// Order parameters signed off-chain by the user and submitted by a relayer.
+/// The borsh serialization of this struct is the ed25519-signed message.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct OrderIntent {
+    pub program_id: Pubkey,
+    pub user: Pubkey,
+    pub pool_id: Pubkey,
+    /// Token account the order is funded from and the relayer fee is paid from
+    pub source: Pubkey,
+    pub amount_in: u64,
+    pub min_amount_out: u64,
+    pub is_base_input: bool,
+    /// Input tokens paid to the relayer for submitting the order
+    pub relayer_fee: u64,
+    pub nonce: u64,
+    pub deadline: i64,
+}
+
+/// Expected outcome of a swap at current reserves, returned by `quote_swap`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct SwapQuote {
+    /// Input spent, fees included
+    pub amount_in: u64,
+    pub amount_out: u64,
+    /// CP-Swap trade fee, charged in the input token
+    pub trade_fee: u64,
+    /// Protocol and fund shares of the trade fee
+    pub protocol_fee: u64,
+    pub fund_fee: u64,
+    /// How far the execution price, fees excluded, falls short of the spot price
+    pub price_impact_bps: u16,
+}
+
+/// How executors may fill an order, chosen by its user at submission
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum FillPolicy {
+    /// Every execution fills the whole order or fails, leaving it pending
+    FillOrKill,
+    /// Executors may fill part of the order, the remainder stays pending at
+    /// its queue position until filled, cancelled or expired
+    PartialKeepRemainder,
+    /// Executors may fill part of the order, the remainder is released and the
+    /// order settles with that fill
+    PartialReleaseRemainder,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
 pub enum OrderStatus {
     Pending,
     Executed,
     Cancelled,
+    /// Settled by `fail_order` once it could no longer be executed
     Failed,
+    Expired,
+    /// Queued behind a hash commitment, its parameters not revealed yet
+    Committed,
+}
+
+/// `FifoState` as written before accounts were versioned
+#[derive(AnchorDeserialize)]
+pub struct FifoStateV0 {
+    pub current_sequence: u64,
+    pub admin: Pubkey,
+    pub emergency_pause: bool,
+}
+
+impl FifoStateV0 {
+    pub const LEN: usize = 8 + 8 + 32 + 1;
+
+    pub fn migrate(self) -> FifoState {
+        FifoState {
+            current_sequence: self.current_sequence,
+            admin: self.admin,
+            emergency_pause: self.emergency_pause,
+            version: StateVersion::CURRENT,
+            relayer_count: 0,
+            reserved: [0; 56],
+        }
+    }
+}
+
+/// `CpSwapPoolRegistry` as written before accounts were versioned
+#[derive(AnchorDeserialize)]
+pub struct CpSwapPoolRegistryV0 {
+    pub pool_id: Pubkey,
+    pub token_0: Pubkey,
+    pub token_1: Pubkey,
+    pub continuum_authority: Pubkey,
+    pub created_at: i64,
+    pub is_active: bool,
+}
+
+impl CpSwapPoolRegistryV0 {
+    pub const LEN: usize = 8 + 32 + 32 + 32 + 32 + 8 + 1;
+
+    /// Order limits and bonds start disabled, vaults are read from the pool itself
+    pub fn migrate(self, pool: &PoolStateView) -> CpSwapPoolRegistry {
+        CpSwapPoolRegistry {
+            pool_id: self.pool_id,
+            token_0: self.token_0,
+            token_1: self.token_1,
+            continuum_authority: self.continuum_authority,
+            created_at: self.created_at,
+            is_active: self.is_active,
+            is_draining: false,
+            max_pending_per_user: 0,
+            order_ttl: 0,
+            order_bond: 0,
+            min_resting_period: 0,
+            bond_slash_bps: 0,
+            bond_recipient: Pubkey::default(),
+            token_0_vault: pool.token_0_vault,
+            token_1_vault: pool.token_1_vault,
+            version: StateVersion::CURRENT,
+            queue: QueueStatus::default(),
+            min_inclusion_delay: 0,
+            reveal_window: 0,
+            min_relayer_stake: 0,
+            relayer_slash_bps: 0,
+            head_since: 0,
+            self_execution_timeout: 0,
+            has_order_ring: false,
+            reserved: [0; 53],
+        }
+    }
+}
+
+/// `OrderState` as written before accounts were versioned
+#[derive(AnchorDeserialize)]
+pub struct OrderStateV0 {
+    pub sequence: u64,
+    pub user: Pubkey,
+    pub pool_id: Pubkey,
+    pub amount_in: u64,
+    pub min_amount_out: u64,
+    pub is_base_input: bool,
+    pub status: OrderStatus,
+    pub submitted_at: i64,
+    pub executed_at: Option<i64>,
+}
+
+impl OrderStateV0 {
+    pub const LEN: usize = 8 + 8 + 32 + 32 + 8 + 8 + 1 + 1 + 8 + 9;
+
+    /// Legacy orders are single-pool and carry no bond
+    pub fn migrate(self) -> OrderState {
+        OrderState {
+            sequence: self.sequence,
+            user: self.user,
+            pool_id: self.pool_id,
+            amount_in: self.amount_in,
+            min_amount_out: self.min_amount_out,
+            is_base_input: self.is_base_input,
+            status: self.status,
+            submitted_at: self.submitted_at,
+            executed_at: self.executed_at,
+            route_len: 1,
+            route_pools: [Pubkey::default(); MAX_ROUTE_LEN - 1],
+            bond_lamports: 0,
+            version: StateVersion::CURRENT,
+            queue_prev: 0,
+            queue_next: 0,
+            submitted_slot: 0,
+            executed_by: 0,
+            filled_amount_in: 0,
+            filled_amount_out: 0,
+            fill_policy: FillPolicy::FillOrKill,
+            source: Pubkey::default(),
+            bond_payer: Pubkey::default(),
+            reserved: [0; 64],
+        }
+    }
+}
+
+/// `OrderState` as written by version 1, before partial fills
+#[derive(AnchorDeserialize)]
+pub struct OrderStateV1 {
+    pub sequence: u64,
+    pub user: Pubkey,
+    pub pool_id: Pubkey,
+    pub amount_in: u64,
+    pub min_amount_out: u64,
+    pub is_base_input: bool,
+    pub status: OrderStatus,
+    pub submitted_at: i64,
+    pub executed_at: Option<i64>,
+    pub route_len: u8,
+    pub route_pools: [Pubkey; MAX_ROUTE_LEN - 1],
+    pub bond_lamports: u64,
+    pub version: u8,
+    pub queue_prev: u64,
+    pub queue_next: u64,
+    pub submitted_slot: u64,
+    pub executed_by: u64,
+}
+
+impl OrderStateV1 {
+    pub const LEN: usize = 8
+        + 8
+        + 32
+        + 32
+        + 8
+        + 8
+        + 1
+        + 1
+        + 8
+        + 9
+        + 1
+        + 32 * (MAX_ROUTE_LEN - 1)
+        + 8
+        + 1
+        + 8
+        + 8
+        + 8
+        + 8;
+
+    /// Nothing is filled yet, and orders could only be filled whole
+    pub fn migrate(self) -> OrderState {
+        OrderState {
+            sequence: self.sequence,
+            user: self.user,
+            pool_id: self.pool_id,
+            amount_in: self.amount_in,
+            min_amount_out: self.min_amount_out,
+            is_base_input: self.is_base_input,
+            status: self.status,
+            submitted_at: self.submitted_at,
+            executed_at: self.executed_at,
+            route_len: self.route_len,
+            route_pools: self.route_pools,
+            bond_lamports: self.bond_lamports,
+            version: StateVersion::CURRENT,
+            queue_prev: self.queue_prev,
+            queue_next: self.queue_next,
+            submitted_slot: self.submitted_slot,
+            executed_by: self.executed_by,
+            filled_amount_in: 0,
+            filled_amount_out: 0,
+            fill_policy: FillPolicy::FillOrKill,
+            source: Pubkey::default(),
+            bond_payer: Pubkey::default(),
+            reserved: [0; 64],
+        }
+    }
+}
+
+/// `OrderState` as written by version 2, before its reserve was sized for the
+/// planned fields
+#[derive(AnchorDeserialize)]
+pub struct OrderStateV2 {
+    pub sequence: u64,
+    pub user: Pubkey,
+    pub pool_id: Pubkey,
+    pub amount_in: u64,
+    pub min_amount_out: u64,
+    pub is_base_input: bool,
+    pub status: OrderStatus,
+    pub submitted_at: i64,
+    pub executed_at: Option<i64>,
+    pub route_len: u8,
+    pub route_pools: [Pubkey; MAX_ROUTE_LEN - 1],
+    pub bond_lamports: u64,
+    pub version: u8,
+    pub queue_prev: u64,
+    pub queue_next: u64,
+    pub submitted_slot: u64,
+    pub executed_by: u64,
+    pub filled_amount_in: u64,
+    pub filled_amount_out: u64,
+    pub fill_policy: FillPolicy,
+}
+
+impl OrderStateV2 {
+    pub const LEN: usize = OrderStateV1::LEN + 8 + 8 + 1 + 31;
+
+    /// Every field carries over, the reserve only grows
+    pub fn migrate(self) -> OrderState {
+        OrderState {
+            sequence: self.sequence,
+            user: self.user,
+            pool_id: self.pool_id,
+            amount_in: self.amount_in,
+            min_amount_out: self.min_amount_out,
+            is_base_input: self.is_base_input,
+            status: self.status,
+            submitted_at: self.submitted_at,
+            executed_at: self.executed_at,
+            route_len: self.route_len,
+            route_pools: self.route_pools,
+            bond_lamports: self.bond_lamports,
+            version: StateVersion::CURRENT,
+            queue_prev: self.queue_prev,
+            queue_next: self.queue_next,
+            submitted_slot: self.submitted_slot,
+            executed_by: self.executed_by,
+            filled_amount_in: self.filled_amount_in,
+            filled_amount_out: self.filled_amount_out,
+            fill_policy: self.fill_policy,
+            source: Pubkey::default(),
+            bond_payer: Pubkey::default(),
+            reserved: [0; 64],
+        }
+    }
+}
+
+/// Version of the event schema below, bumped on any breaking change to an event
+pub const EVENT_VERSION: u8 = 1;
+
+// Every event starts with `version` so indexers can tell schema revisions apart.
+// Events are emitted through `emit_cpi!` and land in inner instruction data.
+
+#[event]
+pub struct FifoInitialized {
+    pub version: u8,
+    pub admin: Pubkey,
+}
+
+#[event]
+pub struct AdminConfigInitialized {
+    pub version: u8,
+    pub super_admin: Pubkey,
+    pub timelock_delay: i64,
 }
 
 #[event]
 pub struct OrderSubmitted {
+    pub version: u8,
     pub sequence: u64,
     pub user: Pubkey,
     pub pool_id: Pubkey,
     pub amount_in: u64,
+    pub min_amount_out: u64,
     pub is_base_input: bool,
+    pub bond_lamports: u64,
+    pub submitted_at: i64,
+}
+
+#[event]
+pub struct MultiHopOrderSubmitted {
+    pub version: u8,
+    pub sequence: u64,
+    pub user: Pubkey,
+    pub route: Vec<Pubkey>,
+    pub amount_in: u64,
+    pub min_amount_out: u64,
+    pub bond_lamports: u64,
+    pub submitted_at: i64,
+}
+
+#[event]
+pub struct IntentRelayed {
+    pub version: u8,
+    pub sequence: u64,
+    pub user: Pubkey,
+    pub relayer: Pubkey,
+    pub relayer_fee: u64,
+    pub nonce: u64,
+}
+
+#[event]
+pub struct OrderAmended {
+    pub version: u8,
+    pub old_sequence: u64,
+    /// Equal to `old_sequence` when the order kept its queue position
+    pub new_sequence: u64,
+    pub user: Pubkey,
+    pub old_amount_in: u64,
+    pub new_amount_in: u64,
+    pub old_min_amount_out: u64,
+    pub new_min_amount_out: u64,
+    pub kept_queue_position: bool,
+    pub amended_at: i64,
 }
 
 #[event]
 pub struct OrderExecuted {
+    pub version: u8,
     pub sequence: u64,
     pub user: Pubkey,
+    /// First pool of the route
+    pub pool_id: Pubkey,
+    pub executor: Pubkey,
+    /// Tokens actually taken from the user's source account, over all fills
+    pub amount_in: u64,
+    /// Tokens actually received in the user's destination account, over all fills
     pub amount_out: u64,
+    pub executed_at: i64,
+}
+
+/// A fill that leaves the rest of the order pending
+#[event]
+pub struct OrderPartiallyFilled {
+    pub version: u8,
+    pub sequence: u64,
+    pub user: Pubkey,
+    pub pool_id: Pubkey,
     pub executor: Pubkey,
+    /// Tokens taken and delivered by this fill
+    pub amount_in: u64,
+    pub amount_out: u64,
+    /// Order size still to fill, in the unit of the order's `amount_in`
+    pub remaining: u64,
+    pub filled_at: i64,
 }
 
 #[event]
 pub struct OrderCancelled {
+    pub version: u8,
+    pub sequence: u64,
+    pub user: Pubkey,
+    /// Bond returned to the user, after any slash
+    pub bond_refunded: u64,
+    pub cancelled_at: i64,
+}
+
+#[event]
+pub struct OrderRingInitialized {
+    pub version: u8,
+    pub pool_id: Pubkey,
+    pub capacity: u16,
+}
+
+/// Ring order events carry the ring sequence, not the FIFO sequence
+#[event]
+pub struct RingOrderSubmitted {
+    pub version: u8,
+    pub pool_id: Pubkey,
+    pub sequence: u64,
+    pub user: Pubkey,
+    pub amount_in: u64,
+    pub min_amount_out: u64,
+    pub is_base_input: bool,
+    pub bond_lamports: u64,
+    pub submitted_at: i64,
+}
+
+#[event]
+pub struct RingOrderExecuted {
+    pub version: u8,
+    pub pool_id: Pubkey,
+    pub sequence: u64,
+    pub user: Pubkey,
+    pub executor: Pubkey,
+    pub amount_in: u64,
+    pub amount_out: u64,
+    pub executed_at: i64,
+}
+
+#[event]
+pub struct RingOrderCancelled {
+    pub version: u8,
+    pub pool_id: Pubkey,
+    pub sequence: u64,
+    pub user: Pubkey,
+    /// Bond returned to the user, after any slash
+    pub bond_refunded: u64,
+    pub cancelled_at: i64,
+}
+
+#[event]
+pub struct RingOrderExpired {
+    pub version: u8,
+    pub pool_id: Pubkey,
     pub sequence: u64,
     pub user: Pubkey,
+    pub bond_refunded: u64,
+    pub expired_at: i64,
+}
+
+#[event]
+pub struct BondSlashed {
+    pub version: u8,
+    pub sequence: u64,
+    pub user: Pubkey,
+    pub slashed: u64,
+    pub recipient: Pubkey,
+}
+
+#[event]
+pub struct OrderCommitted {
+    pub version: u8,
+    pub sequence: u64,
+    pub user: Pubkey,
+    pub pool_id: Pubkey,
+    pub commitment: [u8; 32],
+    pub bond_lamports: u64,
+    pub reveal_deadline: i64,
+    pub committed_at: i64,
+}
+
+#[event]
+pub struct OrderRevealed {
+    pub version: u8,
+    pub sequence: u64,
+    pub user: Pubkey,
+    pub amount_in: u64,
+    pub min_amount_out: u64,
+    pub is_base_input: bool,
+    pub revealed_at: i64,
+}
+
+#[event]
+pub struct OrderExpired {
+    pub version: u8,
+    pub sequence: u64,
+    pub user: Pubkey,
+    pub bond_refunded: u64,
+    pub expired_at: i64,
+}
+
+/// An order settled as `Failed` by a relayer, any earlier partial fills stand
+#[event]
+pub struct OrderFailed {
+    pub version: u8,
+    pub sequence: u64,
+    pub user: Pubkey,
+    pub bond_refunded: u64,
+    pub failed_at: i64,
+}
+
+#[event]
+pub struct RelayerStaked {
+    pub version: u8,
+    pub relayer: Pubkey,
+    pub stake_id: u64,
+    pub amount: u64,
+    /// Stake after this deposit
+    pub total: u64,
+}
+
+#[event]
+pub struct RelayerUnstakeRequested {
+    pub version: u8,
+    pub relayer: Pubkey,
+    pub amount: u64,
+    pub withdrawable_at: i64,
+}
+
+#[event]
+pub struct RelayerStakeWithdrawn {
+    pub version: u8,
+    pub relayer: Pubkey,
+    pub amount: u64,
+}
+
+/// A relayer executed `executed_sequence` ahead of the older `skipped_sequence`
+#[event]
+pub struct RelayerSlashed {
+    pub version: u8,
+    pub relayer: Pubkey,
+    pub pool_id: Pubkey,
+    pub executed_sequence: u64,
+    pub skipped_sequence: u64,
+    pub reporter: Pubkey,
+    pub reporter_reward: u64,
+    /// User of the skipped order
+    pub user: Pubkey,
+    pub user_compensation: u64,
+}
+
+#[event]
+pub struct SwapExecuted {
+    pub version: u8,
+    pub sequence: u64,
+    pub pool_id: Pubkey,
+    pub amount_in: u64,
+    pub min_amount_out: u64,
+    pub is_base_input: bool,
+    pub executed_at: i64,
+}
+
+#[event]
+pub struct LiquidityDeposited {
+    pub version: u8,
+    pub pool_id: Pubkey,
+    pub lp_token_amount: u64,
+    pub maximum_token_0_amount: u64,
+    pub maximum_token_1_amount: u64,
+    pub deposited_at: i64,
+}
+
+#[event]
+pub struct LiquidityWithdrawn {
+    pub version: u8,
+    pub pool_id: Pubkey,
+    pub lp_token_amount: u64,
+    pub minimum_token_0_amount: u64,
+    pub minimum_token_1_amount: u64,
+    pub withdrawn_at: i64,
 }
 
 #[event]
 pub struct PoolRegistered {
+    pub version: u8,
     pub pool_id: Pubkey,
     pub continuum_authority: Pubkey,
+    pub token_0: Pubkey,
+    pub token_1: Pubkey,
+}
+
+#[event]
+pub struct RoleUpdated {
+    pub version: u8,
+    pub role: AdminRole,
+    pub old_holder: Pubkey,
+    pub new_holder: Pubkey,
+}
+
+#[event]
+pub struct SuperAdminProposed {
+    pub version: u8,
+    pub super_admin: Pubkey,
+    pub proposed: Pubkey,
+}
+
+#[event]
+pub struct SuperAdminTransferred {
+    pub version: u8,
+    pub old_super_admin: Pubkey,
+    pub new_super_admin: Pubkey,
+}
+
+#[event]
+pub struct EmergencyPauseSet {
+    pub version: u8,
+    pub paused: bool,
+    pub authority: Pubkey,
+}
+
+#[event]
+pub struct PoolStatusChanged {
+    pub version: u8,
+    pub pool_id: Pubkey,
+    pub old_status: PoolStatus,
+    pub new_status: PoolStatus,
+}
+
+#[event]
+pub struct PoolConfigUpdated {
+    pub version: u8,
+    pub pool_id: Pubkey,
+    pub max_pending_per_user: u16,
+    pub order_ttl: i64,
+    pub order_bond: u64,
+    pub min_resting_period: i64,
+    pub bond_slash_bps: u16,
+    pub bond_recipient: Pubkey,
+    pub min_inclusion_delay: u64,
+    pub reveal_window: i64,
+    pub min_relayer_stake: u64,
+    pub relayer_slash_bps: u16,
+    pub self_execution_timeout: i64,
+}
+
+#[event]
+pub struct TimelockDelayUpdated {
+    pub version: u8,
+    pub old_delay: i64,
+    pub new_delay: i64,
+}
+
+#[event]
+pub struct ConfigChangeQueued {
+    pub version: u8,
+    pub id: u64,
+    pub proposer: Pubkey,
+    pub change: ConfigChange,
+    pub effective_after: i64,
+}
+
+#[event]
+pub struct ConfigChangeExecuted {
+    pub version: u8,
+    pub id: u64,
+    pub change: ConfigChange,
+}
+
+#[event]
+pub struct ConfigChangeCancelled {
+    pub version: u8,
+    pub id: u64,
+    pub cancelled_by: Pubkey,
+}
+
+#[event]
+pub struct AccountMigrated {
+    pub version: u8,
+    pub account: Pubkey,
+    pub state_version: u8,
 }
@@ -0,0 +1,98 @@
+use crate::errors::ContinuumError;
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+
+/// `msg!` for diagnostics that are not worth their compute in production.
+/// Logs only with the `verbose-logs` feature; otherwise the arguments are still
+/// type-checked but the call compiles away.
+macro_rules! debug_msg {
+    ($($arg:tt)*) => {
+        if cfg!(feature = "verbose-logs") {
+            anchor_lang::prelude::msg!($($arg)*);
+        }
+    };
+}
+pub(crate) use debug_msg;
+
+/// Moves lamports out of an account owned by this program
+pub fn transfer_lamports(from: &AccountInfo, to: &AccountInfo, lamports: u64) -> Result<()> {
+    if lamports == 0 {
+        return Ok(());
+    }
+
+    let from_balance = from.lamports();
+    **from.try_borrow_mut_lamports()? = from_balance
+        .checked_sub(lamports)
+        .ok_or(ContinuumError::InvalidAmount)?;
+    let to_balance = to.lamports();
+    **to.try_borrow_mut_lamports()? = to_balance
+        .checked_add(lamports)
+        .ok_or(ContinuumError::InvalidAmount)?;
+
+    Ok(())
+}
+
+/// Closes an order's index, already checked by its seeds, to `recipient`.
+/// Orders submitted before indexing have none, and their index address holds
+/// no account.
+pub fn close_order_index<'info>(
+    order_index: &AccountInfo<'info>,
+    recipient: &AccountInfo<'info>,
+) -> Result<()> {
+    if order_index.owner != &crate::ID {
+        return Ok(());
+    }
+    transfer_lamports(order_index, recipient, order_index.lamports())?;
+    order_index.assign(&system_program::ID);
+    order_index.realloc(0, false)?;
+
+    Ok(())
+}
+
+/// Grows an account owned by this program to `new_len`, topping up rent from `payer`
+pub fn realloc_account<'info>(
+    account: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    new_len: usize,
+) -> Result<()> {
+    let rent_due = Rent::get()?
+        .minimum_balance(new_len)
+        .saturating_sub(account.lamports());
+    if rent_due > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                system_program.clone(),
+                system_program::Transfer {
+                    from: payer.clone(),
+                    to: account.clone(),
+                },
+            ),
+            rent_due,
+        )?;
+    }
+
+    account.realloc(new_len, true)?;
+
+    Ok(())
+}
+
+/// Reads an account of type `T`, an older layout stored with the discriminator of `A`
+pub fn load_legacy<A: Discriminator, T: AnchorDeserialize>(
+    account: &AccountInfo,
+    legacy_len: usize,
+) -> Result<T> {
+    let data = account.try_borrow_data()?;
+    require!(
+        data.len() == legacy_len && data.starts_with(A::DISCRIMINATOR),
+        ContinuumError::UnsupportedAccountVersion
+    );
+
+    Ok(T::deserialize(&mut &data[8..])?)
+}
+
+/// Writes `value` with its discriminator over the whole account data
+pub fn store_account<A: AccountSerialize>(account: &AccountInfo, value: &A) -> Result<()> {
+    let mut data = account.try_borrow_mut_data()?;
+    value.try_serialize(&mut &mut data[..])
+}
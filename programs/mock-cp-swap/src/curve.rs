@@ -0,0 +1,98 @@
+//! Constant-product math with a trade fee, rounding in the pool's favour
+use crate::errors::ErrorCode;
+use anchor_lang::prelude::*;
+
+/// Fee rates are in hundredths of a basis point
+pub const FEE_RATE_DENOMINATOR: u64 = 1_000_000;
+
+fn to_u64(value: u128) -> Result<u64> {
+    u64::try_from(value).map_err(|_| error!(ErrorCode::MathOverflow))
+}
+
+fn ceil_div(numerator: u128, denominator: u128) -> Result<u128> {
+    require!(denominator > 0, ErrorCode::ZeroTradingTokens);
+    Ok(numerator.div_ceil(denominator))
+}
+
+/// Fee charged on `amount_in`
+pub fn trade_fee(amount_in: u64, trade_fee_rate: u64) -> Result<u64> {
+    to_u64(ceil_div(
+        amount_in as u128 * trade_fee_rate as u128,
+        FEE_RATE_DENOMINATOR as u128,
+    )?)
+}
+
+/// Share of `trade_fee` set aside at `rate`, for the protocol or fund owner
+pub fn fee_share(trade_fee: u64, rate: u64) -> u64 {
+    (trade_fee as u128 * rate as u128 / FEE_RATE_DENOMINATOR as u128) as u64
+}
+
+/// Output for an exact `amount_in`
+pub fn swap_base_input(
+    amount_in: u64,
+    input_reserve: u64,
+    output_reserve: u64,
+    trade_fee_rate: u64,
+) -> Result<u64> {
+    let amount_in_less_fee = (amount_in - trade_fee(amount_in, trade_fee_rate)?) as u128;
+    let numerator = amount_in_less_fee * output_reserve as u128;
+    let denominator = input_reserve as u128 + amount_in_less_fee;
+    require!(denominator > 0, ErrorCode::ZeroTradingTokens);
+    to_u64(numerator / denominator)
+}
+
+/// Input, fee included, needed for an exact `amount_out`
+pub fn swap_base_output(
+    amount_out: u64,
+    input_reserve: u64,
+    output_reserve: u64,
+    trade_fee_rate: u64,
+) -> Result<u64> {
+    require!(amount_out < output_reserve, ErrorCode::ZeroTradingTokens);
+    let amount_in_less_fee = ceil_div(
+        amount_out as u128 * input_reserve as u128,
+        (output_reserve - amount_out) as u128,
+    )?;
+    to_u64(ceil_div(
+        amount_in_less_fee * FEE_RATE_DENOMINATOR as u128,
+        (FEE_RATE_DENOMINATOR - trade_fee_rate) as u128,
+    )?)
+}
+
+/// Initial liquidity, the geometric mean of both deposits
+pub fn initial_liquidity(amount_0: u64, amount_1: u64) -> u64 {
+    let product = amount_0 as u128 * amount_1 as u128;
+    if product < 2 {
+        return product as u64;
+    }
+
+    // Newton's method, converges from above
+    let mut root = product;
+    let mut next = (root + product / root) / 2;
+    while next < root {
+        root = next;
+        next = (root + product / root) / 2;
+    }
+    root as u64
+}
+
+/// Token amounts backing `lp_token_amount`, rounded up for deposits and down
+/// for withdrawals
+pub fn lp_tokens_to_trading_tokens(
+    lp_token_amount: u64,
+    lp_supply: u64,
+    reserve_0: u64,
+    reserve_1: u64,
+    round_up: bool,
+) -> Result<(u64, u64)> {
+    require!(lp_supply > 0, ErrorCode::ZeroTradingTokens);
+    let share = |reserve: u64| {
+        let numerator = lp_token_amount as u128 * reserve as u128;
+        if round_up {
+            ceil_div(numerator, lp_supply as u128)
+        } else {
+            Ok(numerator / lp_supply as u128)
+        }
+    };
+    Ok((to_u64(share(reserve_0)?)?, to_u64(share(reserve_1)?)?))
+}
@@ -0,0 +1,25 @@
+use anchor_lang::prelude::*;
+
+#[error_code]
+pub enum ErrorCode {
+    #[msg("Not approved")]
+    NotApproved,
+
+    #[msg("Input account owner is not the program address")]
+    InvalidOwner,
+
+    #[msg("Exceeds desired slippage limit")]
+    ExceededSlippage,
+
+    #[msg("Given pool token amount results in zero trading tokens")]
+    ZeroTradingTokens,
+
+    #[msg("Invalid input")]
+    InvalidInput,
+
+    #[msg("Address of the provided vault is wrong")]
+    InvalidVault,
+
+    #[msg("Math overflow")]
+    MathOverflow,
+}
@@ -0,0 +1,53 @@
+use crate::curve::FEE_RATE_DENOMINATOR;
+use crate::errors::ErrorCode;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(index: u16)]
+pub struct CreateAmmConfig<'info> {
+    /// Becomes the protocol and fund owner, CP-Swap restricts this to its admin
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = AmmConfig::LEN,
+        seeds = [AMM_CONFIG_SEED, &index.to_be_bytes()],
+        bump,
+    )]
+    pub amm_config: Box<Account<'info, AmmConfig>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn create_amm_config(
+    ctx: Context<CreateAmmConfig>,
+    index: u16,
+    trade_fee_rate: u64,
+    protocol_fee_rate: u64,
+    fund_fee_rate: u64,
+    create_pool_fee: u64,
+) -> Result<()> {
+    require!(
+        trade_fee_rate < FEE_RATE_DENOMINATOR,
+        ErrorCode::InvalidInput
+    );
+    require!(
+        protocol_fee_rate + fund_fee_rate <= FEE_RATE_DENOMINATOR,
+        ErrorCode::InvalidInput
+    );
+
+    let amm_config = &mut ctx.accounts.amm_config;
+    amm_config.bump = ctx.bumps.amm_config;
+    amm_config.disable_create_pool = false;
+    amm_config.index = index;
+    amm_config.trade_fee_rate = trade_fee_rate;
+    amm_config.protocol_fee_rate = protocol_fee_rate;
+    amm_config.fund_fee_rate = fund_fee_rate;
+    amm_config.create_pool_fee = create_pool_fee;
+    amm_config.protocol_owner = ctx.accounts.owner.key();
+    amm_config.fund_owner = ctx.accounts.owner.key();
+    Ok(())
+}
@@ -0,0 +1,123 @@
+use crate::curve;
+use crate::errors::ErrorCode;
+use crate::state::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, MintTo, Token, TokenAccount, Transfer};
+
+#[derive(Accounts)]
+pub struct Deposit<'info> {
+    /// Provides the tokens, as owner or delegate of the token accounts, and
+    /// must be the custom authority of custom-authority pools
+    pub owner: Signer<'info>,
+
+    /// CHECK: Vault and LP mint authority
+    #[account(seeds = [AUTH_SEED], bump = pool_state.auth_bump)]
+    pub authority: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub pool_state: Box<Account<'info, PoolState>>,
+
+    #[account(mut, token::mint = lp_mint)]
+    pub owner_lp_token: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut, token::mint = vault_0_mint)]
+    pub token_0_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut, token::mint = vault_1_mint)]
+    pub token_1_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut, address = pool_state.token_0_vault @ ErrorCode::InvalidVault)]
+    pub token_0_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut, address = pool_state.token_1_vault @ ErrorCode::InvalidVault)]
+    pub token_1_vault: Box<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+
+    #[account(address = pool_state.token_0_mint)]
+    pub vault_0_mint: Box<Account<'info, Mint>>,
+
+    #[account(address = pool_state.token_1_mint)]
+    pub vault_1_mint: Box<Account<'info, Mint>>,
+
+    #[account(mut, address = pool_state.lp_mint)]
+    pub lp_mint: Box<Account<'info, Mint>>,
+}
+
+pub fn deposit(
+    ctx: Context<Deposit>,
+    lp_token_amount: u64,
+    maximum_token_0_amount: u64,
+    maximum_token_1_amount: u64,
+) -> Result<()> {
+    let pool_state = &ctx.accounts.pool_state;
+    require!(
+        pool_state.is_authorized(&ctx.accounts.owner.key()),
+        ErrorCode::NotApproved
+    );
+    require!(lp_token_amount > 0, ErrorCode::InvalidInput);
+
+    let (token_0_amount, token_1_amount) = curve::lp_tokens_to_trading_tokens(
+        lp_token_amount,
+        pool_state.lp_supply,
+        pool_state.reserve(
+            &ctx.accounts.token_0_vault.key(),
+            ctx.accounts.token_0_vault.amount,
+        )?,
+        pool_state.reserve(
+            &ctx.accounts.token_1_vault.key(),
+            ctx.accounts.token_1_vault.amount,
+        )?,
+        true,
+    )?;
+    require!(
+        token_0_amount <= maximum_token_0_amount && token_1_amount <= maximum_token_1_amount,
+        ErrorCode::ExceededSlippage
+    );
+
+    for (from, to, amount) in [
+        (
+            &ctx.accounts.token_0_account,
+            &ctx.accounts.token_0_vault,
+            token_0_amount,
+        ),
+        (
+            &ctx.accounts.token_1_account,
+            &ctx.accounts.token_1_vault,
+            token_1_amount,
+        ),
+    ] {
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: from.to_account_info(),
+                    to: to.to_account_info(),
+                    authority: ctx.accounts.owner.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+    }
+
+    token::mint_to(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            MintTo {
+                mint: ctx.accounts.lp_mint.to_account_info(),
+                to: ctx.accounts.owner_lp_token.to_account_info(),
+                authority: ctx.accounts.authority.to_account_info(),
+            },
+            &[&[AUTH_SEED, &[pool_state.auth_bump]]],
+        ),
+        lp_token_amount,
+    )?;
+
+    let pool_state = &mut ctx.accounts.pool_state;
+    pool_state.lp_supply = pool_state
+        .lp_supply
+        .checked_add(lp_token_amount)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    Ok(())
+}
@@ -0,0 +1,183 @@
+use crate::curve;
+use crate::errors::ErrorCode;
+use crate::state::*;
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token::{self, Mint, MintTo, Token, TokenAccount, Transfer};
+
+#[derive(Accounts)]
+pub struct Initialize<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    #[account(constraint = !amm_config.disable_create_pool @ ErrorCode::NotApproved)]
+    pub amm_config: Box<Account<'info, AmmConfig>>,
+
+    /// CHECK: Vault and LP mint authority
+    #[account(seeds = [AUTH_SEED], bump)]
+    pub authority: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = creator,
+        space = PoolState::LEN,
+        seeds = [
+            POOL_SEED,
+            amm_config.key().as_ref(),
+            token_0_mint.key().as_ref(),
+            token_1_mint.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub pool_state: Box<Account<'info, PoolState>>,
+
+    #[account(
+        constraint = token_0_mint.key() < token_1_mint.key() @ ErrorCode::InvalidInput,
+    )]
+    pub token_0_mint: Box<Account<'info, Mint>>,
+
+    pub token_1_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        init,
+        payer = creator,
+        seeds = [POOL_LP_MINT_SEED, pool_state.key().as_ref()],
+        bump,
+        mint::decimals = 9,
+        mint::authority = authority,
+    )]
+    pub lp_mint: Box<Account<'info, Mint>>,
+
+    #[account(mut, token::mint = token_0_mint)]
+    pub creator_token_0: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut, token::mint = token_1_mint)]
+    pub creator_token_1: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        init,
+        payer = creator,
+        associated_token::mint = lp_mint,
+        associated_token::authority = creator,
+    )]
+    pub creator_lp_token: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        init,
+        payer = creator,
+        seeds = [POOL_VAULT_SEED, pool_state.key().as_ref(), token_0_mint.key().as_ref()],
+        bump,
+        token::mint = token_0_mint,
+        token::authority = authority,
+    )]
+    pub token_0_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        init,
+        payer = creator,
+        seeds = [POOL_VAULT_SEED, pool_state.key().as_ref(), token_1_mint.key().as_ref()],
+        bump,
+        token::mint = token_1_mint,
+        token::authority = authority,
+    )]
+    pub token_1_vault: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: Pool creation fee receiver, the mock charges no creation fee
+    #[account(mut)]
+    pub create_pool_fee: UncheckedAccount<'info>,
+
+    /// CHECK: Recorded on the pool, the mock keeps no price observations
+    #[account(mut)]
+    pub observation_state: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub token_0_program: Program<'info, Token>,
+    pub token_1_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+pub fn initialize(
+    ctx: Context<Initialize>,
+    init_amount_0: u64,
+    init_amount_1: u64,
+    open_time: u64,
+    authority_type: u8,
+    custom_authority: Option<Pubkey>,
+) -> Result<()> {
+    require!(
+        authority_type == AUTHORITY_TYPE_DEFAULT || authority_type == AUTHORITY_TYPE_CUSTOM,
+        ErrorCode::InvalidInput
+    );
+    let custom_authority = if authority_type == AUTHORITY_TYPE_CUSTOM {
+        custom_authority.ok_or(ErrorCode::InvalidInput)?
+    } else {
+        Pubkey::default()
+    };
+
+    let liquidity = curve::initial_liquidity(init_amount_0, init_amount_1);
+    require!(liquidity > LOCK_LP_AMOUNT, ErrorCode::InvalidInput);
+
+    for (from, to, amount) in [
+        (
+            &ctx.accounts.creator_token_0,
+            &ctx.accounts.token_0_vault,
+            init_amount_0,
+        ),
+        (
+            &ctx.accounts.creator_token_1,
+            &ctx.accounts.token_1_vault,
+            init_amount_1,
+        ),
+    ] {
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: from.to_account_info(),
+                    to: to.to_account_info(),
+                    authority: ctx.accounts.creator.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+    }
+
+    let auth_bump = ctx.bumps.authority;
+    token::mint_to(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            MintTo {
+                mint: ctx.accounts.lp_mint.to_account_info(),
+                to: ctx.accounts.creator_lp_token.to_account_info(),
+                authority: ctx.accounts.authority.to_account_info(),
+            },
+            &[&[AUTH_SEED, &[auth_bump]]],
+        ),
+        liquidity - LOCK_LP_AMOUNT,
+    )?;
+
+    let pool_state = &mut ctx.accounts.pool_state;
+    pool_state.amm_config = ctx.accounts.amm_config.key();
+    pool_state.pool_creator = ctx.accounts.creator.key();
+    pool_state.token_0_vault = ctx.accounts.token_0_vault.key();
+    pool_state.token_1_vault = ctx.accounts.token_1_vault.key();
+    pool_state.lp_mint = ctx.accounts.lp_mint.key();
+    pool_state.token_0_mint = ctx.accounts.token_0_mint.key();
+    pool_state.token_1_mint = ctx.accounts.token_1_mint.key();
+    pool_state.token_0_program = ctx.accounts.token_0_program.key();
+    pool_state.token_1_program = ctx.accounts.token_1_program.key();
+    pool_state.observation_key = ctx.accounts.observation_state.key();
+    pool_state.auth_bump = auth_bump;
+    pool_state.lp_mint_decimals = ctx.accounts.lp_mint.decimals;
+    pool_state.mint_0_decimals = ctx.accounts.token_0_mint.decimals;
+    pool_state.mint_1_decimals = ctx.accounts.token_1_mint.decimals;
+    pool_state.lp_supply = liquidity;
+    pool_state.open_time = open_time;
+    pool_state.recent_epoch = Clock::get()?.epoch;
+    pool_state.authority_type = authority_type;
+    pool_state.custom_authority = custom_authority;
+
+    Ok(())
+}
@@ -0,0 +1,11 @@
+pub mod create_amm_config;
+pub mod deposit;
+pub mod initialize;
+pub mod swap;
+pub mod withdraw;
+
+pub use create_amm_config::*;
+pub use deposit::*;
+pub use initialize::*;
+pub use swap::*;
+pub use withdraw::*;
@@ -0,0 +1,148 @@
+use crate::curve;
+use crate::errors::ErrorCode;
+use crate::state::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+
+/// Accounts of both swap directions. The user's token accounts come right after
+/// the signer, which is where Continuum places them when it forwards a swap.
+#[derive(Accounts)]
+pub struct Swap<'info> {
+    /// Trades the input tokens, as owner or delegate of `input_token_account`,
+    /// and must be the custom authority of custom-authority pools
+    pub payer: Signer<'info>,
+
+    #[account(mut)]
+    pub input_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub output_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: Vault and LP mint authority
+    #[account(seeds = [AUTH_SEED], bump = pool_state.auth_bump)]
+    pub authority: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub pool_state: Box<Account<'info, PoolState>>,
+
+    #[account(
+        mut,
+        constraint = input_vault.key() == pool_state.token_0_vault
+            || input_vault.key() == pool_state.token_1_vault @ ErrorCode::InvalidVault,
+    )]
+    pub input_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = output_vault.key() == pool_state.token_0_vault
+            || output_vault.key() == pool_state.token_1_vault @ ErrorCode::InvalidVault,
+        constraint = output_vault.key() != input_vault.key() @ ErrorCode::InvalidVault,
+    )]
+    pub output_vault: Box<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+
+    #[account(address = input_vault.mint)]
+    pub input_token_mint: Box<Account<'info, Mint>>,
+
+    #[account(address = output_vault.mint)]
+    pub output_token_mint: Box<Account<'info, Mint>>,
+
+    #[account(address = pool_state.amm_config @ ErrorCode::InvalidInput)]
+    pub amm_config: Box<Account<'info, AmmConfig>>,
+}
+
+pub fn swap_base_input(ctx: Context<Swap>, amount_in: u64, minimum_amount_out: u64) -> Result<()> {
+    check_tradable(&ctx.accounts.pool_state, &ctx.accounts.payer.key())?;
+    require!(amount_in > 0, ErrorCode::InvalidInput);
+
+    let (input_reserve, output_reserve) = reserves(&ctx)?;
+    let amount_out = curve::swap_base_input(
+        amount_in,
+        input_reserve,
+        output_reserve,
+        ctx.accounts.amm_config.trade_fee_rate,
+    )?;
+    require!(amount_out > 0, ErrorCode::ZeroTradingTokens);
+    require!(
+        amount_out >= minimum_amount_out,
+        ErrorCode::ExceededSlippage
+    );
+
+    settle(ctx, amount_in, amount_out)
+}
+
+pub fn swap_base_output(ctx: Context<Swap>, max_amount_in: u64, amount_out: u64) -> Result<()> {
+    check_tradable(&ctx.accounts.pool_state, &ctx.accounts.payer.key())?;
+    require!(amount_out > 0, ErrorCode::InvalidInput);
+
+    let (input_reserve, output_reserve) = reserves(&ctx)?;
+    let amount_in = curve::swap_base_output(
+        amount_out,
+        input_reserve,
+        output_reserve,
+        ctx.accounts.amm_config.trade_fee_rate,
+    )?;
+    require!(amount_in <= max_amount_in, ErrorCode::ExceededSlippage);
+
+    settle(ctx, amount_in, amount_out)
+}
+
+fn check_tradable(pool_state: &PoolState, payer: &Pubkey) -> Result<()> {
+    require!(pool_state.is_authorized(payer), ErrorCode::NotApproved);
+    require!(
+        Clock::get()?.unix_timestamp as u64 > pool_state.open_time,
+        ErrorCode::NotApproved
+    );
+    Ok(())
+}
+
+/// Input and output reserves, without the fees accrued in the vaults
+fn reserves(ctx: &Context<Swap>) -> Result<(u64, u64)> {
+    let accounts = &ctx.accounts;
+    Ok((
+        accounts
+            .pool_state
+            .reserve(&accounts.input_vault.key(), accounts.input_vault.amount)?,
+        accounts
+            .pool_state
+            .reserve(&accounts.output_vault.key(), accounts.output_vault.amount)?,
+    ))
+}
+
+fn settle(ctx: Context<Swap>, amount_in: u64, amount_out: u64) -> Result<()> {
+    let accounts = ctx.accounts;
+
+    // The protocol and fund shares of the fee stay in the input vault, set aside
+    let trade_fee = curve::trade_fee(amount_in, accounts.amm_config.trade_fee_rate)?;
+    accounts.pool_state.accrue_fees(
+        &accounts.input_vault.key(),
+        curve::fee_share(trade_fee, accounts.amm_config.protocol_fee_rate),
+        curve::fee_share(trade_fee, accounts.amm_config.fund_fee_rate),
+    );
+
+    token::transfer(
+        CpiContext::new(
+            accounts.token_program.to_account_info(),
+            Transfer {
+                from: accounts.input_token_account.to_account_info(),
+                to: accounts.input_vault.to_account_info(),
+                authority: accounts.payer.to_account_info(),
+            },
+        ),
+        amount_in,
+    )?;
+
+    token::transfer(
+        CpiContext::new_with_signer(
+            accounts.token_program.to_account_info(),
+            Transfer {
+                from: accounts.output_vault.to_account_info(),
+                to: accounts.output_token_account.to_account_info(),
+                authority: accounts.authority.to_account_info(),
+            },
+            &[&[AUTH_SEED, &[accounts.pool_state.auth_bump]]],
+        ),
+        amount_out,
+    )
+}
@@ -0,0 +1,128 @@
+use crate::curve;
+use crate::errors::ErrorCode;
+use crate::state::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Burn, Mint, Token, TokenAccount, Transfer};
+
+#[derive(Accounts)]
+pub struct Withdraw<'info> {
+    /// Burns the LP tokens, as owner or delegate of `owner_lp_token`, and must
+    /// be the custom authority of custom-authority pools
+    pub owner: Signer<'info>,
+
+    /// CHECK: Vault and LP mint authority
+    #[account(seeds = [AUTH_SEED], bump = pool_state.auth_bump)]
+    pub authority: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub pool_state: Box<Account<'info, PoolState>>,
+
+    #[account(mut, token::mint = lp_mint)]
+    pub owner_lp_token: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut, token::mint = vault_0_mint)]
+    pub token_0_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut, token::mint = vault_1_mint)]
+    pub token_1_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut, address = pool_state.token_0_vault @ ErrorCode::InvalidVault)]
+    pub token_0_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut, address = pool_state.token_1_vault @ ErrorCode::InvalidVault)]
+    pub token_1_vault: Box<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+
+    #[account(address = pool_state.token_0_mint)]
+    pub vault_0_mint: Box<Account<'info, Mint>>,
+
+    #[account(address = pool_state.token_1_mint)]
+    pub vault_1_mint: Box<Account<'info, Mint>>,
+
+    #[account(mut, address = pool_state.lp_mint)]
+    pub lp_mint: Box<Account<'info, Mint>>,
+}
+
+pub fn withdraw(
+    ctx: Context<Withdraw>,
+    lp_token_amount: u64,
+    minimum_token_0_amount: u64,
+    minimum_token_1_amount: u64,
+) -> Result<()> {
+    let pool_state = &ctx.accounts.pool_state;
+    require!(
+        pool_state.is_authorized(&ctx.accounts.owner.key()),
+        ErrorCode::NotApproved
+    );
+    require!(
+        lp_token_amount > 0 && lp_token_amount < pool_state.lp_supply,
+        ErrorCode::InvalidInput
+    );
+
+    let (token_0_amount, token_1_amount) = curve::lp_tokens_to_trading_tokens(
+        lp_token_amount,
+        pool_state.lp_supply,
+        pool_state.reserve(
+            &ctx.accounts.token_0_vault.key(),
+            ctx.accounts.token_0_vault.amount,
+        )?,
+        pool_state.reserve(
+            &ctx.accounts.token_1_vault.key(),
+            ctx.accounts.token_1_vault.amount,
+        )?,
+        false,
+    )?;
+    require!(
+        token_0_amount > 0 || token_1_amount > 0,
+        ErrorCode::ZeroTradingTokens
+    );
+    require!(
+        token_0_amount >= minimum_token_0_amount && token_1_amount >= minimum_token_1_amount,
+        ErrorCode::ExceededSlippage
+    );
+
+    token::burn(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Burn {
+                mint: ctx.accounts.lp_mint.to_account_info(),
+                from: ctx.accounts.owner_lp_token.to_account_info(),
+                authority: ctx.accounts.owner.to_account_info(),
+            },
+        ),
+        lp_token_amount,
+    )?;
+
+    let auth_bump = pool_state.auth_bump;
+    for (from, to, amount) in [
+        (
+            &ctx.accounts.token_0_vault,
+            &ctx.accounts.token_0_account,
+            token_0_amount,
+        ),
+        (
+            &ctx.accounts.token_1_vault,
+            &ctx.accounts.token_1_account,
+            token_1_amount,
+        ),
+    ] {
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: from.to_account_info(),
+                    to: to.to_account_info(),
+                    authority: ctx.accounts.authority.to_account_info(),
+                },
+                &[&[AUTH_SEED, &[auth_bump]]],
+            ),
+            amount,
+        )?;
+    }
+
+    let pool_state = &mut ctx.accounts.pool_state;
+    pool_state.lp_supply -= lp_token_amount;
+
+    Ok(())
+}
@@ -0,0 +1,100 @@
+//! Constant-product stand-in for the Raydium CP-Swap fork Continuum drives.
+//!
+//! It is deployed at the CP-Swap program id and keeps the instruction
+//! discriminators, the `PoolState` byte layout and the custom-authority rules
+//! Continuum relies on, along with the `AmmConfig` fee rates and the protocol
+//! and fund fee accrual, but nothing else: there are no creator fees, no
+//! observations and no Token-2022 support.
+use anchor_lang::prelude::*;
+
+declare_id!("GkenxCtvEabZrwFf15D3E6LjoZTywH2afNwiqDwthyDp");
+
+pub mod curve;
+pub mod errors;
+pub mod instructions;
+pub mod state;
+
+use instructions::*;
+
+#[program]
+pub mod mock_cp_swap {
+    use super::*;
+
+    /// Create a fee tier pools can be opened against
+    pub fn create_amm_config(
+        ctx: Context<CreateAmmConfig>,
+        index: u16,
+        trade_fee_rate: u64,
+        protocol_fee_rate: u64,
+        fund_fee_rate: u64,
+        create_pool_fee: u64,
+    ) -> Result<()> {
+        instructions::create_amm_config(
+            ctx,
+            index,
+            trade_fee_rate,
+            protocol_fee_rate,
+            fund_fee_rate,
+            create_pool_fee,
+        )
+    }
+
+    /// Create a pool, seed it with liquidity and mint the creator's LP tokens
+    pub fn initialize(
+        ctx: Context<Initialize>,
+        init_amount_0: u64,
+        init_amount_1: u64,
+        open_time: u64,
+        authority_type: u8,
+        custom_authority: Option<Pubkey>,
+    ) -> Result<()> {
+        instructions::initialize(
+            ctx,
+            init_amount_0,
+            init_amount_1,
+            open_time,
+            authority_type,
+            custom_authority,
+        )
+    }
+
+    /// Swap an exact input amount
+    pub fn swap_base_input(ctx: Context<Swap>, amount_in: u64, minimum_amount_out: u64) -> Result<()> {
+        instructions::swap_base_input(ctx, amount_in, minimum_amount_out)
+    }
+
+    /// Swap for an exact output amount
+    pub fn swap_base_output(ctx: Context<Swap>, max_amount_in: u64, amount_out: u64) -> Result<()> {
+        instructions::swap_base_output(ctx, max_amount_in, amount_out)
+    }
+
+    /// Deposit both tokens in exchange for `lp_token_amount` LP tokens
+    pub fn deposit(
+        ctx: Context<Deposit>,
+        lp_token_amount: u64,
+        maximum_token_0_amount: u64,
+        maximum_token_1_amount: u64,
+    ) -> Result<()> {
+        instructions::deposit(
+            ctx,
+            lp_token_amount,
+            maximum_token_0_amount,
+            maximum_token_1_amount,
+        )
+    }
+
+    /// Burn `lp_token_amount` LP tokens for both tokens
+    pub fn withdraw(
+        ctx: Context<Withdraw>,
+        lp_token_amount: u64,
+        minimum_token_0_amount: u64,
+        minimum_token_1_amount: u64,
+    ) -> Result<()> {
+        instructions::withdraw(
+            ctx,
+            lp_token_amount,
+            minimum_token_0_amount,
+            minimum_token_1_amount,
+        )
+    }
+}
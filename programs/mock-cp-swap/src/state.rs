@@ -0,0 +1,100 @@
+use crate::errors::ErrorCode;
+use anchor_lang::prelude::*;
+
+pub const AMM_CONFIG_SEED: &[u8] = b"amm_config";
+pub const AUTH_SEED: &[u8] = b"vault_and_lp_mint_auth_seed";
+pub const POOL_SEED: &[u8] = b"pool";
+pub const POOL_LP_MINT_SEED: &[u8] = b"pool_lp_mint";
+pub const POOL_VAULT_SEED: &[u8] = b"pool_vault";
+
+/// `authority_type` of pools anyone can trade against
+pub const AUTHORITY_TYPE_DEFAULT: u8 = 0;
+/// `authority_type` of pools that only `custom_authority` can trade against
+pub const AUTHORITY_TYPE_CUSTOM: u8 = 1;
+
+/// LP tokens locked forever on pool creation
+pub const LOCK_LP_AMOUNT: u64 = 100;
+
+/// Field for field the CP-Swap `AmmConfig`, fee rates are in hundredths of a
+/// basis point and the protocol and fund rates are shares of the trade fee
+#[account]
+pub struct AmmConfig {
+    pub bump: u8,
+    pub disable_create_pool: bool,
+    pub index: u16,
+    pub trade_fee_rate: u64,
+    pub protocol_fee_rate: u64,
+    pub fund_fee_rate: u64,
+    pub create_pool_fee: u64,
+    pub protocol_owner: Pubkey,
+    pub fund_owner: Pubkey,
+    pub padding: [u64; 16],
+}
+
+impl AmmConfig {
+    pub const LEN: usize = 8 + 1 + 1 + 2 + 8 * 4 + 32 * 2 + 8 * 16;
+}
+
+/// Field for field the CP-Swap `PoolState`. Borsh packs it exactly like the
+/// original zero-copy struct, so Continuum's raw offsets line up.
+#[account]
+pub struct PoolState {
+    pub amm_config: Pubkey,
+    pub pool_creator: Pubkey,
+    pub token_0_vault: Pubkey,
+    pub token_1_vault: Pubkey,
+    pub lp_mint: Pubkey,
+    pub token_0_mint: Pubkey,
+    pub token_1_mint: Pubkey,
+    pub token_0_program: Pubkey,
+    pub token_1_program: Pubkey,
+    pub observation_key: Pubkey,
+    pub auth_bump: u8,
+    pub status: u8,
+    pub lp_mint_decimals: u8,
+    pub mint_0_decimals: u8,
+    pub mint_1_decimals: u8,
+    pub lp_supply: u64,
+    pub protocol_fees_token_0: u64,
+    pub protocol_fees_token_1: u64,
+    pub fund_fees_token_0: u64,
+    pub fund_fees_token_1: u64,
+    pub open_time: u64,
+    pub recent_epoch: u64,
+    pub authority_type: u8,
+    pub custom_authority: Pubkey,
+    pub padding: [u64; 31],
+}
+
+impl PoolState {
+    pub const LEN: usize = 8 + 32 * 10 + 5 + 8 * 7 + 1 + 32 + 8 * 31;
+
+    /// Balance of `vault` less the protocol and fund fees accrued in it, which
+    /// belong to their owners rather than to liquidity providers
+    pub fn reserve(&self, vault: &Pubkey, amount: u64) -> Result<u64> {
+        let accrued = if *vault == self.token_0_vault {
+            self.protocol_fees_token_0 + self.fund_fees_token_0
+        } else {
+            self.protocol_fees_token_1 + self.fund_fees_token_1
+        };
+        amount
+            .checked_sub(accrued)
+            .ok_or_else(|| error!(ErrorCode::MathOverflow))
+    }
+
+    /// Record the protocol and fund shares of a trade fee charged in `vault`
+    pub fn accrue_fees(&mut self, vault: &Pubkey, protocol_fee: u64, fund_fee: u64) {
+        if *vault == self.token_0_vault {
+            self.protocol_fees_token_0 += protocol_fee;
+            self.fund_fees_token_0 += fund_fee;
+        } else {
+            self.protocol_fees_token_1 += protocol_fee;
+            self.fund_fees_token_1 += fund_fee;
+        }
+    }
+
+    /// Whether `signer` may trade against or provide liquidity to the pool
+    pub fn is_authorized(&self, signer: &Pubkey) -> bool {
+        self.authority_type != AUTHORITY_TYPE_CUSTOM || self.custom_authority == *signer
+    }
+}